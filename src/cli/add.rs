@@ -1,25 +1,44 @@
 use std::error::Error;
+use std::io::{self, BufRead, IsTerminal, Write};
 use std::sync::Arc;
 
-use chrono::{NaiveDateTime, ParseResult};
+use chrono::{Duration, Local, NaiveDateTime, ParseResult};
 use clap::Args;
+use snafu::prelude::*;
 
-use crate::domain::entity::Priority;
+use crate::domain::entity::{Group, Item, Priority};
 use crate::domain::usecase::plan::{self, Request, Response};
+use crate::format::duration;
+use crate::format::inline::{self, Inline};
 use crate::repository::Repository;
 
 #[derive(Args)]
 pub struct AddArgs {
-    #[arg(short, long)]
-    summary: String,
+    /// Summary with inline `#tag`, `!N` or `!!!` priority and `due:` or `@` deadline
+    #[arg(conflicts_with = "summary")]
+    text: Option<String>,
+    #[arg(short, long, required_unless_present = "text")]
+    summary: Option<String>,
     #[arg(short, long, default_value_t = String::new())]
     content: String,
     #[arg(short, long, value_parser = parse_datetime)]
-    deadline: NaiveDateTime,
+    deadline: Option<NaiveDateTime>,
     #[arg(short, long = "tag")]
     tags: Vec<String>,
-    #[arg(short, long, default_value_t = 0.try_into().unwrap(), value_parser = parse_priority)]
-    priority: Priority,
+    #[arg(short, long, allow_negative_numbers = true, value_parser = parse_priority)]
+    priority: Option<Priority>,
+    /// Expected effort, such as `2h30m`
+    #[arg(short, long, value_parser = duration::parse)]
+    estimate: Option<Duration>,
+    /// Save quick-added items without asking for confirmation
+    #[arg(short, long, default_value_t = false)]
+    yes: bool,
+}
+
+#[derive(Debug, Snafu)]
+enum AddError {
+    #[snafu(display("A deadline is required, use `due:` in the text or `--deadline`"))]
+    MissingDeadline,
 }
 
 fn parse_datetime(value: &str) -> ParseResult<NaiveDateTime> {
@@ -35,24 +54,96 @@ fn parse_priority(value: &str) -> Result<Priority, String> {
 }
 
 pub fn run(repo: Arc<Repository>, args: AddArgs) -> Result<(), Box<dyn Error>> {
+    let quick = args.text.is_some();
+
+    let parsed = match args.text {
+        Some(text) => inline::parse(&text, Local::now().naive_local()),
+        None => Ok(Inline {
+            summary: args.summary.unwrap_or_default(),
+            ..Default::default()
+        }),
+    };
+
+    let Inline {
+        summary,
+        mut tags,
+        priority,
+        deadline,
+    } = match parsed {
+        Ok(inline) => inline,
+        Err(err) => return Err(Box::new(err)),
+    };
+
+    let deadline = match args.deadline.or(deadline) {
+        Some(deadline) => deadline,
+        None => return Err(Box::new(AddError::MissingDeadline)),
+    };
+
+    tags.extend(args.tags);
+
     let request = Request {
-        summary: args.summary,
+        summary,
         content: args.content,
-        deadline: args.deadline,
-        tags: args.tags.into_iter().collect(),
-        priority: args.priority.value(),
+        deadline,
+        tags,
+        priority: args
+            .priority
+            .map(|priority| priority.value())
+            .or(priority)
+            .unwrap_or_default(),
+        estimate: args.estimate.map(|estimate| estimate.num_seconds()),
     };
 
-    let response = repo.apply_planned_ids(|planned, ids| plan::execute(planned, ids, request));
+    if quick {
+        match plan::preview(request.clone()) {
+            Ok(item) => show(&item),
+            Err(err) => return Err(Box::new(err)),
+        }
+
+        if !args.yes && io::stdin().is_terminal() && !confirm()? {
+            println!("Discarded");
+            return Ok(());
+        }
+    }
+
+    let response = repo.transaction([Group::Planned.name()], |[planned], ids| {
+        plan::execute(planned, ids, request)
+    })?;
 
     match response {
         Ok(Response { id }) => {
             println!("New item: {id}");
             Ok(())
         }
-        Err(err) => {
-            eprintln!("{err}");
-            Err(Box::new(err))
-        }
+        Err(err) => Err(Box::new(err)),
+    }
+}
+
+fn show(item: &Item) {
+    let mut tags = item
+        .tags()
+        .iter()
+        .map(|tag| format!("#{tag}"))
+        .collect::<Vec<_>>();
+    tags.sort();
+
+    println!("Summary:  {}", item.summary());
+    println!("Deadline: {}", item.deadline());
+    println!("Tags:     {}", tags.join(" "));
+    println!("Priority: {}", item.priority());
+
+    if let Some(estimate) = item.estimate() {
+        println!("Estimate: {}", duration::render(estimate));
     }
 }
+
+fn confirm() -> io::Result<bool> {
+    print!("Save? [Y/n] ");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    let answer = answer.trim().to_lowercase();
+
+    Ok(answer.is_empty() || answer == "y" || answer == "yes")
+}
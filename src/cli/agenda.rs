@@ -0,0 +1,77 @@
+use std::error::Error;
+use std::sync::Arc;
+
+use chrono::{Days, Local, NaiveDate};
+use clap::Args;
+
+use crate::domain::entity::Item;
+use crate::domain::usecase::agenda::{self, Bucket, Request, Response};
+use crate::format::sorted_tags;
+use crate::repository::Repository;
+
+#[derive(Args)]
+pub struct AgendaArgs {
+    #[arg(short, long, default_value_t = 7)]
+    days: u64,
+    #[arg(short, long = "tag")]
+    tags: Vec<String>,
+}
+
+pub fn run(repo: Arc<Repository>, args: AgendaArgs) -> Result<(), Box<dyn Error>> {
+    let now = Local::now().naive_local();
+
+    let request = Request {
+        now,
+        days: args.days,
+        tags: args.tags.into_iter().collect(),
+    };
+
+    let Response { buckets } = repo.read_open(|open, _| agenda::execute(&open, request));
+
+    if buckets.is_empty() {
+        println!("Nothing is due in {} days", args.days);
+        return Ok(());
+    }
+
+    for (index, (bucket, items)) in buckets.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+
+        println!("{}", label(*bucket, now.date()));
+
+        for item in items {
+            println!("{}", line(item, *bucket));
+        }
+    }
+
+    Ok(())
+}
+
+fn label(bucket: Bucket, today: NaiveDate) -> String {
+    match bucket {
+        Bucket::Overdue => "Overdue".to_owned(),
+        Bucket::Day(date) if date == today => format!("Today, {}", date.format("%a %Y-%m-%d")),
+        Bucket::Day(date) if Some(date) == today.checked_add_days(Days::new(1)) => {
+            format!("Tomorrow, {}", date.format("%a %Y-%m-%d"))
+        }
+        Bucket::Day(date) => date.format("%A, %Y-%m-%d").to_string(),
+    }
+}
+
+/// Overdue items show their whole deadline, others only the time of day.
+fn line(item: &Item, bucket: Bucket) -> String {
+    let deadline = match bucket {
+        Bucket::Overdue => item.deadline().format("%Y-%m-%d %H:%M"),
+        Bucket::Day(_) => item.deadline().format("%H:%M"),
+    };
+
+    let mut line = format!("  {deadline}  {}", item.summary());
+
+    for tag in sorted_tags(item) {
+        line.push_str(" #");
+        line.push_str(tag);
+    }
+
+    format!("{line}  [{}]", item.id())
+}
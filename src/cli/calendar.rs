@@ -0,0 +1,88 @@
+use std::error::Error;
+use std::sync::Arc;
+
+use chrono::{Datelike, Local, NaiveDate, ParseResult};
+use clap::Args;
+
+use crate::domain::usecase::calendar::{self, Request, Response};
+use crate::repository::Repository;
+
+const WIDTH: usize = 6;
+
+#[derive(Args)]
+pub struct CalendarArgs {
+    #[arg(short, long, value_parser = parse_month)]
+    month: Option<NaiveDate>,
+    #[arg(short, long = "tag")]
+    tags: Vec<String>,
+}
+
+fn parse_month(value: &str) -> ParseResult<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{value}-01"), "%Y-%m-%d")
+}
+
+pub fn run(repo: Arc<Repository>, args: CalendarArgs) -> Result<(), Box<dyn Error>> {
+    let today = Local::now().date_naive();
+
+    let request = Request {
+        month: args.month.unwrap_or(today),
+        tags: args.tags.into_iter().collect(),
+    };
+
+    let Response { first, last, days } =
+        repo.read_open(|open, _| calendar::execute(&open, request));
+
+    let title = first.format("%B %Y").to_string();
+    println!(
+        "{}",
+        format!("{title:^width$}", width = WIDTH * 7).trim_end()
+    );
+    println!(
+        "{}",
+        ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"]
+            .map(|day| format!("{day:<WIDTH$}"))
+            .concat()
+            .trim_end()
+    );
+
+    let offset = first.weekday().num_days_from_monday() as usize;
+    let mut week = " ".repeat(WIDTH * offset);
+
+    for date in first.iter_days().take_while(|date| *date <= last) {
+        week.push_str(&cell(date, days.get(&date).map_or(0, Vec::len), today));
+
+        if date.weekday().num_days_from_monday() == 6 || date == last {
+            println!("{}", week.trim_end());
+            week.clear();
+        }
+    }
+
+    if !days.is_empty() {
+        println!();
+    }
+
+    for (date, items) in days {
+        let summaries = items
+            .iter()
+            .map(|item| item.summary())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        println!("{:>2}  {summaries}", date.day());
+    }
+
+    Ok(())
+}
+
+/// Days with deadlines are followed by the number of items due, and today is marked
+/// with `*` if nothing is due.
+fn cell(date: NaiveDate, count: usize, today: NaiveDate) -> String {
+    let marker = match count {
+        0 if date == today => "*".to_owned(),
+        0 => String::new(),
+        1..=9 => format!("({count})"),
+        _ => "(+)".to_owned(),
+    };
+
+    format!("{:>2}{marker:<width$}", date.day(), width = WIDTH - 2)
+}
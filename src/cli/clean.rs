@@ -10,16 +10,19 @@ use crate::repository::Repository;
 struct NoError;
 
 pub fn run(repo: Arc<Repository>) -> Result<(), Box<dyn Error>> {
-    repo.apply_finished(|finished| -> Result<(), NoError> {
-        clean::execute(finished);
-        Ok(())
-    })?;
+    let workflow = repo.workflow();
+    let states = workflow
+        .names()
+        .filter(|name| workflow.is_final(name))
+        .collect::<Vec<_>>();
 
-    repo.apply_canceled(|canceled| -> Result<(), NoError> {
-        clean::execute(canceled);
-        Ok(())
-    })?;
+    for &name in &states {
+        repo.transaction([name], |[pool], _| -> Result<(), NoError> {
+            clean::execute(pool);
+            Ok(())
+        })??;
+    }
 
-    println!("Clean records of finished & canceled items");
+    println!("Clean records of {} items", states.join(" & "));
     Ok(())
 }
@@ -0,0 +1,322 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::sync::Arc;
+
+use clap::{Args, Command as ClapCommand, CommandFactory};
+
+use crate::domain::entity::{Group, Item, TagSet};
+use crate::domain::usecase::select::{self, Request, Response};
+use crate::repository::item::Pool;
+use crate::repository::workspace::Workspaces;
+use crate::repository::Repository;
+
+use super::{Arg, Context};
+
+#[derive(Args)]
+pub struct CompleteArgs {
+    /// The words after the binary name, the last one being the word under the cursor.
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    words: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub value: String,
+    pub description: Option<String>,
+}
+
+/// Prints one candidate per line, with its description after a tab if there's one.
+pub fn run(
+    repo: Arc<Repository>,
+    context: &Context,
+    args: CompleteArgs,
+) -> Result<(), Box<dyn Error>> {
+    let mut root = Arg::command();
+    root.build();
+
+    let mut words = args.words;
+    let word = words.pop().unwrap_or_default();
+    let words = words.iter().map(String::as_str).collect::<Vec<_>>();
+
+    for Candidate { value, description } in
+        candidates(&repo, &context.workspaces, &root, &words, &word)
+    {
+        match description {
+            Some(description) => println!("{value}\t{description}"),
+            None => println!("{value}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Completes `word` following `words`, which are the preceding words of the line
+/// without the binary name, against the command tree of `root`.
+pub fn candidates(
+    repo: &Repository,
+    workspaces: &Workspaces,
+    root: &ClapCommand,
+    words: &[&str],
+    word: &str,
+) -> Vec<Candidate> {
+    let mut command = root;
+    let mut iter = words.iter();
+
+    while let Some(&current) = iter.next() {
+        if let Some(subcommand) = command.find_subcommand(current) {
+            command = subcommand;
+        } else if find_option(command, current).is_some() {
+            iter.next();
+        }
+    }
+
+    if let Some(option) = words.last().and_then(|&last| find_option(command, last)) {
+        return match option.get_long() {
+            Some("id") => ids(repo, word),
+            Some("tag" | "with-tag") => filter(tags(repo), word),
+            Some("to" | "workspace") => filter(names(workspaces), word),
+            Some("group" | "state") => filter(states(repo), word),
+            _ => filter(
+                option
+                    .get_possible_values()
+                    .iter()
+                    .filter(|value| !value.is_hide_set())
+                    .map(|value| Candidate {
+                        value: value.get_name().to_owned(),
+                        description: value.get_help().map(ToString::to_string),
+                    })
+                    .collect(),
+                word,
+            ),
+        };
+    }
+
+    if word.starts_with('-') {
+        let options = command
+            .get_arguments()
+            .filter(|arg| !arg.is_hide_set())
+            .filter_map(|arg| {
+                arg.get_long().map(|long| Candidate {
+                    value: format!("--{long}"),
+                    description: arg.get_help().map(ToString::to_string),
+                })
+            })
+            .collect();
+
+        filter(options, word)
+    } else {
+        let subcommands = command
+            .get_subcommands()
+            .filter(|subcommand| !subcommand.is_hide_set())
+            .map(|subcommand| Candidate {
+                value: subcommand.get_name().to_owned(),
+                description: subcommand.get_about().map(ToString::to_string),
+            })
+            .collect();
+
+        filter(subcommands, word)
+    }
+}
+
+fn find_option<'a>(command: &'a ClapCommand, word: &str) -> Option<&'a clap::Arg> {
+    command
+        .get_arguments()
+        .filter(|arg| arg.get_action().takes_values())
+        .find(|arg| {
+            if let Some(long) = word.strip_prefix("--") {
+                arg.get_long() == Some(long)
+            } else if let Some(short) = word.strip_prefix('-') {
+                short.len() == 1 && arg.get_short().map(String::from).as_deref() == Some(short)
+            } else {
+                false
+            }
+        })
+}
+
+fn filter(candidates: Vec<Candidate>, word: &str) -> Vec<Candidate> {
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.value.starts_with(word))
+        .collect()
+}
+
+/// Only IDs of planned items can be targeted, so they're completed from the ID pool, or
+/// all listed when nothing is typed yet.
+fn ids(repo: &Repository, prefix: &str) -> Vec<Candidate> {
+    let ids = match prefix.parse::<u64>() {
+        Ok(pattern) => repo
+            .read_groups([], |[], ids| ids.find(pattern))
+            .unwrap_or_default(),
+        Err(_) if prefix.is_empty() => repo
+            .read_groups([Group::Planned], |[planned], _| {
+                select::execute(planned, all())
+            })
+            .map(|Response { items }| items.iter().map(Item::id).collect())
+            .unwrap_or_default(),
+        Err(_) => return Vec::new(),
+    };
+
+    repo.read_groups([Group::Planned], |[planned], _| {
+        ids.into_iter()
+            .map(|id| Candidate {
+                value: id.to_string(),
+                description: planned.get(id).ok().map(|item| item.summary().to_owned()),
+            })
+            .collect()
+    })
+}
+
+fn tags(repo: &Repository) -> Vec<Candidate> {
+    let func = |pool: &dyn Pool| match select::execute(pool, all()) {
+        Ok(Response { items }) => items
+            .into_iter()
+            .flat_map(|item| item.tags().clone())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    let tags = repo.read_all(|pools, _| {
+        pools
+            .into_iter()
+            .flat_map(|(_, pool)| func(pool))
+            .collect::<BTreeSet<_>>()
+    });
+
+    tags.into_iter()
+        .map(|value| Candidate {
+            value,
+            description: None,
+        })
+        .collect()
+}
+
+fn states(repo: &Repository) -> Vec<Candidate> {
+    repo.workflow()
+        .names()
+        .map(|name| Candidate {
+            value: name.to_owned(),
+            description: None,
+        })
+        .collect()
+}
+
+fn names(workspaces: &Workspaces) -> Vec<Candidate> {
+    workspaces
+        .list()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|value| Candidate {
+            value,
+            description: None,
+        })
+        .collect()
+}
+
+fn all() -> Request {
+    Request {
+        tags: TagSet::new(),
+        before: None,
+        after: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use chrono::NaiveDateTime;
+
+    use crate::domain::entity::Workflow;
+    use crate::repository::id::TriePool;
+    use crate::repository::item::MemoryPool;
+    use crate::repository::Data;
+
+    use super::*;
+
+    fn repository(items: Vec<Item>) -> Repository {
+        let data = Data::new(Workflow::default(), Box::new(TriePool::new()), |_| {
+            Ok::<_, Infallible>(Box::new(MemoryPool::new()) as Box<dyn Pool>)
+        });
+        let repo = Repository::new(data.unwrap());
+
+        let added = repo.transaction([Group::Planned.name()], |[planned], ids| {
+            items.into_iter().try_for_each(|item| {
+                ids.add(planned.add(item)?);
+                Ok::<_, crate::repository::item::AddError>(())
+            })
+        });
+        assert!(matches!(added, Ok(Ok(()))));
+        repo
+    }
+
+    fn item(summary: &str, tags: &[&str]) -> Item {
+        let deadline =
+            NaiveDateTime::parse_from_str("2023-06-17 23:20:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let tags = tags.iter().map(|&tag| tag.to_owned()).collect::<TagSet>();
+        Item::new(summary, "", deadline, tags, 0.try_into().unwrap())
+    }
+
+    fn complete(repo: &Repository, words: &[&str], word: &str) -> Vec<Candidate> {
+        let workspaces = Workspaces::new(std::env::temp_dir().join("todo-complete-none"));
+        let mut root = Arg::command();
+        root.build();
+
+        candidates(repo, &workspaces, &root, words, word)
+    }
+
+    fn values(candidates: Vec<Candidate>) -> Vec<String> {
+        candidates
+            .into_iter()
+            .map(|candidate| candidate.value)
+            .collect()
+    }
+
+    #[test]
+    fn it_should_complete_ids_from_a_prefix_with_their_summaries() {
+        let first = item("First", &[]);
+        let id = first.id();
+        let repo = repository(vec![first, item("Second", &[])]);
+
+        let prefix = &id.to_string()[..3];
+        let candidates = complete(&repo, &["finish", "--id"], prefix);
+
+        assert!(candidates.contains(&Candidate {
+            value: id.to_string(),
+            description: Some("First".to_owned()),
+        }));
+        assert!(candidates
+            .iter()
+            .all(|candidate| candidate.value.starts_with(prefix)));
+        assert!(complete(&repo, &["finish", "--id"], "x").is_empty());
+    }
+
+    #[test]
+    fn it_should_complete_tags_of_all_items() {
+        let repo = repository(vec![item("First", &["work"]), item("Second", &["home"])]);
+
+        assert_eq!(
+            values(complete(&repo, &["add-tag", "--tag"], "wo")),
+            ["work"]
+        );
+        assert_eq!(
+            values(complete(&repo, &["finish", "--with-tag"], "")),
+            ["home", "work"]
+        );
+    }
+
+    #[test]
+    fn it_should_complete_subcommands_and_options_by_position() {
+        let repo = repository(Vec::new());
+
+        assert_eq!(
+            values(complete(&repo, &[], "se")),
+            ["set-priority", "set-state", "serve"]
+        );
+        // Hidden subcommands aren't offered.
+        assert!(complete(&repo, &[], "__").is_empty());
+        assert_eq!(
+            values(complete(&repo, &["--storage", "tmp", "set-state"], "--st")),
+            ["--state"]
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use std::error::Error;
+
+use clap::{Args, ValueEnum};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+#[derive(Args)]
+pub struct CompletionsArgs {
+    #[arg(value_enum)]
+    shell: Shell,
+}
+
+const BASH: &str = r#"_todo() {
+    local storage=() workspace=() keyfile=() i
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case ${COMP_WORDS[i]} in
+            --storage) storage=(--storage "${COMP_WORDS[i + 1]}") ;;
+            --workspace) workspace=(--workspace "${COMP_WORDS[i + 1]}") ;;
+            --keyfile) keyfile=(--keyfile "${COMP_WORDS[i + 1]}") ;;
+        esac
+    done
+
+    local IFS=$'\n'
+    local lines=($("${COMP_WORDS[0]}" "${storage[@]}" "${workspace[@]}" "${keyfile[@]}" __complete "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null))
+
+    if ((${#lines[@]} == 1)); then
+        COMPREPLY=("${lines[0]%%$'\t'*}")
+    else
+        local line
+        COMPREPLY=()
+        for line in "${lines[@]}"; do
+            if [[ $line == *$'\t'* ]]; then
+                COMPREPLY+=("${line%%$'\t'*}  (${line#*$'\t'})")
+            else
+                COMPREPLY+=("$line")
+            fi
+        done
+    fi
+}
+
+complete -o default -F _todo todo
+"#;
+
+const ZSH: &str = r#"#compdef todo
+
+_todo() {
+    local -a storage workspace keyfile lines candidates
+    local i line
+    for ((i = 2; i < CURRENT; i++)); do
+        case ${words[i]} in
+            (--storage) storage=(--storage "${words[i + 1]}") ;;
+            (--workspace) workspace=(--workspace "${words[i + 1]}") ;;
+            (--keyfile) keyfile=(--keyfile "${words[i + 1]}") ;;
+        esac
+    done
+
+    lines=("${(@f)$("${words[1]}" "${storage[@]}" "${workspace[@]}" "${keyfile[@]}" __complete "${(@)words[2,CURRENT]}" 2>/dev/null)}")
+    for line in "${lines[@]}"; do
+        [[ -z $line ]] && continue
+        if [[ $line == *$'\t'* ]]; then
+            candidates+=("${${line%%$'\t'*}//:/\\:}:${line#*$'\t'}")
+        else
+            candidates+=("${line//:/\\:}")
+        fi
+    done
+
+    if ((${#candidates[@]})); then
+        _describe 'values' candidates
+    else
+        _files
+    fi
+}
+
+if [[ $zsh_eval_context[-1] == loadautofunc ]]; then
+    _todo "$@"
+else
+    compdef _todo todo
+fi
+"#;
+
+const FISH: &str = r#"function __todo_complete
+    set -l words (commandline -opc)
+    set -l storage
+    set -l workspace
+    set -l keyfile
+    for i in (seq 2 (count $words))
+        switch $words[$i]
+            case --storage
+                set storage --storage $words[(math $i + 1)]
+            case --workspace
+                set workspace --workspace $words[(math $i + 1)]
+            case --keyfile
+                set keyfile --keyfile $words[(math $i + 1)]
+        end
+    end
+    $words[1] $storage $workspace $keyfile __complete $words[2..] (commandline -ct) 2>/dev/null
+end
+
+complete -c todo -f -a '(__todo_complete)'
+"#;
+
+/// Prints a completion script. Candidates are asked from `todo __complete`, which gets the words of the line with the
+/// word under the cursor last, and prints a candidate with an optional tab-separated
+/// description per line. The `--storage`, `--workspace` and `--keyfile` options are
+/// passed along so that the right items are completed.
+pub fn run(args: CompletionsArgs) -> Result<(), Box<dyn Error>> {
+    let script = match args.shell {
+        Shell::Bash => BASH,
+        Shell::Zsh => ZSH,
+        Shell::Fish => FISH,
+    };
+
+    print!("{script}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_pass_along_the_options_choosing_the_workspace() {
+        for script in [BASH, ZSH, FISH] {
+            let call = script.lines().find(|line| line.contains("__complete"));
+
+            for name in ["storage", "workspace", "keyfile"] {
+                assert!(script.contains(&format!("--{name}")));
+                assert!(call.is_some_and(|call| call.contains(&format!("${name}"))
+                    || call.contains(&format!("${{{name}[@]}}"))));
+            }
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::error::Error;
+use std::path::Path;
+
+use clap::Args;
+use snafu::prelude::*;
+
+use crate::domain::entity::Workflow;
+use crate::repository::item::doctor::{Examination, QUARANTINE};
+
+use super::storage;
+
+#[derive(Args)]
+pub struct DoctorArgs {
+    /// Clamp out of range priorities and move offending items to the quarantine file.
+    #[arg(long, default_value_t = false)]
+    fix: bool,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("{count} problems are left"))]
+struct UnhealthyError {
+    count: usize,
+}
+
+/// Checks the pools of the workspace in use, which must not be open, as repairs are
+/// written to the files directly. Fails as long as any problem is left.
+pub fn run(
+    dir: &Path,
+    workflow: &Workflow,
+    keyfile: Option<&Path>,
+    args: DoctorArgs,
+) -> Result<(), Box<dyn Error>> {
+    let cipher = storage::unlock(dir, workflow, keyfile)?;
+    let examination = Examination::new(dir, workflow, cipher);
+    let findings = examination.findings().to_vec();
+
+    if findings.is_empty() {
+        println!("No problems found");
+        return Ok(());
+    }
+
+    for finding in &findings {
+        if args.fix {
+            println!("{finding}, {}", finding.problem.remedy());
+        } else {
+            println!("{finding}");
+        }
+    }
+
+    let left = if args.fix {
+        if examination.repair()? > 0 {
+            println!(
+                "Quarantined items are kept in {}",
+                dir.join(QUARANTINE).display()
+            );
+        }
+
+        findings
+            .iter()
+            .filter(|finding| !finding.problem.is_fixable())
+            .count()
+    } else {
+        findings.len()
+    };
+
+    if left == 0 {
+        return Ok(());
+    }
+
+    Err(Box::new(UnhealthyError { count: left }))
+}
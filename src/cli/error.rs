@@ -0,0 +1,264 @@
+use std::backtrace::Backtrace;
+use std::error::Error as StdError;
+use std::fmt::{self, Display, Formatter};
+use std::io::Error as IoError;
+use std::process::ExitCode;
+
+use crate::domain::entity::WorkflowError;
+use crate::domain::usecase::{
+    add_tag::AddTagError, complete_id::CompleteIdError, edit::EditItemError, get::GetItemError,
+    plan::PlanError, relocate::RelocateError, remove_tag::RemoveTagError, select::SelectItemError,
+    set_priority::SetPriorityError, shift::ShiftError, start::StartError, stop::StopError,
+    transition::TransitionError,
+};
+use crate::format::duration::DurationError;
+use crate::format::inline::InlineError;
+use crate::format::ParseError;
+use crate::repository::cipher::CipherError;
+use crate::repository::item::doctor::DoctorError;
+use crate::repository::item::migrate::MigrateError;
+use crate::repository::item::{InitError, SyncError};
+use crate::repository::workflow::LoadError;
+use crate::repository::workspace::WorkspaceError;
+use crate::repository::StateError;
+use crate::sync::{CaldavError, GitError};
+
+use super::storage::{InUseError, PassphraseError};
+
+/// What went wrong, which decides the exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Failure,
+    /// Same as clap uses for invalid arguments.
+    Usage,
+    NotFound,
+    Conflict,
+    Invalid,
+    Storage,
+    /// A passphrase is missing or wrong.
+    Locked,
+    /// A git remote or a CalDAV server.
+    Remote,
+}
+
+impl Class {
+    pub fn code(self) -> u8 {
+        match self {
+            Class::Failure => 1,
+            Class::Usage => 2,
+            Class::NotFound => 3,
+            Class::Conflict => 4,
+            Class::Invalid => 5,
+            Class::Storage => 6,
+            Class::Locked => 7,
+            Class::Remote => 8,
+        }
+    }
+
+    /// Classifies by the first error of the chain that's known.
+    fn of(err: &(dyn StdError + 'static)) -> Class {
+        let mut next = Some(err);
+
+        while let Some(err) = next {
+            if let Some(class) = Self::known(err) {
+                return class;
+            }
+            next = err.source();
+        }
+
+        Class::Failure
+    }
+
+    fn known(err: &(dyn StdError + 'static)) -> Option<Class> {
+        use Class::*;
+
+        if let Some(err) = err.downcast_ref::<Error>() {
+            return Some(err.class);
+        }
+        if let Some(err) = err.downcast_ref::<InitError>() {
+            return Some(match err {
+                InitError::Locked | InitError::Decrypt { .. } => Locked,
+                InitError::Migrate { .. } | InitError::Collision { .. } => Invalid,
+                _ => Storage,
+            });
+        }
+        if let Some(err) = err.downcast_ref::<WorkspaceError>() {
+            return Some(match err {
+                WorkspaceError::NotFound { .. } => NotFound,
+                WorkspaceError::Conflict { .. } => Conflict,
+                WorkspaceError::Invalid { .. } | WorkspaceError::Default => Usage,
+                WorkspaceError::Io { .. } => Storage,
+            });
+        }
+        if let Some(err) = err.downcast_ref::<ShiftError>() {
+            return match err {
+                ShiftError::NotFound => Some(NotFound),
+                ShiftError::Already { .. } | ShiftError::NotAllowed { .. } => Some(Conflict),
+                // Classified by its source.
+                ShiftError::Transition { .. } => None,
+            };
+        }
+        if let Some(err) = err.downcast_ref::<PlanError>() {
+            return Some(match err {
+                PlanError::Invalid => Invalid,
+                PlanError::Conflict => Conflict,
+            });
+        }
+        if let Some(err) = err.downcast_ref::<EditItemError>() {
+            return Some(match err {
+                EditItemError::Invalid => Invalid,
+                EditItemError::NotFound => NotFound,
+                EditItemError::Conflict => Conflict,
+            });
+        }
+        if let Some(err) = err.downcast_ref::<SelectItemError>() {
+            return Some(match err {
+                SelectItemError::Invalid => Invalid,
+                SelectItemError::NotFound => NotFound,
+            });
+        }
+        if let Some(err) = err.downcast_ref::<SetPriorityError>() {
+            return Some(match err {
+                SetPriorityError::Invalid => Invalid,
+                SetPriorityError::NotFound => NotFound,
+            });
+        }
+        if let Some(err) = err.downcast_ref::<TransitionError>() {
+            return Some(match err {
+                TransitionError::NotFound => NotFound,
+                TransitionError::Conflict => Conflict,
+            });
+        }
+        if let Some(err) = err.downcast_ref::<RelocateError>() {
+            return Some(match err {
+                RelocateError::NotFound => NotFound,
+                RelocateError::Conflict => Conflict,
+            });
+        }
+        if let Some(err) = err.downcast_ref::<AddTagError>() {
+            return Some(match err {
+                AddTagError::NotFound => NotFound,
+                AddTagError::Conflict => Conflict,
+            });
+        }
+        if let Some(err) = err.downcast_ref::<RemoveTagError>() {
+            return Some(match err {
+                RemoveTagError::ItemNotFound | RemoveTagError::TagNotFound => NotFound,
+                RemoveTagError::Conflict => Conflict,
+            });
+        }
+        if let Some(err) = err.downcast_ref::<StartError>() {
+            return Some(match err {
+                StartError::NotFound => NotFound,
+                StartError::Running { .. } => Conflict,
+            });
+        }
+        if let Some(err) = err.downcast_ref::<CompleteIdError>() {
+            return Some(match err {
+                CompleteIdError::NotFound => NotFound,
+                CompleteIdError::Ambiguous => Conflict,
+            });
+        }
+
+        let class = if err.is::<GetItemError>() || err.is::<StopError>() {
+            NotFound
+        } else if err.is::<SyncError>() || err.is::<DoctorError>() || err.is::<IoError>() {
+            Storage
+        } else if err.is::<CipherError>() || err.is::<PassphraseError>() {
+            Locked
+        } else if err.is::<GitError>() || err.is::<CaldavError>() {
+            Remote
+        } else if err.is::<StateError>() || err.is::<InUseError>() || err.is::<WorkflowError>() {
+            Usage
+        } else if err.is::<ParseError>()
+            || err.is::<InlineError>()
+            || err.is::<DurationError>()
+            || err.is::<MigrateError>()
+            || err.is::<LoadError>()
+        {
+            Invalid
+        } else {
+            return None;
+        };
+
+        Some(class)
+    }
+}
+
+/// An error as reported to the user, with what was being done when it happened.
+#[derive(Debug)]
+pub struct Error {
+    class: Class,
+    /// Outermost first.
+    context: Vec<String>,
+    source: Box<dyn StdError>,
+    backtrace: Backtrace,
+}
+
+impl Error {
+    pub fn new(source: impl Into<Box<dyn StdError>>) -> Self {
+        let source = match source.into().downcast::<Error>() {
+            Ok(err) => return *err,
+            Err(source) => source,
+        };
+
+        Error {
+            class: Class::of(source.as_ref()),
+            context: Vec::new(),
+            source,
+            backtrace: Backtrace::force_capture(),
+        }
+    }
+
+    /// Describes what was being done, such as which file was being opened.
+    pub fn context(mut self, context: impl Into<String>) -> Self {
+        self.context.insert(0, context.into());
+        self
+    }
+
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::from(self.class.code())
+    }
+
+    /// Prints the error to stderr. Verbose reports also list every cause and where the
+    /// error was caught.
+    pub fn report(&self, verbose: bool) {
+        eprintln!("Error: {self}");
+
+        if !verbose {
+            return;
+        }
+
+        let mut cause = self.source.source();
+        if cause.is_some() {
+            eprintln!("\nCaused by:");
+        }
+        while let Some(err) = cause {
+            eprintln!("    {err}");
+            cause = err.source();
+        }
+
+        eprintln!("\nBacktrace:\n{}", self.backtrace);
+    }
+}
+
+impl From<Box<dyn StdError>> for Error {
+    fn from(source: Box<dyn StdError>) -> Self {
+        Error::new(source)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for context in &self.context {
+            write!(f, "{context}: ")?;
+        }
+        write!(f, "{}", self.source)
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
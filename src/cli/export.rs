@@ -0,0 +1,114 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::{NaiveDateTime, ParseResult, Utc};
+use clap::{Args, ValueEnum};
+
+use crate::domain::entity::{Group as ItemGroup, Item};
+use crate::domain::usecase::select::{self, Request, Response, SelectItemError};
+use crate::format::{csv, ics, json, markdown, todotxt};
+use crate::repository::item::Pool;
+use crate::repository::Repository;
+
+/// Export formats only know about the built-in groups, which items of other states are
+/// exported as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Group {
+    Planned,
+    Finished,
+    Canceled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Ics,
+    Todotxt,
+    Json,
+    Csv,
+    Markdown,
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    #[arg(short, long, value_enum)]
+    format: Format,
+    #[arg(short, long = "group", value_enum)]
+    groups: Vec<Group>,
+    #[arg(short, long = "tag")]
+    tags: Vec<String>,
+    #[arg(short, long, value_parser = parse_datetime)]
+    before: Option<NaiveDateTime>,
+    #[arg(short, long, value_parser = parse_datetime)]
+    after: Option<NaiveDateTime>,
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+impl From<Group> for ItemGroup {
+    fn from(value: Group) -> Self {
+        match value {
+            Group::Planned => ItemGroup::Planned,
+            Group::Finished => ItemGroup::Finished,
+            Group::Canceled => ItemGroup::Canceled,
+        }
+    }
+}
+
+fn parse_datetime(value: &str) -> ParseResult<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+}
+
+pub fn run(repo: Arc<Repository>, args: ExportArgs) -> Result<(), Box<dyn Error>> {
+    let groups = if args.groups.is_empty() {
+        ItemGroup::ALL.to_vec()
+    } else {
+        args.groups.into_iter().map(ItemGroup::from).collect()
+    };
+
+    let select = |group: ItemGroup, pool: &dyn Pool| {
+        let request = Request {
+            tags: args.tags.iter().cloned().collect(),
+            before: args.before,
+            after: args.after,
+        };
+
+        match select::execute(pool, request) {
+            Ok(Response { items }) => Ok(items.into_iter().map(|item| (group, item)).collect()),
+            Err(SelectItemError::NotFound) => Ok(Vec::new()),
+            Err(err) => Err(err),
+        }
+    };
+
+    let workflow = repo.workflow();
+    let response = repo.read_all(|pools, _| {
+        pools
+            .into_iter()
+            .filter_map(|(name, pool)| {
+                let group = workflow.group(name);
+                groups.contains(&group).then(|| select(group, pool))
+            })
+            .collect::<Result<Vec<Vec<(ItemGroup, Item)>>, _>>()
+    });
+
+    let items = match response {
+        Ok(items) => items.concat(),
+        Err(err) => return Err(Box::new(err)),
+    };
+
+    let output = match args.format {
+        Format::Ics => ics::render(&items, Utc::now().naive_utc()),
+        Format::Todotxt => todotxt::render(&items),
+        Format::Json => json::render(&items)?,
+        Format::Csv => csv::render(&items)?,
+        Format::Markdown => markdown::render(&items),
+    };
+
+    match args.output {
+        Some(path) => fs::write(path, output)?,
+        None => print!("{output}"),
+    }
+
+    Ok(())
+}
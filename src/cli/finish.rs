@@ -1,33 +1,23 @@
 use std::error::Error;
 use std::sync::Arc;
 
+use chrono::Local;
 use clap::Args;
 
-use crate::domain::usecase::transfer::{self, Request};
+use crate::cli::set_state;
+use crate::cli::target::{self, TargetArgs};
+use crate::domain::entity::Group;
 use crate::repository::Repository;
 
 #[derive(Args)]
 pub struct FinishArgs {
-    #[arg(short, long)]
-    id: u64,
+    #[command(flatten)]
+    target: TargetArgs,
 }
 
 pub fn run(repo: Arc<Repository>, args: FinishArgs) -> Result<(), Box<dyn Error>> {
-    let id = args.id;
-    let request = Request { id };
+    let now = Local::now().naive_local();
 
-    let response = repo.apply_planned_finished_ids(|planned, finished, ids| {
-        transfer::execute(planned, finished, ids, request)
-    });
-
-    match response {
-        Ok(()) => {
-            println!("Mark {id} as finished");
-            Ok(())
-        }
-        Err(err) => {
-            eprintln!("{err}");
-            Err(Box::new(err))
-        }
-    }
+    let results = set_state::shift(&repo, &args.target, Group::Finished.name(), now)?;
+    target::report(results, |id, ()| format!("Mark {id} as finished"))
 }
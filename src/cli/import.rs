@@ -0,0 +1,80 @@
+use std::convert::Infallible;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::{NaiveDateTime, ParseResult};
+use clap::{Args, ValueEnum};
+
+use crate::cli::target;
+use crate::domain::entity::Group;
+use crate::domain::usecase::import::{self, Request, Response};
+use crate::format::{csv, ics, markdown, taskwarrior, todotxt};
+use crate::repository::Repository;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Taskwarrior,
+    Todotxt,
+    Csv,
+    Markdown,
+    Ics,
+}
+
+#[derive(Args)]
+pub struct ImportArgs {
+    #[arg(short, long, value_enum)]
+    format: Format,
+    file: PathBuf,
+    #[arg(long, value_parser = parse_datetime)]
+    default_deadline: Option<NaiveDateTime>,
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+}
+
+fn parse_datetime(value: &str) -> ParseResult<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+}
+
+pub fn run(repo: Arc<Repository>, args: ImportArgs) -> Result<(), Box<dyn Error>> {
+    let input = fs::read_to_string(&args.file)?;
+
+    let parse = match args.format {
+        Format::Taskwarrior => taskwarrior::parse,
+        Format::Todotxt => todotxt::parse,
+        Format::Csv => csv::parse,
+        Format::Markdown => markdown::parse,
+        Format::Ics => ics::parse,
+    };
+
+    let entries = match parse(&input, args.default_deadline) {
+        Ok(entries) => entries,
+        Err(err) => return Err(Box::new(err)),
+    };
+
+    let labels = entries
+        .iter()
+        .map(|entry| format!("{} ({})", entry.request.summary, entry.group))
+        .collect::<Vec<_>>();
+
+    let request = Request {
+        entries,
+        dry_run: args.dry_run,
+    };
+
+    let names = Group::ALL.map(|group| group.name());
+    let Ok(Response { results }) =
+        repo.transaction(names, |[planned, finished, canceled], ids| {
+            Ok::<_, Infallible>(import::execute(planned, finished, canceled, ids, request))
+        })?;
+
+    let verb = if args.dry_run {
+        "Would import"
+    } else {
+        "Import"
+    };
+    target::report(labels.into_iter().zip(results).collect(), |label, id| {
+        format!("{verb} {label} as {id}")
+    })
+}
@@ -1,26 +1,34 @@
+use std::collections::HashMap;
+use std::env;
 use std::error::Error;
+use std::ffi::OsStr;
+use std::path::Path;
 use std::sync::Arc;
 
-use chrono::{NaiveDateTime, ParseResult};
+use chrono::{Duration, Local, NaiveDateTime, ParseResult};
 use clap::{Args, ValueEnum};
-use comfy_table::{Attribute, Cell, CellAlignment, ContentArrangement, Row, Table};
+use comfy_table::{Attribute, Cell, CellAlignment, Color, ContentArrangement, Row, Table};
 
-use crate::domain::entity::{Item, TagSet};
+use crate::domain::entity::{Group, Item, TagSet};
 use crate::domain::usecase::select::{self, Request, Response};
+use crate::domain::usecase::timesheet;
+use crate::format::duration;
 use crate::repository::item::Pool;
+use crate::repository::timer::LocalPool;
 use crate::repository::Repository;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
-enum Group {
-    Planned,
-    Finished,
-    Canceled,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
 }
 
 #[derive(Args)]
 pub struct ListArgs {
-    #[arg(short, long, value_enum, default_value_t = Group::Planned)]
-    group: Group,
+    /// Any configured state
+    #[arg(short, long, default_value_t = Group::Planned.name().to_owned())]
+    group: String,
     #[arg(short, long = "tag")]
     tags: Vec<String>,
     #[arg(short, long, value_parser = parse_datetime)]
@@ -29,15 +37,21 @@ pub struct ListArgs {
     after: Option<NaiveDateTime>,
     #[arg(short, long, default_value_t = false)]
     verbose: bool,
+    #[arg(long, default_value_t = false)]
+    overdue: bool,
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
 }
 
 fn parse_datetime(value: &str) -> ParseResult<NaiveDateTime> {
     NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
 }
 
-pub fn run(repo: Arc<Repository>, args: ListArgs) -> Result<(), Box<dyn Error>> {
+pub fn run(repo: Arc<Repository>, storage: &Path, args: ListArgs) -> Result<(), Box<dyn Error>> {
     let group = args.group;
+    let workflow = repo.workflow();
     let verbose = args.verbose;
+    let now = Local::now().naive_local();
 
     let request = Request {
         tags: args.tags.into_iter().collect(),
@@ -45,27 +59,76 @@ pub fn run(repo: Arc<Repository>, args: ListArgs) -> Result<(), Box<dyn Error>>
         after: args.after,
     };
 
-    let func = |pool: &mut dyn Pool| select::execute(pool, request);
+    let func = |pool: &dyn Pool| select::execute(pool, request);
 
-    let response = match group {
-        Group::Planned => repo.apply_planned(func),
-        Group::Finished => repo.apply_finished(func),
-        Group::Canceled => repo.apply_canceled(func),
+    let response = match repo.read([&group], |[pool], _| func(pool)) {
+        Ok(response) => response,
+        Err(err) => return Err(Box::new(err)),
     };
 
     match response {
-        Ok(Response { items }) => {
-            println!("{}", build_table(items, verbose));
+        Ok(Response { mut items }) => {
+            if args.overdue {
+                keep_overdue(&mut items, now);
+            }
+
+            // Deadlines of closed items are no longer urgent.
+            let now = (!workflow.is_final(&group)).then_some(now);
+
+            // Tracked time is only shown along with the other details.
+            let tracked = if verbose {
+                let timers = LocalPool::open(storage.join("timers.json"))?;
+                timesheet::tracked(&timers, Local::now().naive_local())
+            } else {
+                HashMap::new()
+            };
+
+            let mut table = build_table(items, verbose, &tracked, now);
+            style(&mut table, args.color);
+            println!("{table}");
             Ok(())
         }
-        Err(err) => {
-            println!("{err}");
-            Err(Box::new(err))
+        Err(err) => Err(Box::new(err)),
+    }
+}
+
+/// Items are overdue from their deadline on.
+fn keep_overdue(items: &mut Vec<Item>, now: NaiveDateTime) {
+    items.retain(|item| item.is_expired(now));
+}
+
+fn style(table: &mut Table, color: ColorChoice) {
+    match styling(color, env::var_os("NO_COLOR").as_deref()) {
+        Some(true) => {
+            table.enforce_styling();
+        }
+        Some(false) => {
+            table.force_no_tty();
         }
+        None => {}
+    }
+}
+
+/// Whether styling is forced on or off, or `None` to follow whether stdout is a
+/// terminal. Without `--color`, a non-empty `NO_COLOR` turns it off, see
+/// <https://no-color.org>.
+fn styling(color: ColorChoice, no_color: Option<&OsStr>) -> Option<bool> {
+    match color {
+        ColorChoice::Always => Some(true),
+        ColorChoice::Never => Some(false),
+        ColorChoice::Auto if no_color.is_some_and(|value| !value.is_empty()) => Some(false),
+        ColorChoice::Auto => None,
     }
 }
 
-fn build_table(items: Vec<Item>, verbose: bool) -> Table {
+/// When `now` is given, rows are colored by urgency, red for overdue items and yellow
+/// for ones due today, and deadlines come with the time left.
+fn build_table(
+    items: Vec<Item>,
+    verbose: bool,
+    tracked: &HashMap<u64, Duration>,
+    now: Option<NaiveDateTime>,
+) -> Table {
     let mut table = Table::new();
     table.set_content_arrangement(ContentArrangement::Dynamic);
 
@@ -77,16 +140,18 @@ fn build_table(items: Vec<Item>, verbose: bool) -> Table {
             Cell::new("Deadline").add_attribute(Attribute::Bold),
             Cell::new("Tags").add_attribute(Attribute::Bold),
             Cell::new("Priority").add_attribute(Attribute::Bold),
+            Cell::new("Tracked").add_attribute(Attribute::Bold),
         ]);
 
         for item in items {
             let mut row = Row::new();
-            row.add_cell(item.id().into());
-            row.add_cell(item.summary().into());
-            row.add_cell(item.content().into());
-            row.add_cell(item.deadline().into());
-            row.add_cell(tags_to_cell(item.tags()));
-            row.add_cell(item.priority().value().into());
+            row.add_cell(highlight(item.id().into(), &item, now));
+            row.add_cell(highlight(item.summary().into(), &item, now));
+            row.add_cell(highlight(item.content().into(), &item, now));
+            row.add_cell(highlight(deadline_to_cell(&item, now), &item, now));
+            row.add_cell(highlight(tags_to_cell(item.tags()), &item, now));
+            row.add_cell(highlight(item.priority().value().into(), &item, now));
+            row.add_cell(highlight(tracked_to_cell(&item, tracked), &item, now));
             table.add_row(row);
         }
     } else {
@@ -98,9 +163,9 @@ fn build_table(items: Vec<Item>, verbose: bool) -> Table {
 
         for item in items {
             let mut row = Row::new();
-            row.add_cell(item.id().into());
-            row.add_cell(item.summary().into());
-            row.add_cell(item.deadline().into());
+            row.add_cell(highlight(item.id().into(), &item, now));
+            row.add_cell(highlight(item.summary().into(), &item, now));
+            row.add_cell(highlight(deadline_to_cell(&item, now), &item, now));
             table.add_row(row);
         }
     }
@@ -108,6 +173,51 @@ fn build_table(items: Vec<Item>, verbose: bool) -> Table {
     table
 }
 
+fn highlight(cell: Cell, item: &Item, now: Option<NaiveDateTime>) -> Cell {
+    match urgency(item, now) {
+        Some(color) => cell.fg(color),
+        None => cell,
+    }
+}
+
+fn urgency(item: &Item, now: Option<NaiveDateTime>) -> Option<Color> {
+    match now {
+        Some(now) if item.is_expired(now) => Some(Color::Red),
+        Some(now) if item.deadline().date() == now.date() => Some(Color::Yellow),
+        _ => None,
+    }
+}
+
+fn deadline_to_cell(item: &Item, now: Option<NaiveDateTime>) -> Cell {
+    let deadline = *item.deadline();
+
+    match now {
+        Some(now) if item.is_expired(now) => Cell::new(format!(
+            "{deadline} (overdue {})",
+            duration::render(now - deadline)
+        )),
+        Some(now) => Cell::new(format!(
+            "{deadline} (in {})",
+            duration::render(deadline - now)
+        )),
+        None => deadline.into(),
+    }
+}
+
+/// Tracked time comes with the estimate, if any, such as `1h30m / 4h`.
+fn tracked_to_cell(item: &Item, tracked: &HashMap<u64, Duration>) -> Cell {
+    let time = tracked
+        .get(&item.id())
+        .copied()
+        .unwrap_or_else(Duration::zero);
+    let time = duration::render_minutes(time);
+
+    match item.estimate() {
+        Some(estimate) => Cell::new(format!("{time} / {}", duration::render_minutes(estimate))),
+        None => Cell::new(time),
+    }
+}
+
 fn tags_to_cell(tags: &TagSet) -> Cell {
     let mut res = tags
         .iter()
@@ -123,3 +233,106 @@ fn tags_to_cell(tags: &TagSet) -> Cell {
         Cell::new("/").set_alignment(CellAlignment::Center)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+
+    use super::*;
+
+    fn at(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    fn item(deadline: &str) -> Item {
+        Item::new(
+            "Test",
+            "",
+            at(deadline),
+            TagSet::new(),
+            0.try_into().unwrap(),
+        )
+    }
+
+    #[test]
+    fn it_should_color_overdue_items_and_ones_due_today() {
+        let today = item("2026-10-19 23:59:59");
+        let now = |value| Some(at(value));
+
+        assert_eq!(
+            urgency(&today, now("2026-10-19 00:00:00")),
+            Some(Color::Yellow)
+        );
+        assert_eq!(urgency(&today, now("2026-10-18 23:59:59")), None);
+        // Right at the deadline it's overdue already.
+        assert_eq!(
+            urgency(&today, now("2026-10-19 23:59:59")),
+            Some(Color::Red)
+        );
+        assert_eq!(
+            urgency(&today, now("2026-10-20 00:00:00")),
+            Some(Color::Red)
+        );
+
+        let midnight = item("2026-10-20 00:00:00");
+        assert_eq!(urgency(&midnight, now("2026-10-19 23:59:59")), None);
+        assert_eq!(
+            urgency(&midnight, now("2026-10-20 00:00:00")),
+            Some(Color::Red)
+        );
+
+        // Closed items come without `now`.
+        assert_eq!(urgency(&today, None), None);
+    }
+
+    #[test]
+    fn it_should_show_the_time_left_or_past_the_deadline() {
+        let item = item("2026-10-19 23:59:59");
+
+        assert_eq!(
+            deadline_to_cell(&item, Some(at("2026-10-19 21:59:59"))).content(),
+            "2026-10-19 23:59:59 (in 2h)"
+        );
+        assert_eq!(
+            deadline_to_cell(&item, Some(at("2026-10-19 23:59:59"))).content(),
+            "2026-10-19 23:59:59 (overdue 0s)"
+        );
+        assert_eq!(
+            deadline_to_cell(&item, None).content(),
+            "2026-10-19 23:59:59"
+        );
+    }
+
+    #[test]
+    fn it_should_keep_only_overdue_items() {
+        let mut items = vec![
+            item("2026-10-19 12:00:00"),
+            item("2026-10-19 12:00:01"),
+            item("2026-10-18 00:00:00"),
+        ];
+
+        keep_overdue(&mut items, at("2026-10-19 12:00:00"));
+
+        let deadlines = items
+            .iter()
+            .map(|item| *item.deadline())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            deadlines,
+            [at("2026-10-19 12:00:00"), at("2026-10-18 00:00:00")]
+        );
+    }
+
+    #[test]
+    fn it_should_follow_color_and_no_color() {
+        let set = OsString::from("1");
+        let empty = OsString::new();
+
+        assert_eq!(styling(ColorChoice::Auto, None), None);
+        assert_eq!(styling(ColorChoice::Auto, Some(&set)), Some(false));
+        // An empty `NO_COLOR` counts as unset.
+        assert_eq!(styling(ColorChoice::Auto, Some(&empty)), None);
+        assert_eq!(styling(ColorChoice::Always, Some(&set)), Some(true));
+        assert_eq!(styling(ColorChoice::Never, None), Some(false));
+    }
+}
@@ -1,33 +1,89 @@
 pub mod add;
 pub mod add_tag;
+pub mod agenda;
+pub mod calendar;
 pub mod cancel;
 pub mod clean;
+pub mod complete;
+pub mod completions;
+pub mod doctor;
+pub mod error;
+pub mod export;
 pub mod finish;
+pub mod import;
 pub mod list;
+pub mod move_item;
+pub mod remind;
 pub mod remove_tag;
+pub mod serve;
 pub mod set_priority;
+pub mod set_state;
+pub mod shell;
+pub mod show;
+pub mod start;
+pub mod stats;
+pub mod stop;
+pub mod storage;
+pub mod sync;
+pub mod target;
+pub mod timesheet;
+pub mod workspace;
 
 use std::error::Error;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use clap::{Parser, Subcommand};
 
-use crate::repository::Repository;
+use crate::domain::entity::Workflow;
+use crate::domain::usecase::index;
+use crate::repository::cipher::Cipher;
+use crate::repository::id::TriePool;
+use crate::repository::item::{InitError, LocalPool, Pool as ItemPool};
+use crate::repository::workspace::Workspaces;
+use crate::repository::{Data, Repository};
 
 use add::AddArgs;
 use add_tag::AddTagArgs;
+use agenda::AgendaArgs;
+use calendar::CalendarArgs;
 use cancel::CancelArgs;
+use complete::CompleteArgs;
+use completions::CompletionsArgs;
+use doctor::DoctorArgs;
+use error::Error as CliError;
+use export::ExportArgs;
 use finish::FinishArgs;
+use import::ImportArgs;
 use list::ListArgs;
+use move_item::MoveArgs;
+use remind::RemindArgs;
 use remove_tag::RemoveTagArgs;
+use serve::ServeArgs;
 use set_priority::SetPriorityArgs;
+use set_state::SetStateArgs;
+use show::ShowArgs;
+use start::StartArgs;
+use stats::StatsArgs;
+use storage::StorageArgs;
+use sync::SyncArgs;
+use timesheet::TimesheetArgs;
+use workspace::WorkspaceArgs;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about)]
 pub struct Arg {
     #[arg(long)]
     pub storage: Option<PathBuf>,
+    #[arg(long)]
+    pub workspace: Option<String>,
+    /// A file holding the passphrase of encrypted storage.
+    #[arg(long)]
+    pub keyfile: Option<PathBuf>,
+    /// Show what caused an error and where it was caught.
+    #[arg(long)]
+    pub verbose: bool,
     #[command(subcommand)]
     pub command: Option<Command>,
 }
@@ -42,17 +98,107 @@ pub enum Command {
     AddTag(AddTagArgs),
     RemoveTag(RemoveTagArgs),
     SetPriority(SetPriorityArgs),
+    SetState(SetStateArgs),
+    Import(ImportArgs),
+    Export(ExportArgs),
+    Shell,
+    Completions(CompletionsArgs),
+    Serve(ServeArgs),
+    Remind(RemindArgs),
+    Agenda(AgendaArgs),
+    Calendar(CalendarArgs),
+    Stats(StatsArgs),
+    Workspace(WorkspaceArgs),
+    Move(MoveArgs),
+    Show(ShowArgs),
+    Start(StartArgs),
+    Stop,
+    Timesheet(TimesheetArgs),
+    Sync(SyncArgs),
+    Storage(StorageArgs),
+    Doctor(DoctorArgs),
+    #[command(name = "__complete", hide = true)]
+    Complete(CompleteArgs),
+}
+
+pub struct Context {
+    pub workspaces: Workspaces,
+    pub workflow: Workflow,
+    /// The name of the workspace in use.
+    pub workspace: String,
+    /// The directory of the workspace in use.
+    pub storage: PathBuf,
+    pub keyfile: Option<PathBuf>,
+    /// What the pools of the workspace in use are encrypted with, if they are.
+    pub cipher: Option<Arc<Cipher>>,
+    pub verbose: bool,
+}
+
+/// Opens the pools of every state in `dir`, creating it if needed, and registers the IDs
+/// of open items. Pools are encrypted with `cipher` if there's one.
+pub fn open(
+    dir: &Path,
+    workflow: &Workflow,
+    cipher: Option<Arc<Cipher>>,
+) -> Result<Arc<Repository>, CliError> {
+    fs::create_dir_all(dir).map_err(|err| {
+        CliError::new(InitError::Open { source: err })
+            .context(format!("Failed to create {}", dir.display()))
+    })?;
+
+    let data = Data::new(workflow.clone(), Box::new(TriePool::new()), |name| {
+        let path = dir.join(format!("{name}.json"));
+        match LocalPool::with_cipher(path.clone(), cipher.clone()) {
+            Ok(pool) => Ok(Box::new(pool) as Box<dyn ItemPool>),
+            Err(err) => {
+                Err(CliError::new(err).context(format!("Failed to open {}", path.display())))
+            }
+        }
+    })?;
+
+    let repo = Repository::new(data);
+
+    for name in workflow.open() {
+        let _ = repo.apply([name], |[pool], ids| index::execute(pool, ids));
+    }
+
+    Ok(Arc::new(repo))
 }
 
-pub fn run(repo: Arc<Repository>, command: Command) -> Result<(), Box<dyn Error>> {
+pub fn run(
+    repo: Arc<Repository>,
+    context: &Context,
+    command: Command,
+) -> Result<(), Box<dyn Error>> {
     match command {
         Command::Add(args) => add::run(repo, args),
         Command::Finish(args) => finish::run(repo, args),
         Command::Cancel(args) => cancel::run(repo, args),
         Command::Clean => clean::run(repo),
-        Command::List(args) => list::run(repo, args),
+        Command::List(args) => list::run(repo, &context.storage, args),
         Command::AddTag(args) => add_tag::run(repo, args),
         Command::RemoveTag(args) => remove_tag::run(repo, args),
         Command::SetPriority(args) => set_priority::run(repo, args),
+        Command::SetState(args) => set_state::run(repo, args),
+        Command::Import(args) => import::run(repo, args),
+        Command::Export(args) => export::run(repo, args),
+        Command::Shell => shell::run(repo, context),
+        Command::Completions(args) => completions::run(args),
+        Command::Serve(args) => serve::run(repo, args),
+        Command::Remind(args) => remind::run(repo, &context.storage, args),
+        Command::Agenda(args) => agenda::run(repo, args),
+        Command::Calendar(args) => calendar::run(repo, args),
+        Command::Stats(args) => stats::run(repo, args),
+        Command::Workspace(args) => {
+            workspace::run(&context.workspaces, Some(&context.workspace), args)
+        }
+        Command::Move(args) => move_item::run(repo, context, args),
+        Command::Show(args) => show::run(repo, &context.storage, args),
+        Command::Start(args) => start::run(repo, &context.storage, args),
+        Command::Stop => stop::run(&context.storage),
+        Command::Timesheet(args) => timesheet::run(repo, &context.storage, args),
+        Command::Sync(args) => sync::run(repo, context, args),
+        Command::Storage(_) | Command::Doctor(_) => Err(Box::new(storage::InUseError)),
+        Command::Complete(args) => complete::run(repo, context, args),
     }
 }
@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::sync::Arc;
+
+use clap::Args;
+use snafu::prelude::*;
+
+use crate::domain::entity::Group;
+use crate::domain::usecase::relocate::{self, Request, Response};
+use crate::repository::Repository;
+
+use super::error::Error as CliError;
+use super::{storage, Context};
+
+#[derive(Args)]
+pub struct MoveArgs {
+    id: u64,
+    #[arg(long)]
+    to: String,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("Item is already in workspace `{name}`"))]
+struct SameWorkspaceError {
+    name: String,
+}
+
+pub fn run(repo: Arc<Repository>, context: &Context, args: MoveArgs) -> Result<(), Box<dyn Error>> {
+    let MoveArgs { id, to } = args;
+
+    if to == context.workspace {
+        return Err(Box::new(SameWorkspaceError { name: to }));
+    }
+
+    let destination = context.workspaces.path(&to)?;
+    let cipher = storage::unlock(&destination, &context.workflow, context.keyfile.as_deref())?;
+    let destination = super::open(&destination, &context.workflow, cipher)?;
+
+    // The destination is written first, so that the item is still in this workspace if
+    // either of them fails to be written.
+    let planned = Group::Planned.name();
+    let response = repo.transaction([planned], |[source], ids| {
+        destination
+            .transaction([planned], |[destination], destination_ids| {
+                relocate::execute(source, ids, destination, destination_ids, Request { id })
+            })
+            .map_err(|err| CliError::new(err).context(format!("Failed to move {id} to `{to}`")))?
+            .map_err(CliError::new)
+    })?;
+
+    let Response { id } = response?;
+    println!("Move {id} to workspace {to}");
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+
+use chrono::{Duration, Local, NaiveDateTime};
+use clap::Args;
+
+use crate::domain::usecase::remind::{self, Reminder, Request, Response};
+use crate::format::duration;
+use crate::notify::{self, CommandSink, FileSink, Sink, StdoutSink};
+use crate::repository::reminder::{LocalPool, Pool as ReminderPool};
+use crate::repository::Repository;
+
+#[derive(Args)]
+pub struct RemindArgs {
+    #[arg(short, long = "lead", value_parser = duration::parse, default_values = ["1d", "1h"])]
+    leads: Vec<Duration>,
+    #[arg(long)]
+    exec: Option<String>,
+    #[arg(long)]
+    file: Option<PathBuf>,
+    #[arg(long, default_value_t = false)]
+    stdout: bool,
+    #[arg(short, long, default_value_t = false)]
+    watch: bool,
+    #[arg(long, value_parser = duration::parse, default_value = "1m")]
+    interval: Duration,
+}
+
+/// Hands every reminder to every sink. Only reminders which every sink took are marked
+/// as fired, so that the others are tried again rather than lost.
+fn deliver(
+    reminders: &[Reminder],
+    sinks: &mut [Box<dyn Sink>],
+    fired: &mut dyn ReminderPool,
+    leads: &[Duration],
+    now: NaiveDateTime,
+) {
+    for reminder in reminders {
+        let message = notify::message(reminder, now);
+        let mut delivered = true;
+
+        for sink in sinks.iter_mut() {
+            if let Err(err) = sink.notify(reminder, &message) {
+                eprintln!("{err}");
+                delivered = false;
+            }
+        }
+
+        if delivered {
+            remind::fire(fired, reminder, leads);
+        }
+    }
+}
+
+pub fn run(repo: Arc<Repository>, storage: &Path, args: RemindArgs) -> Result<(), Box<dyn Error>> {
+    let mut fired = LocalPool::open(storage.join("reminders.json"))?;
+    let mut sinks: Vec<Box<dyn Sink>> = Vec::new();
+
+    if let Some(command) = args.exec {
+        sinks.push(Box::new(CommandSink::new(command)));
+    }
+
+    if let Some(path) = args.file {
+        sinks.push(Box::new(FileSink::new(path)));
+    }
+
+    if args.stdout || sinks.is_empty() {
+        sinks.push(Box::new(StdoutSink));
+    }
+
+    loop {
+        let now = Local::now().naive_local();
+        let request = Request {
+            now,
+            leads: args.leads.clone(),
+        };
+
+        let Response { reminders } =
+            repo.read_open(|open, _| remind::execute(&open, &mut fired, request));
+
+        deliver(&reminders, &mut sinks, &mut fired, &args.leads, now);
+
+        if !args.watch {
+            return Ok(());
+        }
+
+        fired.sync()?;
+        thread::sleep(args.interval.to_std()?);
+
+        // Items may have been changed by other commands in the meantime.
+        repo.reload()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::notify::NotifyError;
+    use crate::repository::reminder::MemoryPool;
+
+    use super::*;
+
+    /// Fails like a webhook which is down.
+    struct Down;
+
+    impl Sink for Down {
+        fn notify(&mut self, _: &Reminder, _: &str) -> Result<(), NotifyError> {
+            Err(NotifyError::Write {
+                path: "webhook".into(),
+                source: std::io::ErrorKind::BrokenPipe.into(),
+            })
+        }
+    }
+
+    #[test]
+    fn it_should_only_mark_reminders_every_sink_took_as_fired() {
+        let now =
+            NaiveDateTime::parse_from_str("2026-10-19 12:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let leads = [Duration::hours(1), Duration::days(1)];
+        let reminders = [Reminder {
+            id: 1,
+            summary: "Test".to_owned(),
+            deadline: now + Duration::minutes(30),
+            lead: Duration::hours(1),
+        }];
+
+        let mut fired = MemoryPool::new();
+        let mut sinks: Vec<Box<dyn Sink>> = vec![Box::new(StdoutSink), Box::new(Down)];
+
+        deliver(&reminders, &mut sinks, &mut fired, &leads, now);
+        assert!(!fired.contains(1, Duration::hours(1)));
+
+        sinks.pop();
+        deliver(&reminders, &mut sinks, &mut fired, &leads, now);
+        assert!(fired.contains(1, Duration::hours(1)));
+        assert!(fired.contains(1, Duration::days(1)));
+    }
+}
@@ -3,30 +3,39 @@ use std::sync::Arc;
 
 use clap::Args;
 
-use crate::domain::usecase::remove_tag::{self, Request};
+use crate::cli::target::{self, TargetArgs};
+use crate::domain::entity::TagSet;
+use crate::domain::usecase::remove_tag::{self, RemoveTagError, Request};
 use crate::repository::Repository;
 
 #[derive(Args)]
 pub struct RemoveTagArgs {
-    #[arg(short, long)]
-    id: u64,
+    #[command(flatten)]
+    target: TargetArgs,
     #[arg(short, long = "tag")]
     tags: Vec<String>,
 }
 
 pub fn run(repo: Arc<Repository>, args: RemoveTagArgs) -> Result<(), Box<dyn Error>> {
-    let request = Request {
-        id: args.id,
-        tags: args.tags.into_iter().collect(),
-    };
+    let tags: TagSet = args.tags.into_iter().collect();
 
-    let response = repo.apply_planned(|planned| remove_tag::execute(planned, request));
+    let workflow = repo.workflow();
+
+    let response = repo.transaction_all(|pools, _| {
+        args.target.apply(&workflow, pools, |id, pool| {
+            let request = Request {
+                id,
+                tags: tags.clone(),
+            };
+            match pool {
+                Some((_, pool)) => remove_tag::execute(pool, request),
+                None => Err(RemoveTagError::ItemNotFound),
+            }
+        })
+    })?;
 
     match response {
-        Ok(()) => Ok(()),
-        Err(err) => {
-            eprintln!("{err}");
-            Err(Box::new(err))
-        }
+        Ok(results) => target::report(results, |id, ()| format!("Remove tags from {id}")),
+        Err(err) => Err(Box::new(err)),
     }
 }
@@ -0,0 +1,18 @@
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use clap::Args;
+
+use crate::repository::Repository;
+use crate::server;
+
+#[derive(Args)]
+pub struct ServeArgs {
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    bind: SocketAddr,
+}
+
+pub fn run(repo: Arc<Repository>, args: ServeArgs) -> Result<(), Box<dyn Error>> {
+    server::run(repo, args.bind)
+}
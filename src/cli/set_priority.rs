@@ -3,14 +3,15 @@ use std::sync::Arc;
 
 use clap::Args;
 
+use crate::cli::target::{self, TargetArgs};
 use crate::domain::entity::Priority;
-use crate::domain::usecase::set_priority::{self, Request};
+use crate::domain::usecase::set_priority::{self, Request, SetPriorityError};
 use crate::repository::Repository;
 
 #[derive(Args)]
 pub struct SetPriorityArgs {
-    #[arg(short, long)]
-    id: u64,
+    #[command(flatten)]
+    target: TargetArgs,
     #[arg(short, long, default_value_t = 0.try_into().unwrap(), value_parser = parse_priority)]
     priority: Priority,
 }
@@ -24,18 +25,24 @@ fn parse_priority(value: &str) -> Result<Priority, String> {
 }
 
 pub fn run(repo: Arc<Repository>, args: SetPriorityArgs) -> Result<(), Box<dyn Error>> {
-    let request = Request {
-        id: args.id,
-        priority: args.priority.value(),
-    };
+    let priority = args.priority.value();
 
-    let response = repo.apply_planned(|planned| set_priority::execute(planned, request));
+    let workflow = repo.workflow();
+
+    let response = repo.transaction_all(|pools, _| {
+        args.target.apply(&workflow, pools, |id, pool| {
+            let request = Request { id, priority };
+            match pool {
+                Some((_, pool)) => set_priority::execute(pool, request),
+                None => Err(SetPriorityError::NotFound),
+            }
+        })
+    })?;
 
     match response {
-        Ok(()) => Ok(()),
-        Err(err) => {
-            eprintln!("{err}");
-            Err(Box::new(err))
-        }
+        Ok(results) => target::report(results, |id, ()| {
+            format!("Set priority of {id} to {priority}")
+        }),
+        Err(err) => Err(Box::new(err)),
     }
 }
@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::sync::Arc;
+
+use chrono::{Local, NaiveDateTime};
+use clap::Args;
+
+use crate::cli::target::{self, TargetArgs};
+use crate::domain::usecase::select::SelectItemError;
+use crate::domain::usecase::shift::{self, Request, ShiftError};
+use crate::repository::Repository;
+
+#[derive(Args)]
+pub struct SetStateArgs {
+    #[arg(short, long)]
+    state: String,
+    #[command(flatten)]
+    target: TargetArgs,
+}
+
+/// The outcome for one target.
+pub type Shifted = (u64, Result<(), ShiftError>);
+
+pub fn run(repo: Arc<Repository>, args: SetStateArgs) -> Result<(), Box<dyn Error>> {
+    let SetStateArgs { state, target } = args;
+    let now = Local::now().naive_local();
+
+    let results = shift(&repo, &target, &state, now)?;
+    target::report(results, |id, ()| format!("Mark {id} as {state}"))
+}
+
+/// Moves the targets in any open state to the state `to`, as far as the workflow allows
+/// it. Items moved to a final state are closed at `now`. Targets are located and moved
+/// in one transaction, so that no other change gets in between.
+pub fn shift(
+    repo: &Repository,
+    target: &TargetArgs,
+    to: &str,
+    now: NaiveDateTime,
+) -> Result<Vec<Shifted>, Box<dyn Error>> {
+    repo.read([to], |_, _| ())?;
+
+    let workflow = repo.workflow();
+
+    let response = repo.transaction_all(|pools, ids| {
+        let (mut destination, sources): (Vec<_>, Vec<_>) =
+            pools.into_iter().partition(|(name, _)| *name == to);
+        let Some((_, destination)) = destination.pop() else {
+            unreachable!("the state was found before");
+        };
+
+        // Items which are in `to` already can't go there.
+        let mut results = Vec::new();
+        if !workflow.is_final(to) {
+            let already = target.find(destination)?.into_iter();
+            results.extend(already.map(|id| (id, shift::check(&workflow, to, to))));
+        }
+        let already = results.iter().map(|(id, _)| *id).collect::<HashSet<_>>();
+
+        let shifted = target.apply(&workflow, sources, |id, pool| {
+            let Some((from, source)) = pool else {
+                return Err(ShiftError::NotFound);
+            };
+            let request = Request {
+                id,
+                from,
+                to,
+                time: now,
+            };
+            shift::execute(&workflow, source, destination, ids, request)
+        });
+
+        match shifted {
+            Ok(shifted) => {
+                results.extend(shifted.into_iter().filter(|(id, _)| !already.contains(id)))
+            }
+            Err(SelectItemError::NotFound) if !results.is_empty() => {}
+            Err(err) => return Err(err),
+        }
+
+        Ok(results)
+    })?;
+
+    Ok(response?)
+}
@@ -0,0 +1,206 @@
+use std::error::Error;
+use std::sync::Arc;
+
+use clap::{Command as ClapCommand, CommandFactory, Parser};
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{CompletionType, Config, Context, Editor, Helper};
+use snafu::prelude::*;
+
+use crate::repository::workspace::Workspaces;
+use crate::repository::Repository;
+
+use super::complete::{self, Candidate};
+use super::error::Error as CliError;
+use super::{Command, Context as CliContext};
+
+const PROMPT: &str = "todo> ";
+const BUILTINS: [&str; 3] = ["save", "exit", "quit"];
+
+#[derive(Parser)]
+#[command(no_binary_name = true)]
+struct Line {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Snafu)]
+enum SplitError {
+    #[snafu(display("Unterminated quote `{quote}`"))]
+    Unterminated { quote: char },
+}
+
+struct ShellHelper {
+    repo: Arc<Repository>,
+    workspaces: Workspaces,
+    root: ClapCommand,
+}
+
+pub fn run(repo: Arc<Repository>, context: &CliContext) -> Result<(), Box<dyn Error>> {
+    let config = Config::builder()
+        .completion_type(CompletionType::List)
+        .auto_add_history(true)
+        .build();
+
+    let mut editor: Editor<ShellHelper, DefaultHistory> = Editor::with_config(config)?;
+    let mut root = Line::command();
+    root.build();
+
+    editor.set_helper(Some(ShellHelper {
+        repo: repo.clone(),
+        workspaces: Workspaces::new(context.workspaces.root().to_owned()),
+        root,
+    }));
+
+    // Lines hold summaries and tags, so history isn't written next to encrypted pools
+    // and only lasts as long as the shell.
+    let history = context
+        .cipher
+        .is_none()
+        .then(|| context.storage.join("history"));
+
+    if let Some(history) = &history {
+        let _ = editor.load_history(history);
+    }
+
+    loop {
+        let line = match editor.readline(PROMPT) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => {
+                eprintln!("{err}");
+                break;
+            }
+        };
+
+        let words = match split(&line) {
+            Ok(words) => words,
+            Err(err) => {
+                eprintln!("{err}");
+                continue;
+            }
+        };
+
+        match words.first().map(String::as_str) {
+            None => continue,
+            Some("exit" | "quit") => break,
+            Some("save") => {
+                match repo.sync() {
+                    Ok(()) => println!("Saved"),
+                    Err(err) => CliError::new(err).report(context.verbose),
+                }
+                continue;
+            }
+            Some(_) => {}
+        }
+
+        match Line::try_parse_from(words) {
+            Ok(Line {
+                command: Command::Shell,
+            }) => eprintln!("Already in a shell"),
+            Ok(Line { command }) => {
+                if let Err(err) = super::run(repo.clone(), context, command) {
+                    CliError::from(err).report(context.verbose);
+                }
+            }
+            Err(err) => {
+                let _ = err.print();
+            }
+        }
+    }
+
+    if let Some(history) = &history {
+        if let Err(err) = editor.save_history(history) {
+            eprintln!("Failed to save history: {err}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits a line into words by whitespace. Single and double quotes group words, and a
+/// backslash escapes the next character outside single quotes.
+fn split(line: &str) -> Result<Vec<String>, SplitError> {
+    let mut words = Vec::new();
+    let mut word = None::<String>;
+    let mut quote = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"') | None, '\\') => {
+                if let Some(next) = chars.next() {
+                    word.get_or_insert_with(String::new).push(next);
+                }
+            }
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if let Some(quote) = quote {
+        return UnterminatedSnafu { quote }.fail();
+    }
+
+    words.extend(word);
+    Ok(words)
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let word = &line[start..];
+        let words = line[..start].split_whitespace().collect::<Vec<_>>();
+
+        let builtins = BUILTINS
+            .into_iter()
+            .filter(|builtin| words.is_empty() && builtin.starts_with(word))
+            .map(|builtin| Candidate {
+                value: builtin.to_owned(),
+                description: None,
+            });
+
+        let pairs = complete::candidates(&self.repo, &self.workspaces, &self.root, &words, word)
+            .into_iter()
+            .chain(builtins)
+            .map(|Candidate { value, description }| Pair {
+                display: match description {
+                    Some(description) => format!("{value}  {description}"),
+                    None => value.clone(),
+                },
+                replacement: value,
+            })
+            .collect();
+
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
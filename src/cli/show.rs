@@ -0,0 +1,75 @@
+use std::error::Error;
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::{Duration, Local};
+use clap::Args;
+
+use crate::domain::entity::Group;
+use crate::domain::usecase::get::{self, Request, Response};
+use crate::domain::usecase::timesheet;
+use crate::format::duration;
+use crate::repository::timer::{LocalPool, Pool};
+use crate::repository::Repository;
+
+#[derive(Args)]
+pub struct ShowArgs {
+    id: u64,
+}
+
+pub fn run(repo: Arc<Repository>, storage: &Path, args: ShowArgs) -> Result<(), Box<dyn Error>> {
+    let timers = LocalPool::open(storage.join("timers.json"))?;
+    let id = args.id;
+    let now = Local::now().naive_local();
+
+    let state = repo
+        .locate(id)
+        .unwrap_or_else(|| Group::Planned.name().to_owned());
+
+    let response = repo.read([&state], |[pool], _| get::execute(pool, Request { id }))?;
+
+    let Response {
+        id,
+        summary,
+        content,
+        deadline,
+        tags,
+        priority,
+        closed,
+        estimate,
+    } = match response {
+        Ok(response) => response,
+        Err(err) => return Err(Box::new(err)),
+    };
+
+    let mut tags = tags.iter().map(|tag| format!("#{tag}")).collect::<Vec<_>>();
+    tags.sort();
+
+    let tracked = timesheet::tracked(&timers, now)
+        .remove(&id)
+        .unwrap_or_else(Duration::zero);
+    let running = timers.active().is_some_and(|(active, _)| active == id);
+
+    println!("ID:       {id}");
+    println!("Summary:  {summary}");
+    if !content.is_empty() {
+        println!("Content:  {content}");
+    }
+    println!("Deadline: {deadline}");
+    println!("Tags:     {}", tags.join(" "));
+    println!("Priority: {priority}");
+    println!("State:    {state}");
+    if let Some(closed) = closed {
+        println!("Closed:   {closed}");
+    }
+    if let Some(estimate) = estimate {
+        println!("Estimate: {}", duration::render_minutes(estimate));
+    }
+    println!(
+        "Tracked:  {}{}",
+        duration::render_minutes(tracked),
+        if running { " (running)" } else { "" }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use std::error::Error;
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::Local;
+use clap::Args;
+
+use crate::domain::entity::Group;
+use crate::domain::usecase::start::{self, Request, Response};
+use crate::repository::timer::LocalPool;
+use crate::repository::Repository;
+
+#[derive(Args)]
+pub struct StartArgs {
+    id: u64,
+}
+
+pub fn run(repo: Arc<Repository>, storage: &Path, args: StartArgs) -> Result<(), Box<dyn Error>> {
+    let mut timers = LocalPool::open(storage.join("timers.json"))?;
+    let id = args.id;
+
+    // Closed items can't be worked on, so only open states are looked into.
+    let workflow = repo.workflow();
+    let state = repo
+        .locate(id)
+        .filter(|state| !workflow.is_final(state))
+        .unwrap_or_else(|| Group::Planned.name().to_owned());
+
+    let request = Request {
+        id,
+        time: Local::now().naive_local(),
+    };
+
+    match repo.read([&state], |[pool], _| {
+        start::execute(pool, &mut timers, request)
+    })? {
+        Ok(Response { id }) => {
+            println!("Start timer of {id}");
+            Ok(())
+        }
+        Err(err) => Err(Box::new(err)),
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::sync::Arc;
+
+use chrono::{Duration, Local};
+use clap::{Args, ValueEnum};
+use comfy_table::{Attribute, Cell, ContentArrangement, Table};
+
+use crate::domain::usecase::stats::{self, Bucket, Counts, Request, Response};
+use crate::format::duration;
+use crate::repository::Repository;
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Output {
+    Text,
+    Json,
+}
+
+#[derive(Args)]
+pub struct StatsArgs {
+    #[arg(short, long, value_parser = duration::parse)]
+    since: Option<Duration>,
+    #[arg(short, long, value_enum, default_value_t = Output::Text)]
+    output: Output,
+}
+
+pub fn run(repo: Arc<Repository>, args: StatsArgs) -> Result<(), Box<dyn Error>> {
+    let now = Local::now().naive_local();
+
+    let request = Request {
+        now,
+        since: args.since.map(|since| now - since),
+    };
+
+    let workflow = repo.workflow();
+    let response = repo.read_all(|pools, _| stats::execute(&workflow, pools, request));
+
+    match args.output {
+        Output::Json => println!("{}", serde_json::to_string_pretty(&response)?),
+        Output::Text => print(&response),
+    }
+
+    Ok(())
+}
+
+fn print(response: &Response) {
+    let Counts {
+        planned,
+        finished,
+        canceled,
+    } = response.total;
+
+    println!("Planned   {planned} ({} overdue)", response.overdue);
+    println!("Finished  {finished}");
+    println!("Canceled  {canceled}");
+    println!();
+
+    if let Some(ratio) = response.completion_ratio {
+        println!(
+            "Completion  {:.0}% finished, {:.0}% canceled",
+            ratio * 100.0,
+            (1.0 - ratio) * 100.0
+        );
+    }
+
+    if let Some(lateness) = response.average_lateness {
+        let lateness = Duration::seconds(lateness);
+        let word = if lateness < Duration::zero() {
+            "early"
+        } else {
+            "late"
+        };
+
+        println!(
+            "Lateness    {} {word} on average",
+            duration::render(lateness)
+        );
+    }
+
+    println!("Per day     {}", sparkline(&response.finished_per_day));
+    println!("Per week    {}", sparkline(&response.finished_per_week));
+
+    if !response.tags.is_empty() {
+        println!();
+        println!("{}", build_table("Tag", &response.tags));
+    }
+
+    if !response.priorities.is_empty() {
+        println!();
+        println!("{}", build_table("Priority", &response.priorities));
+    }
+}
+
+/// Buckets without any finished item get the lowest bar, so a sparkline keeps its
+/// length. The range of the buckets follows it.
+fn sparkline(buckets: &[Bucket]) -> String {
+    let max = buckets.iter().map(|bucket| bucket.count).max().unwrap_or(0);
+
+    let line = buckets
+        .iter()
+        .map(|bucket| match bucket.count {
+            0 => BARS[0],
+            count => BARS[(count * (BARS.len() - 1)).div_ceil(max)],
+        })
+        .collect::<String>();
+
+    match (buckets.first(), buckets.last()) {
+        (Some(first), Some(last)) => format!("{line}  {} - {}", first.start, last.start),
+        _ => line,
+    }
+}
+
+fn build_table<K: Display>(name: &str, rows: &BTreeMap<K, Counts>) -> Table {
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+
+    table.set_header(vec![
+        Cell::new(name).add_attribute(Attribute::Bold),
+        Cell::new("Planned").add_attribute(Attribute::Bold),
+        Cell::new("Finished").add_attribute(Attribute::Bold),
+        Cell::new("Canceled").add_attribute(Attribute::Bold),
+    ]);
+
+    for (key, counts) in rows {
+        table.add_row(vec![
+            Cell::new(key),
+            Cell::new(counts.planned),
+            Cell::new(counts.finished),
+            Cell::new(counts.canceled),
+        ]);
+    }
+
+    table
+}
@@ -0,0 +1,25 @@
+use std::error::Error;
+use std::path::Path;
+
+use chrono::Local;
+
+use crate::domain::usecase::stop::{self, Request, Response};
+use crate::format::duration;
+use crate::repository::timer::LocalPool;
+
+pub fn run(storage: &Path) -> Result<(), Box<dyn Error>> {
+    let mut timers = LocalPool::open(storage.join("timers.json"))?;
+
+    let request = Request {
+        time: Local::now().naive_local(),
+    };
+
+    match stop::execute(&mut timers, request) {
+        Ok(Response { interval }) => {
+            let tracked = duration::render_minutes(interval.duration());
+            println!("Stop timer of {} after {tracked}", interval.id);
+            Ok(())
+        }
+        Err(err) => Err(Box::new(err)),
+    }
+}
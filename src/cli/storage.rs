@@ -0,0 +1,319 @@
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::Error as IoError;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use clap::{Args, Subcommand};
+use snafu::prelude::*;
+
+use crate::domain::entity::Workflow;
+use crate::repository::cipher::{self, Cipher};
+use crate::repository::item::migrate::VERSION;
+use crate::repository::item::LocalPool;
+
+/// Holds the passphrase, so that it needn't be typed every time.
+pub const PASSPHRASE_VAR: &str = "TODO_PASSPHRASE";
+/// Holds the new passphrase for `storage rekey`.
+pub const NEW_PASSPHRASE_VAR: &str = "TODO_NEW_PASSPHRASE";
+
+#[derive(Args)]
+pub struct StorageArgs {
+    #[command(subcommand)]
+    command: StorageCommand,
+}
+
+#[derive(Subcommand)]
+enum StorageCommand {
+    /// Encrypt the pools of the workspace in use.
+    Encrypt,
+    /// Decrypt the pools of the workspace in use.
+    Decrypt,
+    /// Encrypt the pools of the workspace in use with another passphrase.
+    Rekey {
+        /// A file holding the new passphrase.
+        #[arg(long)]
+        new_keyfile: Option<PathBuf>,
+    },
+    /// Rewrite the pools of the workspace in use in the current file format.
+    Migrate {
+        /// Only report which pools would be migrated.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug, Snafu)]
+pub enum PassphraseError {
+    #[snafu(display("Failed to read keyfile {}: {source}", path.display()))]
+    Keyfile { path: PathBuf, source: IoError },
+    #[snafu(display("Failed to read passphrase: {source}"))]
+    Prompt { source: IoError },
+    #[snafu(display(
+        "A passphrase is needed, but none is given by a keyfile or `{PASSPHRASE_VAR}`"
+    ))]
+    Unavailable,
+    #[snafu(display("Passphrase may not be empty"))]
+    Empty,
+    #[snafu(display("Passphrases don't match"))]
+    Mismatch,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("Storage commands can't run while the pools are open"))]
+pub struct InUseError;
+
+/// Where a passphrase comes from, tried in order: a keyfile, an environment variable,
+/// then a prompt on the terminal unless there's none.
+struct Source<'a> {
+    keyfile: Option<&'a Path>,
+    var: &'a str,
+    prompt: Option<&'a str>,
+    /// Whether a typed passphrase is asked twice, which matters when it's a new one.
+    confirm: bool,
+}
+
+impl Source<'_> {
+    fn read(&self) -> Result<String, PassphraseError> {
+        let passphrase = match (self.keyfile, env::var(self.var)) {
+            (Some(path), _) => fs::read_to_string(path)
+                .context(KeyfileSnafu { path })?
+                .trim_end_matches(['\r', '\n'])
+                .to_owned(),
+            (None, Ok(passphrase)) => passphrase,
+            (None, Err(_)) => {
+                let prompt = self.prompt.context(UnavailableSnafu)?;
+                let passphrase = rpassword::prompt_password(prompt).context(PromptSnafu)?;
+
+                if self.confirm {
+                    let again =
+                        rpassword::prompt_password("Repeat passphrase: ").context(PromptSnafu)?;
+                    ensure!(passphrase == again, MismatchSnafu);
+                }
+
+                passphrase
+            }
+        };
+
+        ensure!(!passphrase.is_empty(), EmptySnafu);
+        Ok(passphrase)
+    }
+}
+
+/// Asks for the passphrase if any pool in `dir` is encrypted.
+pub fn unlock(
+    dir: &Path,
+    workflow: &Workflow,
+    keyfile: Option<&Path>,
+) -> Result<Option<Arc<Cipher>>, PassphraseError> {
+    let source = Source {
+        keyfile,
+        var: PASSPHRASE_VAR,
+        prompt: Some("Passphrase: "),
+        confirm: false,
+    };
+
+    read_cipher(dir, workflow, source)
+}
+
+/// Like [`unlock`], for commands which mustn't wait on the terminal. The passphrase is
+/// only taken from a keyfile or the environment, otherwise
+/// [`PassphraseError::Unavailable`] is returned.
+pub fn unlock_quietly(
+    dir: &Path,
+    workflow: &Workflow,
+    keyfile: Option<&Path>,
+) -> Result<Option<Arc<Cipher>>, PassphraseError> {
+    let source = Source {
+        keyfile,
+        var: PASSPHRASE_VAR,
+        prompt: None,
+        confirm: false,
+    };
+
+    read_cipher(dir, workflow, source)
+}
+
+fn read_cipher(
+    dir: &Path,
+    workflow: &Workflow,
+    source: Source,
+) -> Result<Option<Arc<Cipher>>, PassphraseError> {
+    let encrypted = pools(dir, workflow)
+        .iter()
+        .any(|path| fs::read(path).is_ok_and(|bytes| cipher::is_encrypted(&bytes)));
+
+    if !encrypted {
+        return Ok(None);
+    }
+
+    Ok(Some(Arc::new(Cipher::new(&source.read()?))))
+}
+
+/// Converts the pool files of `dir`, which must not be open. Every file is converted in
+/// memory before any is written, so that a wrong passphrase changes nothing.
+pub fn run(
+    dir: &Path,
+    workflow: &Workflow,
+    keyfile: Option<&Path>,
+    args: StorageArgs,
+) -> Result<(), Box<dyn Error>> {
+    if let StorageCommand::Migrate { dry_run } = args.command {
+        return migrate(dir, workflow, keyfile, dry_run);
+    }
+
+    let current = Source {
+        keyfile,
+        var: PASSPHRASE_VAR,
+        prompt: Some("Passphrase: "),
+        confirm: false,
+    };
+
+    let mut files = Vec::new();
+    for path in pools(dir, workflow) {
+        let bytes = fs::read(&path)?;
+        files.push((path, bytes));
+    }
+
+    let encrypted = files
+        .iter()
+        .filter(|(_, bytes)| cipher::is_encrypted(bytes))
+        .count();
+
+    let (verb, converted) = match args.command {
+        StorageCommand::Encrypt => {
+            // Encrypting what's left of a partly encrypted storage must use the same
+            // passphrase, which is checked by decrypting what's already encrypted.
+            let source = Source {
+                confirm: encrypted == 0,
+                ..current
+            };
+            let cipher = Cipher::new(&source.read()?);
+            decrypt(&cipher, &files)?;
+
+            let converted = files
+                .iter()
+                .filter(|(_, bytes)| !cipher::is_encrypted(bytes))
+                .map(|(path, bytes)| Ok((path, cipher.encrypt(bytes)?)))
+                .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+            ("Encrypt", converted)
+        }
+        StorageCommand::Decrypt => {
+            let cipher = Cipher::new(&current.read()?);
+            ("Decrypt", decrypt(&cipher, &files)?)
+        }
+        StorageCommand::Rekey { new_keyfile } => {
+            let old = Cipher::new(&current.read()?);
+            let plaintexts = decrypt(&old, &files)?;
+
+            let source = Source {
+                keyfile: new_keyfile.as_deref(),
+                var: NEW_PASSPHRASE_VAR,
+                prompt: Some("New passphrase: "),
+                confirm: true,
+            };
+            let new = Cipher::new(&source.read()?);
+
+            let converted = plaintexts
+                .into_iter()
+                .map(|(path, bytes)| Ok((path, new.encrypt(&bytes)?)))
+                .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+            ("Rekey", converted)
+        }
+        StorageCommand::Migrate { .. } => unreachable!(),
+    };
+
+    if converted.is_empty() {
+        println!("Nothing to {}", verb.to_lowercase());
+    }
+
+    for (path, bytes) in converted {
+        replace(path, &bytes)?;
+        println!("{verb} {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Every pool is checked before any is rewritten, so that one which can't be migrated,
+/// such as one written by a newer version, leaves all of them as they were.
+fn migrate(
+    dir: &Path,
+    workflow: &Workflow,
+    keyfile: Option<&Path>,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let cipher = unlock(dir, workflow, keyfile)?;
+
+    let mut outdated = Vec::new();
+    for path in pools(dir, workflow) {
+        match LocalPool::version(path.clone(), cipher.as_deref()) {
+            Ok(version) if version < VERSION => outdated.push((path, version)),
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("{}: {err}", path.display());
+                return Err(Box::new(err));
+            }
+        }
+    }
+
+    if outdated.is_empty() {
+        println!("All pools are at version {VERSION}");
+    }
+
+    for (path, version) in outdated {
+        if dry_run {
+            println!(
+                "Would migrate {} from version {version} to {VERSION}",
+                path.display()
+            );
+        } else {
+            LocalPool::with_cipher(path.clone(), cipher.clone())?.sync()?;
+            println!(
+                "Migrate {} from version {version} to {VERSION}",
+                path.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// The pool files of every state which exist in `dir`.
+fn pools(dir: &Path, workflow: &Workflow) -> Vec<PathBuf> {
+    workflow
+        .names()
+        .map(|name| dir.join(format!("{name}.json")))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Files by path, with their converted contents.
+type Converted<'a> = Vec<(&'a PathBuf, Vec<u8>)>;
+
+fn decrypt<'a>(
+    cipher: &Cipher,
+    files: &'a [(PathBuf, Vec<u8>)],
+) -> Result<Converted<'a>, Box<dyn Error>> {
+    files
+        .iter()
+        .filter(|(_, bytes)| cipher::is_encrypted(bytes))
+        .map(|(path, bytes)| match cipher.decrypt(bytes) {
+            Ok(bytes) => Ok((path, bytes)),
+            Err(err) => {
+                eprintln!("{}: {err}", path.display());
+                Err(Box::new(err) as Box<dyn Error>)
+            }
+        })
+        .collect()
+}
+
+/// Writes next to the file then renames, so that it's never left half written.
+fn replace(path: &Path, bytes: &[u8]) -> Result<(), IoError> {
+    let temporary = path.with_extension("json.tmp");
+    fs::write(&temporary, bytes)?;
+    fs::rename(temporary, path)
+}
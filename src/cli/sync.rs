@@ -0,0 +1,134 @@
+use std::error::Error;
+use std::path::Path;
+use std::sync::Arc;
+
+use clap::{Args, Subcommand, ValueEnum};
+
+use crate::repository::Repository;
+use crate::sync::{self, CaldavReport, Links, Report, Side};
+
+use super::Context;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct SyncArgs {
+    #[command(subcommand)]
+    command: Option<SyncCommand>,
+    #[arg(short, long, default_value = "Sync todo items")]
+    message: String,
+}
+
+#[derive(Subcommand)]
+enum SyncCommand {
+    /// Sync items with a CalDAV calendar instead of git.
+    Caldav {
+        /// The URL of the calendar collection.
+        #[arg(long)]
+        url: String,
+        /// Which side wins when an item changed on both.
+        #[arg(long, value_enum)]
+        prefer: Option<Prefer>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Prefer {
+    Local,
+    Remote,
+}
+
+/// Syncs the whole storage root, so that all workspaces are shared. Pools in use are
+/// written out first and read again afterwards, as merges may have changed them.
+pub fn run(repo: Arc<Repository>, context: &Context, args: SyncArgs) -> Result<(), Box<dyn Error>> {
+    if let Some(SyncCommand::Caldav { url, prefer }) = args.command {
+        return caldav(repo, &context.storage, url, prefer);
+    }
+
+    repo.sync()?;
+    let root = context.workspaces.root();
+    let res = sync::git(root, context.cipher.as_deref(), &args.message);
+    repo.reload()?;
+
+    match res {
+        Ok(Report {
+            committed,
+            tracked,
+            merged,
+        }) => {
+            println!(
+                "{}",
+                if committed {
+                    "Commit local changes"
+                } else {
+                    "No local changes"
+                }
+            );
+
+            for path in merged {
+                println!("Merge {path}");
+            }
+
+            if tracked {
+                println!("Pull and push done");
+            } else {
+                println!("No upstream branch to pull from or push to");
+            }
+
+            Ok(())
+        }
+        Err(err) => Err(Box::new(err)),
+    }
+}
+
+/// Syncs the workspace in use with a calendar, keeping which resource each item maps
+/// to in the workspace. Syncing with another calendar starts the mapping over.
+fn caldav(
+    repo: Arc<Repository>,
+    storage: &Path,
+    url: String,
+    prefer: Option<Prefer>,
+) -> Result<(), Box<dyn Error>> {
+    let path = storage.join("caldav.json");
+    let mut links = Links::load(&path)?;
+
+    if links.url != url {
+        links = Links {
+            url,
+            entries: Vec::new(),
+        };
+    }
+
+    let prefer = prefer.map(|prefer| match prefer {
+        Prefer::Local => Side::Local,
+        Prefer::Remote => Side::Remote,
+    });
+
+    let workflow = repo.workflow();
+    let res = repo.apply_all(|pools, ids| sync::caldav(&workflow, pools, ids, &mut links, prefer));
+
+    // Whatever was done before a failure is still recorded.
+    links.save(&path)?;
+    repo.sync()?;
+
+    match res {
+        Ok(CaldavReport {
+            pushed,
+            pulled,
+            deleted,
+            conflicts,
+        }) => {
+            println!("Push {pushed}, pull {pulled} and delete {deleted} items");
+
+            for conflict in &conflicts {
+                eprintln!("Conflict: {conflict}");
+            }
+
+            if !conflicts.is_empty() {
+                eprintln!("Run again with `--prefer local` or `--prefer remote` to settle them");
+            }
+
+            Ok(())
+        }
+        Err(err) => Err(Box::new(err)),
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::Display;
+
+use chrono::{NaiveDateTime, ParseResult};
+use clap::Args;
+use snafu::prelude::*;
+
+use crate::domain::entity::Workflow;
+use crate::domain::usecase::select::{self, Request, Response, SelectItemError};
+use crate::repository::item::Pool;
+
+#[derive(Args)]
+#[group(required = true, multiple = true)]
+pub struct TargetArgs {
+    #[arg(short, long = "id")]
+    ids: Vec<u64>,
+    #[arg(long = "with-tag")]
+    with_tags: Vec<String>,
+    #[arg(short, long, value_parser = parse_datetime)]
+    before: Option<NaiveDateTime>,
+    #[arg(short, long, value_parser = parse_datetime)]
+    after: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("{failed} of {total} items failed"))]
+pub struct BatchError {
+    failed: usize,
+    total: usize,
+}
+
+fn parse_datetime(value: &str) -> ParseResult<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+}
+
+impl TargetArgs {
+    /// Collects the explicit IDs followed by the IDs of all items matching the filter,
+    /// without duplicates.
+    pub fn resolve(self, pool: &dyn Pool) -> Result<Vec<u64>, SelectItemError> {
+        let mut ids = self.ids;
+
+        if !self.with_tags.is_empty() || self.before.is_some() || self.after.is_some() {
+            let request = Request {
+                tags: self.with_tags.into_iter().collect(),
+                before: self.before,
+                after: self.after,
+            };
+
+            match select::execute(pool, request) {
+                Ok(Response { items }) => ids.extend(items.iter().map(|item| item.id())),
+                Err(SelectItemError::NotFound) => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        let mut seen = HashSet::new();
+        ids.retain(|id| seen.insert(*id));
+
+        if !ids.is_empty() {
+            Ok(ids)
+        } else {
+            Err(SelectItemError::NotFound)
+        }
+    }
+
+    pub fn ids(&self) -> &[u64] {
+        &self.ids
+    }
+
+    /// Collects the explicit IDs of items in `pool` followed by the IDs of its items
+    /// matching the filter, without duplicates. Unlike [`TargetArgs::resolve`], finding
+    /// nothing isn't an error, as targets may be spread over several pools.
+    pub fn find(&self, pool: &dyn Pool) -> Result<Vec<u64>, SelectItemError> {
+        let mut ids = self
+            .ids
+            .iter()
+            .copied()
+            .filter(|&id| pool.get(id).is_ok())
+            .collect::<Vec<_>>();
+
+        if !self.with_tags.is_empty() || self.before.is_some() || self.after.is_some() {
+            let request = Request {
+                tags: self.with_tags.iter().cloned().collect(),
+                before: self.before,
+                after: self.after,
+            };
+
+            match select::execute(pool, request) {
+                Ok(Response { items }) => ids.extend(items.iter().map(|item| item.id())),
+                Err(SelectItemError::NotFound) => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        let mut seen = HashSet::new();
+        ids.retain(|id| seen.insert(*id));
+        Ok(ids)
+    }
+
+    /// Like [`TargetArgs::resolve`], over the pools of every open state of `workflow`.
+    /// `f` is applied to each target along with the state it's in and its pool, or to
+    /// `None` for explicit IDs which are in none of them.
+    pub fn apply<F, T>(
+        &self,
+        workflow: &Workflow,
+        pools: Vec<(&str, &mut dyn Pool)>,
+        mut f: F,
+    ) -> Result<Vec<(u64, T)>, SelectItemError>
+    where
+        F: FnMut(u64, Option<(&str, &mut dyn Pool)>) -> T,
+    {
+        let mut open = pools
+            .into_iter()
+            .filter(|(name, _)| !workflow.is_final(name))
+            .collect::<Vec<_>>();
+
+        let mut targets = self
+            .ids
+            .iter()
+            .map(|&id| (id, open.iter().position(|(_, pool)| pool.get(id).is_ok())))
+            .collect::<Vec<_>>();
+
+        for (index, (_, pool)) in open.iter().enumerate() {
+            targets.extend(self.find(&**pool)?.into_iter().map(|id| (id, Some(index))));
+        }
+
+        let mut seen = HashSet::new();
+        targets.retain(|(id, _)| seen.insert(*id));
+        if targets.is_empty() {
+            return Err(SelectItemError::NotFound);
+        }
+
+        let mut results = Vec::new();
+        for (id, index) in targets {
+            let pool = match index {
+                Some(index) => {
+                    let (name, pool) = &mut open[index];
+                    Some((*name, &mut **pool as &mut dyn Pool))
+                }
+                None => None,
+            };
+            results.push((id, f(id, pool)));
+        }
+
+        Ok(results)
+    }
+}
+
+pub fn report<K, T, E, F>(results: Vec<(K, Result<T, E>)>, message: F) -> Result<(), Box<dyn Error>>
+where
+    K: Display,
+    E: Display,
+    F: Fn(&K, T) -> String,
+{
+    let total = results.len();
+    let mut failed = 0;
+
+    for (key, result) in results {
+        match result {
+            Ok(value) => println!("{}", message(&key, value)),
+            Err(err) => {
+                eprintln!("{key}: {err}");
+                failed += 1;
+            }
+        }
+    }
+
+    if total > 1 {
+        println!("{} succeeded, {failed} failed", total - failed);
+    }
+
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(Box::new(BatchError { failed, total }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::entity::{Item, State, TagSet};
+    use crate::domain::usecase::add_tag::{self, AddTagError};
+    use crate::repository::item::MemoryPool;
+
+    use super::*;
+
+    fn item(summary: &str, tags: &[&str]) -> Item {
+        let deadline =
+            NaiveDateTime::parse_from_str("2023-06-17 23:20:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let tags = tags.iter().map(|&tag| tag.to_owned()).collect::<TagSet>();
+        Item::new(summary, "", deadline, tags, 0.try_into().unwrap())
+    }
+
+    fn target(ids: Vec<u64>, with_tags: &[&str]) -> TargetArgs {
+        TargetArgs {
+            ids,
+            with_tags: with_tags.iter().map(|&tag| tag.to_owned()).collect(),
+            before: None,
+            after: None,
+        }
+    }
+
+    #[test]
+    fn it_should_resolve_ids_then_matches_without_duplicates() {
+        let mut pool = MemoryPool::new();
+        let items = [
+            item("First", &["a"]),
+            item("Second", &["a"]),
+            item("Third", &["b"]),
+        ];
+        let [first, second, third] = items.each_ref().map(Item::id);
+        for item in items {
+            assert!(pool.add(item).is_ok());
+        }
+
+        let ids = target(vec![third, first], &["a"]).resolve(&pool).unwrap();
+
+        // The matching `first` is already among the explicit IDs.
+        assert_eq!(ids, [third, first, second]);
+    }
+
+    #[test]
+    fn it_should_resolve_nothing_as_not_found() {
+        let pool = MemoryPool::new();
+
+        let res = target(Vec::new(), &["a"]).resolve(&pool);
+
+        assert!(matches!(res, Err(SelectItemError::NotFound)));
+    }
+
+    #[test]
+    fn it_should_tag_targets_in_any_open_state() {
+        let state = |name: &str, to: &[&str]| State {
+            name: name.to_owned(),
+            to: to.iter().map(|&name| name.to_owned()).collect(),
+        };
+        let workflow = Workflow::new(vec![
+            state("planned", &["review", "canceled"]),
+            state("review", &["finished"]),
+            state("finished", &[]),
+            state("canceled", &[]),
+        ])
+        .unwrap();
+
+        let [first, second, third] = [
+            item("First", &["a"]),
+            item("Second", &[]),
+            item("Third", &["a"]),
+        ];
+        let ids = [first.id(), second.id(), third.id()];
+        let mut planned = MemoryPool::new();
+        let mut review = MemoryPool::new();
+        let mut finished = MemoryPool::new();
+        let mut canceled = MemoryPool::new();
+        assert!(planned.add(first).is_ok());
+        assert!(review.add(second).is_ok());
+        assert!(finished.add(third).is_ok());
+
+        let pools: Vec<(&str, &mut dyn Pool)> = vec![
+            ("planned", &mut planned),
+            ("review", &mut review),
+            ("finished", &mut finished),
+            ("canceled", &mut canceled),
+        ];
+        let results = target(vec![ids[1], ids[2]], &["a"])
+            .apply(&workflow, pools, |id, pool| {
+                let request = add_tag::Request {
+                    id,
+                    tags: ["b"].iter().map(|&tag| tag.to_owned()).collect(),
+                };
+                match pool {
+                    Some((_, pool)) => add_tag::execute(pool, request),
+                    None => Err(AddTagError::NotFound),
+                }
+            })
+            .unwrap();
+
+        // Finished items aren't targets, even when given explicitly.
+        assert_eq!(
+            results,
+            [
+                (ids[1], Ok(())),
+                (ids[2], Err(AddTagError::NotFound)),
+                (ids[0], Ok(())),
+            ]
+        );
+        assert!(matches!(review.get(ids[1]), Ok(item) if item.tags().contains("b")));
+        assert!(matches!(planned.get(ids[0]), Ok(item) if item.tags().contains("b")));
+    }
+
+    #[test]
+    fn it_should_report_every_failure_of_a_batch() {
+        let results = vec![(1, Ok(())), (2, Err("Target isn't found")), (3, Ok(()))];
+
+        let err = report(results, |id, ()| format!("Done {id}")).unwrap_err();
+
+        assert_eq!(err.to_string(), "1 of 3 items failed");
+    }
+
+    #[test]
+    fn it_should_report_success_when_nothing_failed() {
+        let results = vec![(1, Ok::<_, &str>(())), (2, Ok(()))];
+
+        assert!(report(results, |id, ()| format!("Done {id}")).is_ok());
+    }
+}
@@ -0,0 +1,104 @@
+use std::error::Error;
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::{Local, NaiveDateTime};
+use clap::Args;
+use comfy_table::{Attribute, Cell, ContentArrangement, Table};
+use snafu::prelude::*;
+
+use crate::domain::entity::{Item, TagSet};
+use crate::domain::usecase::timesheet::{self, Request, Response};
+use crate::format::{duration, inline};
+use crate::repository::timer::LocalPool;
+use crate::repository::Repository;
+
+#[derive(Args)]
+pub struct TimesheetArgs {
+    /// A date, `today`, `yesterday` or a weekday name for the last such day
+    #[arg(short, long, default_value = "monday")]
+    since: String,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("`{value}` is not a valid day, try e.g. `monday` or `2023-06-17`"))]
+struct SinceError {
+    value: String,
+}
+
+pub fn run(
+    repo: Arc<Repository>,
+    storage: &Path,
+    args: TimesheetArgs,
+) -> Result<(), Box<dyn Error>> {
+    let timers = LocalPool::open(storage.join("timers.json"))?;
+    let now = Local::now().naive_local();
+
+    let Some(since) = inline::parse_since(&args.since, now) else {
+        let err = SinceError { value: args.since };
+        return Err(Box::new(err));
+    };
+
+    let request = Request { since, now };
+    let response = timesheet::execute(&timers, &items(&repo), request);
+
+    print(&response, since);
+    Ok(())
+}
+
+/// Items of every state, for tags of time tracked before they were closed.
+fn items(repo: &Repository) -> Vec<Item> {
+    repo.read_all(|pools, _| {
+        pools
+            .into_iter()
+            .flat_map(|(_, pool)| pool.select(TagSet::new(), None, None).unwrap_or_default())
+            .collect()
+    })
+}
+
+fn print(response: &Response, since: NaiveDateTime) {
+    if response.days.is_empty() {
+        println!("Nothing tracked since {}", since.date());
+        return;
+    }
+
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+
+    table.set_header(vec![
+        Cell::new("Day").add_attribute(Attribute::Bold),
+        Cell::new("Tag").add_attribute(Attribute::Bold),
+        Cell::new("Time").add_attribute(Attribute::Bold),
+    ]);
+
+    for (day, tags) in &response.days {
+        for (index, (tag, time)) in tags.iter().enumerate() {
+            let day = if index == 0 {
+                day.format("%Y-%m-%d %a").to_string()
+            } else {
+                String::new()
+            };
+
+            let tag = match tag {
+                Some(tag) => format!("#{tag}"),
+                None => "(untagged)".to_owned(),
+            };
+
+            table.add_row(vec![
+                Cell::new(day),
+                Cell::new(tag),
+                Cell::new(duration::render_minutes(*time)),
+            ]);
+        }
+
+        table.add_row(vec![
+            Cell::new(""),
+            Cell::new("total").add_attribute(Attribute::Bold),
+            Cell::new(duration::render_minutes(response.totals[day]))
+                .add_attribute(Attribute::Bold),
+        ]);
+    }
+
+    println!("{table}");
+    println!("Total {}", duration::render_minutes(response.total));
+}
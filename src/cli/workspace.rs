@@ -0,0 +1,65 @@
+use std::error::Error;
+
+use clap::{Args, Subcommand};
+use snafu::prelude::*;
+
+use crate::repository::workspace::Workspaces;
+
+#[derive(Args)]
+pub struct WorkspaceArgs {
+    #[command(subcommand)]
+    command: WorkspaceCommand,
+}
+
+#[derive(Subcommand)]
+enum WorkspaceCommand {
+    Create { name: String },
+    List,
+    Switch { name: String },
+    Delete { name: String },
+    Rename { name: String, new_name: String },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("Workspace `{name}` is in use"))]
+struct InUseError {
+    name: String,
+}
+
+/// `in_use` is the workspace whose pools are open, if any, which may not be deleted or
+/// renamed as they are written back on exit.
+pub fn run(
+    workspaces: &Workspaces,
+    in_use: Option<&str>,
+    args: WorkspaceArgs,
+) -> Result<(), Box<dyn Error>> {
+    let res = match args.command {
+        WorkspaceCommand::Create { name } => workspaces
+            .create(&name)
+            .map(|_| println!("Create workspace {name}")),
+        WorkspaceCommand::List => workspaces.list().map(|names| {
+            let current = workspaces.current();
+
+            for name in names {
+                let marker = if name == current { '*' } else { ' ' };
+                println!("{marker} {name}");
+            }
+        }),
+        WorkspaceCommand::Switch { name } => workspaces
+            .switch(&name)
+            .map(|()| println!("Switch to workspace {name}")),
+        WorkspaceCommand::Delete { name } | WorkspaceCommand::Rename { name, .. }
+            if in_use == Some(name.as_str()) =>
+        {
+            return Err(Box::new(InUseError { name }));
+        }
+        WorkspaceCommand::Delete { name } => workspaces
+            .delete(&name)
+            .map(|()| println!("Delete workspace {name}")),
+        WorkspaceCommand::Rename { name, new_name } => workspaces
+            .rename(&name, &new_name)
+            .map(|()| println!("Rename workspace {name} to {new_name}")),
+    };
+
+    Ok(res?)
+}
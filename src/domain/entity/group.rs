@@ -0,0 +1,29 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Group {
+    Planned,
+    Finished,
+    Canceled,
+}
+
+impl Group {
+    pub const ALL: [Group; 3] = [Group::Planned, Group::Finished, Group::Canceled];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Group::Planned => "planned",
+            Group::Finished => "finished",
+            Group::Canceled => "canceled",
+        }
+    }
+}
+
+impl Display for Group {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.name())
+    }
+}
@@ -0,0 +1,17 @@
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+/// A span of time tracked against an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Interval {
+    pub id: u64,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl Interval {
+    #[inline]
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
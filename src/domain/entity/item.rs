@@ -2,7 +2,7 @@ use std::collections::hash_map::DefaultHasher;
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 
-use chrono::NaiveDateTime;
+use chrono::{Duration, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 
 use crate::domain::entity::priority::Priority;
@@ -16,6 +16,11 @@ pub struct Item {
     deadline: NaiveDateTime,
     tags: TagSet,
     priority: Priority,
+    #[serde(default)]
+    closed: Option<NaiveDateTime>,
+    /// Expected effort in seconds.
+    #[serde(default)]
+    estimate: Option<i64>,
 }
 
 impl Item {
@@ -38,6 +43,8 @@ impl Item {
             deadline,
             tags,
             priority,
+            closed: None,
+            estimate: None,
         }
     }
 
@@ -84,7 +91,7 @@ impl Item {
 
     #[inline]
     pub fn find_tag(&self, tag: &Tag) -> bool {
-        self.tags.get(tag).is_some()
+        self.tags.contains(tag)
     }
 
     #[inline]
@@ -106,6 +113,27 @@ impl Item {
     pub fn set_priority(&mut self, priority: Priority) {
         self.priority = priority;
     }
+
+    /// When the item was finished or canceled, if it's known.
+    #[inline]
+    pub fn closed(&self) -> Option<&NaiveDateTime> {
+        self.closed.as_ref()
+    }
+
+    #[inline]
+    pub fn set_closed(&mut self, closed: Option<NaiveDateTime>) {
+        self.closed = closed;
+    }
+
+    #[inline]
+    pub fn estimate(&self) -> Option<Duration> {
+        self.estimate.map(Duration::seconds)
+    }
+
+    #[inline]
+    pub fn set_estimate(&mut self, estimate: Option<Duration>) {
+        self.estimate = estimate.map(|estimate| estimate.num_seconds());
+    }
 }
 
 impl PartialOrd for Item {
@@ -1,7 +1,13 @@
+mod group;
+mod interval;
 mod item;
 mod priority;
 mod tag;
+mod workflow;
 
+pub use group::Group;
+pub use interval::Interval;
 pub use item::Item;
 pub use priority::Priority;
 pub use tag::{Tag, TagSet};
+pub use workflow::{State, Workflow, WorkflowError};
@@ -0,0 +1,228 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+
+use super::Group;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct State {
+    pub name: String,
+    /// The states items in this one may go to. A state without any is final: items in
+    /// it are closed and can no longer be targeted by their IDs.
+    #[serde(default)]
+    pub to: Vec<String>,
+}
+
+/// The states items go through and which transitions between them are allowed. Items
+/// are added as planned, and the built-in groups must always be among the states.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Workflow {
+    states: Vec<State>,
+}
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub enum WorkflowError {
+    #[snafu(display("State `{name}` is defined more than once"))]
+    Duplicate { name: String },
+    #[snafu(display("State `{name}` is required"))]
+    Missing { name: String },
+    #[snafu(display("State `{from}` goes to unknown state `{name}`"))]
+    Unknown { from: String, name: String },
+    #[snafu(display("`{name}` is not a valid state name, use letters, digits, `-` and `_`"))]
+    Invalid { name: String },
+    #[snafu(display("`{name}` is reserved for other files next to the pools"))]
+    Reserved { name: String },
+}
+
+/// Names of the files kept next to the pools, which are stored as `{state}.json`: the
+/// workflow itself, timers, fired reminders, CalDAV links and items set aside by
+/// `doctor`.
+const RESERVED: [&str; 5] = ["states", "timers", "reminders", "caldav", "quarantine"];
+
+impl Workflow {
+    pub fn new(states: Vec<State>) -> Result<Self, WorkflowError> {
+        let mut names = HashSet::new();
+
+        for State { name, .. } in &states {
+            ensure!(valid(name), InvalidSnafu { name });
+            ensure!(!RESERVED.contains(&name.as_str()), ReservedSnafu { name });
+            ensure!(names.insert(name.as_str()), DuplicateSnafu { name });
+        }
+
+        for group in Group::ALL {
+            let name = group.name();
+            ensure!(names.contains(name), MissingSnafu { name });
+        }
+
+        for State { name: from, to } in &states {
+            if let Some(name) = to.iter().find(|name| !names.contains(name.as_str())) {
+                return UnknownSnafu { from, name }.fail();
+            }
+        }
+
+        Ok(Self { states })
+    }
+
+    pub fn states(&self) -> &[State] {
+        &self.states
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.states.iter().map(|state| state.name.as_str())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    pub fn allows(&self, from: &str, to: &str) -> bool {
+        self.get(from)
+            .is_some_and(|state| state.to.iter().any(|name| name == to))
+    }
+
+    /// Unknown states are considered final, as nothing can leave them.
+    pub fn is_final(&self, name: &str) -> bool {
+        self.get(name).is_none_or(|state| state.to.is_empty())
+    }
+
+    /// States items are still open in, in order.
+    pub fn open(&self) -> impl Iterator<Item = &str> {
+        self.names().filter(|name| !self.is_final(name))
+    }
+
+    /// The built-in group items of a state count as wherever only those are known: open
+    /// states are planned, and final ones other than finished are canceled.
+    pub fn group(&self, name: &str) -> Group {
+        if !self.is_final(name) {
+            Group::Planned
+        } else if name == Group::Finished.name() {
+            Group::Finished
+        } else {
+            Group::Canceled
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&State> {
+        self.states.iter().find(|state| state.name == name)
+    }
+}
+
+/// Planned items can be finished or canceled, as before states were configurable.
+impl Default for Workflow {
+    fn default() -> Self {
+        let state = |group: Group, to: &[Group]| State {
+            name: group.name().to_owned(),
+            to: to.iter().map(|group| group.name().to_owned()).collect(),
+        };
+
+        Self {
+            states: vec![
+                state(Group::Planned, &[Group::Finished, Group::Canceled]),
+                state(Group::Finished, &[]),
+                state(Group::Canceled, &[]),
+            ],
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Workflow {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Raw {
+            states: Vec<State>,
+        }
+
+        let Raw { states } = Raw::deserialize(deserializer)?;
+        Workflow::new(states).map_err(serde::de::Error::custom)
+    }
+}
+
+fn valid(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_accept_custom_states_with_transitions() {
+        let workflow: Workflow = serde_json::from_value(serde_json::json!({
+            "states": [
+                { "name": "planned", "to": ["in-progress", "canceled"] },
+                { "name": "in-progress", "to": ["waiting", "review"] },
+                { "name": "waiting", "to": ["in-progress"] },
+                { "name": "review", "to": ["in-progress", "finished"] },
+                { "name": "finished" },
+                { "name": "canceled" }
+            ]
+        }))
+        .unwrap();
+
+        assert!(workflow.allows("review", "finished"));
+        assert!(!workflow.allows("planned", "finished"));
+        assert!(workflow.is_final("finished"));
+        assert_eq!(
+            workflow.open().collect::<Vec<_>>(),
+            ["planned", "in-progress", "waiting", "review"]
+        );
+        assert_eq!(workflow.group("review"), Group::Planned);
+        assert_eq!(workflow.group("finished"), Group::Finished);
+    }
+
+    #[test]
+    fn it_should_reject_inconsistent_states() {
+        assert_eq!(
+            Workflow::new(vec![state("planned", &[]), state("finished", &[])]),
+            Err(WorkflowError::Missing {
+                name: "canceled".to_owned()
+            })
+        );
+        assert_eq!(
+            Workflow::new(vec![
+                state("planned", &["done"]),
+                state("finished", &[]),
+                state("canceled", &[]),
+            ]),
+            Err(WorkflowError::Unknown {
+                from: "planned".to_owned(),
+                name: "done".to_owned()
+            })
+        );
+        assert!(matches!(
+            Workflow::new(vec![state("planned", &[]), state("planned", &[])]),
+            Err(WorkflowError::Duplicate { .. })
+        ));
+    }
+
+    #[test]
+    fn it_should_reject_names_of_other_files() {
+        for name in ["states", "timers", "reminders", "caldav", "quarantine"] {
+            assert_eq!(
+                Workflow::new(vec![
+                    state("planned", &[name]),
+                    state(name, &["finished"]),
+                    state("finished", &[]),
+                    state("canceled", &[]),
+                ]),
+                Err(WorkflowError::Reserved {
+                    name: name.to_owned()
+                })
+            );
+        }
+    }
+
+    fn state(name: &str, to: &[&str]) -> State {
+        State {
+            name: name.to_owned(),
+            to: to.iter().map(|&name| name.to_owned()).collect(),
+        }
+    }
+}
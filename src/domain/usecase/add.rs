@@ -1,58 +1,71 @@
 use chrono::prelude::*;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
 use snafu::prelude::*;
 
 use crate::domain::entity::{Item, Priority, TagSet};
 use crate::repository::item::{AddError, Pool};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Request {
     pub summary: String,
+    #[serde(default)]
     pub content: String,
     pub deadline: NaiveDateTime,
+    #[serde(default)]
     pub tags: TagSet,
+    #[serde(default)]
     pub priority: i32,
+    /// Expected effort in seconds.
+    #[serde(default)]
+    pub estimate: Option<i64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Response {
     pub id: u64,
 }
 
 #[derive(Debug, PartialEq, Eq, Snafu)]
 pub enum AddItemError {
-    #[snafu(display("`summary` may not be empty and `priority` should be in [-3, 3]"))]
+    #[snafu(display(
+        "`summary` may not be empty, `priority` should be in [-3, 3] and `estimate` positive"
+    ))]
     Invalid,
     #[snafu(display("Two same items may not exist"))]
     Conflict,
 }
 
 pub fn execute(pool: &mut dyn Pool, request: Request) -> Result<Response, AddItemError> {
+    let item = prepare(request)?;
+
+    match pool.add(item) {
+        Ok(id) => Ok(Response { id }),
+        Err(AddError::Conflict) => Err(AddItemError::Conflict),
+    }
+}
+
+pub fn prepare(request: Request) -> Result<Item, AddItemError> {
     let Request {
         summary,
         content,
         deadline,
         tags,
         priority,
+        estimate,
     } = request;
     ensure!(!summary.is_empty(), InvalidSnafu);
+    ensure!(estimate.is_none_or(|estimate| estimate > 0), InvalidSnafu);
 
     let priority = match Priority::try_from(priority) {
         Ok(v) => v,
         Err(()) => return Err(AddItemError::Invalid),
     };
 
-    let res = pool.add(Item::new(
-        summary.as_str(),
-        content.as_str(),
-        deadline,
-        tags,
-        priority,
-    ));
+    let mut item = Item::new(summary.as_str(), content.as_str(), deadline, tags, priority);
 
-    match res {
-        Ok(id) => Ok(Response { id }),
-        Err(AddError::Conflict) => Err(AddItemError::Conflict),
-    }
+    item.set_estimate(estimate.map(Duration::seconds));
+    Ok(item)
 }
 
 #[cfg(test)]
@@ -74,6 +87,7 @@ mod tests {
             deadline: *item.deadline(),
             tags: item.tags().clone(),
             priority: item.priority().value(),
+            estimate: None,
         };
 
         let mut pool: Box<dyn Pool> = Box::new(MemoryPool::new());
@@ -89,6 +103,7 @@ mod tests {
             deadline: get_deadline(),
             tags: HashSet::new(),
             priority: 0i32,
+            estimate: None,
         };
 
         let mut pool: Box<dyn Pool> = Box::new(MemoryPool::new());
@@ -104,6 +119,7 @@ mod tests {
             deadline: get_deadline(),
             tags: HashSet::new(),
             priority: 10i32,
+            estimate: None,
         };
 
         let mut pool: Box<dyn Pool> = Box::new(MemoryPool::new());
@@ -119,6 +135,7 @@ mod tests {
             deadline: get_deadline(),
             tags: HashSet::new(),
             priority: 0i32,
+            estimate: None,
         };
 
         let mut pool: Box<dyn Pool> = Box::new(MemoryPool::new());
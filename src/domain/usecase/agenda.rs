@@ -0,0 +1,200 @@
+use std::cmp::Ordering;
+
+use chrono::{Days, NaiveDate, NaiveDateTime};
+
+use crate::domain::entity::{Item, TagSet};
+use crate::domain::usecase::select::{self, Request as SelectRequest, Response as SelectResponse};
+use crate::repository::item::Pool as ItemPool;
+
+pub struct Request {
+    pub now: NaiveDateTime,
+    pub days: u64,
+    pub tags: TagSet,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Bucket {
+    Overdue,
+    Day(NaiveDate),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    pub buckets: Vec<(Bucket, Vec<Item>)>,
+}
+
+/// Buckets items of the `open` pools into overdue ones and ones due on each of the
+/// `days` days starting today. Days without any item are left out.
+pub fn execute(open: &[&dyn ItemPool], request: Request) -> Response {
+    let Request { now, days, tags } = request;
+    let today = now.date();
+    let mut buckets = Vec::new();
+
+    let overdue = select(open, tags.clone(), Some(now), None)
+        .into_iter()
+        .filter(|item| item.is_expired(now))
+        .collect::<Vec<_>>();
+
+    if !overdue.is_empty() {
+        buckets.push((Bucket::Overdue, overdue));
+    }
+
+    let Some(last) = days
+        .checked_sub(1)
+        .and_then(|days| today.checked_add_days(Days::new(days)))
+    else {
+        return Response { buckets };
+    };
+
+    for item in select(
+        open,
+        tags,
+        Some(last.and_hms_opt(23, 59, 59).unwrap()),
+        Some(now),
+    ) {
+        if item.is_expired(now) {
+            continue;
+        }
+
+        let bucket = Bucket::Day(item.deadline().date());
+
+        match buckets.last_mut() {
+            Some((last, items)) if *last == bucket => items.push(item),
+            _ => buckets.push((bucket, vec![item])),
+        }
+    }
+
+    Response { buckets }
+}
+
+/// Items of every pool matching the filter, in the order a single pool keeps them.
+fn select(
+    pools: &[&dyn ItemPool],
+    tags: TagSet,
+    before: Option<NaiveDateTime>,
+    after: Option<NaiveDateTime>,
+) -> Vec<Item> {
+    let mut items = pools
+        .iter()
+        .flat_map(|&pool| {
+            let request = SelectRequest {
+                tags: tags.clone(),
+                before,
+                after,
+            };
+
+            match select::execute(pool, request) {
+                Ok(SelectResponse { items }) => items,
+                Err(_) => Vec::new(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    items.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::Duration;
+
+    use crate::repository::item::MemoryPool;
+
+    use super::*;
+
+    #[test]
+    fn it_should_bucket_items_by_day() {
+        let now = datetime("2026-10-18 12:00:00");
+        let planned = planned(&[
+            ("Overdue", "2026-10-01 10:00:00"),
+            ("Today", "2026-10-18 18:00:00"),
+            ("Tomorrow 1", "2026-10-19 09:00:00"),
+            ("Tomorrow 2", "2026-10-19 23:59:59"),
+            ("Later", "2026-10-30 09:00:00"),
+        ]);
+
+        let request = Request {
+            now,
+            days: 7,
+            tags: TagSet::new(),
+        };
+
+        let Response { buckets } = execute(&[planned.as_ref()], request);
+        let summaries = buckets
+            .iter()
+            .map(|(bucket, items)| {
+                let summaries = items.iter().map(Item::summary).collect::<Vec<_>>();
+                (*bucket, summaries)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            summaries,
+            vec![
+                (Bucket::Overdue, vec!["Overdue"]),
+                (Bucket::Day(now.date()), vec!["Today"]),
+                (
+                    Bucket::Day((now + Duration::days(1)).date()),
+                    vec!["Tomorrow 1", "Tomorrow 2"]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_respect_tag_filters() {
+        let now = datetime("2026-10-18 12:00:00");
+        let planned = planned(&[("Today", "2026-10-18 18:00:00")]);
+
+        let request = Request {
+            now,
+            days: 7,
+            tags: TagSet::from(["work".to_owned()]),
+        };
+
+        assert!(execute(&[planned.as_ref()], request).buckets.is_empty());
+    }
+
+    #[test]
+    fn it_should_merge_items_of_every_open_state() {
+        let now = datetime("2026-10-18 08:00:00");
+        let doing = planned(&[("Morning", "2026-10-18 10:00:00")]);
+        let planned = planned(&[("Evening", "2026-10-18 18:00:00")]);
+
+        let request = Request {
+            now,
+            days: 1,
+            tags: TagSet::new(),
+        };
+
+        let Response { buckets } = execute(&[planned.as_ref(), doing.as_ref()], request);
+        let summaries = buckets[0].1.iter().map(Item::summary).collect::<Vec<_>>();
+
+        assert_eq!(summaries, ["Morning", "Evening"]);
+        assert_eq!(buckets.len(), 1);
+    }
+
+    fn planned(items: &[(&str, &str)]) -> Box<dyn ItemPool> {
+        let map = items
+            .iter()
+            .map(|(summary, deadline)| {
+                let item = Item::new(
+                    summary,
+                    "",
+                    datetime(deadline),
+                    TagSet::new(),
+                    0.try_into().unwrap(),
+                );
+                (item.id(), item)
+            })
+            .collect::<HashMap<_, _>>();
+
+        Box::new(MemoryPool::from(map))
+    }
+
+    fn datetime(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+}
@@ -0,0 +1,104 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Months, NaiveDate};
+
+use crate::domain::entity::{Item, TagSet};
+use crate::domain::usecase::select::{self, Request as SelectRequest, Response as SelectResponse};
+use crate::repository::item::Pool as ItemPool;
+
+pub struct Request {
+    /// Any day of the month.
+    pub month: NaiveDate,
+    pub tags: TagSet,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+    pub days: BTreeMap<NaiveDate, Vec<Item>>,
+}
+
+/// Groups items of the `open` pools due within a month by their day.
+pub fn execute(open: &[&dyn ItemPool], request: Request) -> Response {
+    let Request { month, tags } = request;
+    let first = month.with_day(1).unwrap();
+    let last = first
+        .checked_add_months(Months::new(1))
+        .unwrap()
+        .pred_opt()
+        .unwrap();
+
+    let mut days = BTreeMap::<_, Vec<_>>::new();
+
+    for &pool in open {
+        let request = SelectRequest {
+            tags: tags.clone(),
+            before: Some(last.and_hms_opt(23, 59, 59).unwrap()),
+            after: Some(first.and_hms_opt(0, 0, 0).unwrap()),
+        };
+
+        let items = match select::execute(pool, request) {
+            Ok(SelectResponse { items }) => items,
+            Err(_) => Vec::new(),
+        };
+
+        for item in items {
+            days.entry(item.deadline().date()).or_default().push(item);
+        }
+    }
+
+    for items in days.values_mut() {
+        items.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    }
+
+    Response { first, last, days }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::NaiveDateTime;
+
+    use crate::repository::item::MemoryPool;
+
+    use super::*;
+
+    #[test]
+    fn it_should_group_items_of_the_month_by_day() {
+        let planned = pool(&[
+            "2026-10-31 23:00:00",
+            "2026-11-01 12:00:00",
+            "2026-11-30 23:59:59",
+        ]);
+        let doing = pool(&["2026-11-01 00:00:00", "2026-12-01 00:00:00"]);
+
+        let request = Request {
+            month: NaiveDate::from_ymd_opt(2026, 11, 17).unwrap(),
+            tags: TagSet::new(),
+        };
+
+        let Response { first, last, days } = execute(&[planned.as_ref(), doing.as_ref()], request);
+        assert_eq!(first, NaiveDate::from_ymd_opt(2026, 11, 1).unwrap());
+        assert_eq!(last, NaiveDate::from_ymd_opt(2026, 11, 30).unwrap());
+
+        let counts = days.values().map(Vec::len).collect::<Vec<_>>();
+        assert_eq!(counts, vec![2, 1]);
+    }
+
+    fn pool(deadlines: &[&str]) -> Box<dyn ItemPool> {
+        let map = deadlines
+            .iter()
+            .map(|deadline| {
+                let deadline =
+                    NaiveDateTime::parse_from_str(deadline, "%Y-%m-%d %H:%M:%S").unwrap();
+                let item = Item::new("Test", "", deadline, TagSet::new(), 0.try_into().unwrap());
+                (item.id(), item)
+            })
+            .collect::<HashMap<_, _>>();
+
+        Box::new(MemoryPool::from(map))
+    }
+}
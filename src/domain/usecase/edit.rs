@@ -0,0 +1,152 @@
+use snafu::prelude::*;
+
+use crate::domain::usecase::add::{self, AddItemError};
+use crate::repository::id::Pool as IdPool;
+use crate::repository::item::{AddError, Pool as ItemPool, RemoveError};
+
+pub struct Request {
+    pub id: u64,
+    pub item: add::Request,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    pub id: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub enum EditItemError {
+    #[snafu(display("`summary` may not be empty and `priority` should be in [-3, 3]"))]
+    Invalid,
+    #[snafu(display("Target isn't found"))]
+    NotFound,
+    #[snafu(display("Two same items may not exist"))]
+    Conflict,
+}
+
+/// Replaces a planned item. Since IDs are derived from the summary, the content and the
+/// deadline, the edited item may get a new ID, which is returned, and which tracked
+/// time should then be handed over to. The original item is put back if the
+/// replacement conflicts with another item.
+pub fn execute(
+    planned: &mut dyn ItemPool,
+    ids: &mut dyn IdPool,
+    request: Request,
+) -> Result<Response, EditItemError> {
+    let Request { id, item } = request;
+
+    let replacement = match add::prepare(item) {
+        Ok(item) => item,
+        Err(AddItemError::Invalid) => return Err(EditItemError::Invalid),
+        Err(AddItemError::Conflict) => return Err(EditItemError::Conflict),
+    };
+
+    let original = match planned.remove(id) {
+        Ok(item) => item,
+        Err(RemoveError::NotFound) => return Err(EditItemError::NotFound),
+    };
+
+    match planned.add(replacement) {
+        Ok(new_id) => {
+            ids.remove(id);
+            ids.add(new_id);
+            Ok(Response { id: new_id })
+        }
+        Err(AddError::Conflict) => {
+            let _ = planned.add(original);
+            Err(EditItemError::Conflict)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::domain::entity::Item;
+    use crate::repository::id::{Trie, TriePool};
+    use crate::repository::item::{GetError, MemoryPool};
+
+    use super::*;
+
+    #[test]
+    fn it_should_replace_the_item_and_its_id() {
+        let item = Item::new_test();
+        let id = item.id();
+
+        let mut map = HashMap::new();
+        let _ = map.insert(id, item.clone());
+        let mut planned: Box<dyn ItemPool> = Box::new(MemoryPool::from(map));
+
+        let mut trie = Trie::new();
+        trie.insert(id);
+        let mut ids: Box<dyn IdPool> = Box::new(TriePool::from(trie));
+
+        let request = Request {
+            id,
+            item: request(&item, "Edited"),
+        };
+
+        let Response { id: new_id } = execute(planned.as_mut(), ids.as_mut(), request).unwrap();
+
+        assert_ne!(new_id, id);
+        assert!(matches!(planned.get(id), Err(GetError::NotFound)));
+        assert!(matches!(planned.get(new_id), Ok(item) if item.summary() == "Edited"));
+        assert!(!ids.remove(id));
+        assert!(ids.remove(new_id));
+    }
+
+    #[test]
+    fn it_should_keep_the_original_item_when_the_replacement_conflicts() {
+        let item = Item::new_test();
+        let id = item.id();
+        let other = Item::new(
+            "Other",
+            item.content(),
+            *item.deadline(),
+            item.tags().clone(),
+            item.priority().clone(),
+        );
+
+        let mut map = HashMap::new();
+        let _ = map.insert(id, item.clone());
+        let _ = map.insert(other.id(), other);
+        let mut planned: Box<dyn ItemPool> = Box::new(MemoryPool::from(map));
+        let mut ids: Box<dyn IdPool> = Box::new(TriePool::new());
+
+        let request = Request {
+            id,
+            item: request(&item, "Other"),
+        };
+
+        let res = execute(planned.as_mut(), ids.as_mut(), request);
+        assert_eq!(res, Err(EditItemError::Conflict));
+        assert_eq!(planned.get(id).ok(), Some(item));
+    }
+
+    #[test]
+    fn it_should_return_not_found_error_when_the_target_does_not_exist() {
+        let item = Item::new_test();
+        let mut planned: Box<dyn ItemPool> = Box::new(MemoryPool::new());
+        let mut ids: Box<dyn IdPool> = Box::new(TriePool::new());
+
+        let request = Request {
+            id: item.id(),
+            item: request(&item, "Edited"),
+        };
+
+        let res = execute(planned.as_mut(), ids.as_mut(), request);
+        assert_eq!(res, Err(EditItemError::NotFound));
+    }
+
+    fn request(item: &Item, summary: &str) -> add::Request {
+        add::Request {
+            summary: summary.to_owned(),
+            content: item.content().to_owned(),
+            deadline: *item.deadline(),
+            tags: item.tags().clone(),
+            priority: item.priority().value(),
+            estimate: None,
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::NaiveDateTime;
+use chrono::{Duration, NaiveDateTime};
 use snafu::prelude::*;
 
 use crate::domain::entity::{Priority, TagSet};
@@ -16,6 +16,8 @@ pub struct Response {
     pub deadline: NaiveDateTime,
     pub tags: TagSet,
     pub priority: Priority,
+    pub closed: Option<NaiveDateTime>,
+    pub estimate: Option<Duration>,
 }
 
 #[derive(Debug, PartialEq, Eq, Snafu)]
@@ -33,6 +35,8 @@ pub fn execute(pool: &dyn Pool, request: Request) -> Result<Response, GetItemErr
             deadline: *item.deadline(),
             tags: item.tags().clone(),
             priority: item.priority().clone(),
+            closed: item.closed().copied(),
+            estimate: item.estimate(),
         }),
         Err(GetError::NotFound) => Err(GetItemError::NotFound),
     }
@@ -66,6 +70,8 @@ mod tests {
             deadline: *item.deadline(),
             tags: item.tags().clone(),
             priority: item.priority().clone(),
+            closed: None,
+            estimate: None,
         };
 
         assert_eq!(res, Ok(response.clone()));
@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+
+use chrono::NaiveDateTime;
+
+use crate::domain::entity::Group;
+use crate::domain::usecase::add::{self, AddItemError};
+use crate::domain::usecase::add_id::{self, Request as AddIdRequest};
+use crate::repository::id::Pool as IdPool;
+use crate::repository::item::{AddError, Pool as ItemPool};
+
+pub type ImportItemError = AddItemError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub group: Group,
+    pub request: add::Request,
+    /// When the item was closed, if it's finished or canceled and that's known.
+    pub closed: Option<NaiveDateTime>,
+}
+
+pub struct Request {
+    pub entries: Vec<Entry>,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    pub results: Vec<Result<u64, ImportItemError>>,
+}
+
+pub fn execute(
+    planned: &mut dyn ItemPool,
+    finished: &mut dyn ItemPool,
+    canceled: &mut dyn ItemPool,
+    ids: &mut dyn IdPool,
+    request: Request,
+) -> Response {
+    let mut staged = HashSet::new();
+
+    let results = request
+        .entries
+        .into_iter()
+        .map(
+            |Entry {
+                 group,
+                 request: add_request,
+                 closed,
+             }| {
+                let pool: &mut dyn ItemPool = match group {
+                    Group::Planned => &mut *planned,
+                    Group::Finished => &mut *finished,
+                    Group::Canceled => &mut *canceled,
+                };
+
+                if request.dry_run {
+                    let id = add::prepare(add_request)?.id();
+
+                    if pool.get(id).is_ok() || !staged.insert((group, id)) {
+                        Err(ImportItemError::Conflict)
+                    } else {
+                        Ok(id)
+                    }
+                } else {
+                    let mut item = add::prepare(add_request)?;
+                    if group != Group::Planned {
+                        item.set_closed(closed);
+                    }
+                    let id = match pool.add(item) {
+                        Ok(id) => id,
+                        Err(AddError::Conflict) => return Err(ImportItemError::Conflict),
+                    };
+
+                    if group == Group::Planned {
+                        let _ = add_id::execute(&mut *ids, AddIdRequest { id });
+                    }
+
+                    Ok(id)
+                }
+            },
+        )
+        .collect();
+
+    Response { results }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use crate::domain::entity::TagSet;
+    use crate::repository::id::TriePool;
+    use crate::repository::item::MemoryPool;
+
+    use super::*;
+
+    #[test]
+    fn it_should_add_items_into_pools_of_their_groups() {
+        let mut planned: Box<dyn ItemPool> = Box::new(MemoryPool::new());
+        let mut finished: Box<dyn ItemPool> = Box::new(MemoryPool::new());
+        let mut canceled: Box<dyn ItemPool> = Box::new(MemoryPool::new());
+        let mut ids: Box<dyn IdPool> = Box::new(TriePool::new());
+
+        let closed =
+            NaiveDateTime::parse_from_str("2023-06-16 08:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let mut finished_entry = new(Group::Finished, "2");
+        finished_entry.closed = Some(closed);
+        let request = Request {
+            entries: vec![new(Group::Planned, "1"), finished_entry],
+            dry_run: false,
+        };
+
+        let response = execute(
+            planned.as_mut(),
+            finished.as_mut(),
+            canceled.as_mut(),
+            ids.as_mut(),
+            request,
+        );
+
+        let planned_id = *response.results[0].as_ref().unwrap();
+        let finished_id = *response.results[1].as_ref().unwrap();
+        assert!(planned.get(planned_id).is_ok());
+        assert!(matches!(finished.get(finished_id), Ok(item) if item.closed() == Some(&closed)));
+        assert!(ids.remove(planned_id));
+        assert!(!ids.remove(finished_id));
+    }
+
+    #[test]
+    fn it_should_report_conflicts_and_invalid_entries() {
+        let mut planned: Box<dyn ItemPool> = Box::new(MemoryPool::new());
+        let mut finished: Box<dyn ItemPool> = Box::new(MemoryPool::new());
+        let mut canceled: Box<dyn ItemPool> = Box::new(MemoryPool::new());
+        let mut ids: Box<dyn IdPool> = Box::new(TriePool::new());
+
+        let request = Request {
+            entries: vec![
+                new(Group::Planned, "1"),
+                new(Group::Planned, "1"),
+                new(Group::Canceled, ""),
+            ],
+            dry_run: false,
+        };
+
+        let response = execute(
+            planned.as_mut(),
+            finished.as_mut(),
+            canceled.as_mut(),
+            ids.as_mut(),
+            request,
+        );
+
+        assert!(response.results[0].is_ok());
+        assert_eq!(response.results[1], Err(ImportItemError::Conflict));
+        assert_eq!(response.results[2], Err(ImportItemError::Invalid));
+    }
+
+    #[test]
+    fn it_should_leave_pools_untouched_but_report_conflicts_when_dry_running() {
+        let mut planned: Box<dyn ItemPool> = Box::new(MemoryPool::new());
+        let mut finished: Box<dyn ItemPool> = Box::new(MemoryPool::new());
+        let mut canceled: Box<dyn ItemPool> = Box::new(MemoryPool::new());
+        let mut ids: Box<dyn IdPool> = Box::new(TriePool::new());
+
+        let request = Request {
+            entries: vec![
+                new(Group::Planned, "1"),
+                new(Group::Planned, "1"),
+                new(Group::Finished, "1"),
+            ],
+            dry_run: true,
+        };
+
+        let response = execute(
+            planned.as_mut(),
+            finished.as_mut(),
+            canceled.as_mut(),
+            ids.as_mut(),
+            request,
+        );
+
+        let id = *response.results[0].as_ref().unwrap();
+        assert_eq!(response.results[1], Err(ImportItemError::Conflict));
+        assert_eq!(response.results[2], Ok(id));
+        assert!(planned.get(id).is_err());
+        assert!(finished.get(id).is_err());
+        assert!(!ids.remove(id));
+    }
+
+    fn new(group: Group, summary: &str) -> Entry {
+        Entry {
+            group,
+            request: add::Request {
+                summary: summary.to_owned(),
+                content: String::new(),
+                deadline: NaiveDateTime::parse_from_str("2023-06-17 23:20:00", "%Y-%m-%d %H:%M:%S")
+                    .unwrap(),
+                tags: TagSet::new(),
+                priority: 0,
+                estimate: None,
+            },
+            closed: None,
+        }
+    }
+}
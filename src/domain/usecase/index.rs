@@ -0,0 +1,38 @@
+use crate::domain::entity::TagSet;
+use crate::repository::id::Pool as IdPool;
+use crate::repository::item::Pool as ItemPool;
+
+/// Registers the IDs of all items in `pool`, so that they can be completed from their
+/// prefixes.
+pub fn execute(pool: &dyn ItemPool, ids: &mut dyn IdPool) {
+    if let Ok(items) = pool.select(TagSet::new(), None, None) {
+        for item in items {
+            ids.add(item.id());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::domain::entity::Item;
+    use crate::repository::id::TriePool;
+    use crate::repository::item::MemoryPool;
+
+    use super::*;
+
+    #[test]
+    fn it_should_register_ids_of_all_items() {
+        let item = Item::new_test();
+        let id = item.id();
+
+        let mut map = HashMap::new();
+        let _ = map.insert(id, item);
+        let pool: Box<dyn ItemPool> = Box::new(MemoryPool::from(map));
+        let mut ids: Box<dyn IdPool> = Box::new(TriePool::new());
+
+        execute(pool.as_ref(), ids.as_mut());
+        assert_eq!(ids.find(id), Some(vec![id]));
+    }
+}
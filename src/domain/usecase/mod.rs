@@ -1,14 +1,25 @@
 mod add;
-mod remove;
 
 pub mod add_tag;
+pub mod agenda;
+pub mod calendar;
 pub mod clean;
+pub mod edit;
 pub mod get;
+pub mod import;
+pub mod index;
 pub mod plan;
+pub mod relocate;
+pub mod remind;
 pub mod remove_tag;
 pub mod select;
 pub mod set_priority;
-pub mod transfer;
+pub mod shift;
+pub mod start;
+pub mod stats;
+pub mod stop;
+pub mod timesheet;
+pub mod transition;
 
 pub mod add_id;
 pub mod complete_id;
@@ -1,3 +1,4 @@
+use crate::domain::entity::Item;
 use crate::domain::usecase::add;
 use crate::domain::usecase::add_id::{self, Request as AddIdRequest};
 use crate::repository::id::Pool as IdPool;
@@ -17,9 +18,14 @@ pub fn execute(
     Ok(response)
 }
 
+/// Validates the request the same way `execute` does and returns the item it would
+/// plan, without touching any pool.
+pub fn preview(request: Request) -> Result<Item, PlanError> {
+    add::prepare(request)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::domain::entity::Item;
     use crate::repository::id::TriePool;
     use crate::repository::item::MemoryPool;
 
@@ -36,6 +42,7 @@ mod tests {
             deadline: *item.deadline(),
             tags: item.tags().clone(),
             priority: item.priority().value(),
+            estimate: None,
         };
 
         let mut planned: Box<dyn ItemPool> = Box::new(MemoryPool::new());
@@ -44,4 +51,26 @@ mod tests {
         assert_eq!(res, Ok(Response { id }));
         assert!(ids.remove(res.unwrap().id));
     }
+
+    #[test]
+    fn it_should_return_the_item_to_be_planned_when_previewing() {
+        let item = Item::new_test();
+
+        let request = Request {
+            summary: item.summary().to_owned(),
+            content: item.content().to_owned(),
+            deadline: *item.deadline(),
+            tags: item.tags().clone(),
+            priority: item.priority().value(),
+            estimate: None,
+        };
+
+        assert_eq!(preview(request.clone()), Ok(item));
+
+        let request = Request {
+            priority: 4,
+            ..request
+        };
+        assert_eq!(preview(request), Err(PlanError::Invalid));
+    }
 }
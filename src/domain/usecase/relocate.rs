@@ -0,0 +1,116 @@
+use snafu::prelude::*;
+
+use crate::repository::id::Pool as IdPool;
+use crate::repository::item::{AddError, Pool as ItemPool, RemoveError};
+
+pub struct Request {
+    pub id: u64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    pub id: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub enum RelocateError {
+    #[snafu(display("Target isn't found"))]
+    NotFound,
+    #[snafu(display("The same item already exists in the destination"))]
+    Conflict,
+}
+
+/// Moves a planned item into the planned pool of another repository, such as another
+/// workspace. The item is put back if the destination already has it.
+pub fn execute(
+    source: &mut dyn ItemPool,
+    source_ids: &mut dyn IdPool,
+    destination: &mut dyn ItemPool,
+    destination_ids: &mut dyn IdPool,
+    request: Request,
+) -> Result<Response, RelocateError> {
+    let id = request.id;
+
+    let item = match source.remove(id) {
+        Ok(item) => item,
+        Err(RemoveError::NotFound) => return Err(RelocateError::NotFound),
+    };
+
+    match destination.add(item.clone()) {
+        Ok(id) => {
+            source_ids.remove(id);
+            destination_ids.add(id);
+            Ok(Response { id })
+        }
+        Err(AddError::Conflict) => {
+            let _ = source.add(item);
+            Err(RelocateError::Conflict)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::domain::entity::Item;
+    use crate::repository::id::{Trie, TriePool};
+    use crate::repository::item::MemoryPool;
+
+    use super::*;
+
+    #[test]
+    fn it_should_move_the_item_and_its_id() {
+        let item = Item::new_test();
+        let id = item.id();
+
+        let mut map = HashMap::new();
+        let _ = map.insert(id, item.clone());
+        let mut source: Box<dyn ItemPool> = Box::new(MemoryPool::from(map));
+
+        let mut trie = Trie::new();
+        trie.insert(id);
+        let mut source_ids: Box<dyn IdPool> = Box::new(TriePool::from(trie));
+
+        let mut destination: Box<dyn ItemPool> = Box::new(MemoryPool::new());
+        let mut destination_ids: Box<dyn IdPool> = Box::new(TriePool::new());
+
+        let res = execute(
+            source.as_mut(),
+            source_ids.as_mut(),
+            destination.as_mut(),
+            destination_ids.as_mut(),
+            Request { id },
+        );
+
+        assert_eq!(res, Ok(Response { id }));
+        assert!(source.get(id).is_err());
+        assert_eq!(destination.get(id).ok(), Some(item));
+        assert!(!source_ids.remove(id));
+        assert!(destination_ids.remove(id));
+    }
+
+    #[test]
+    fn it_should_keep_the_item_when_the_destination_has_it() {
+        let item = Item::new_test();
+        let id = item.id();
+
+        let mut map = HashMap::new();
+        let _ = map.insert(id, item.clone());
+        let mut source: Box<dyn ItemPool> = Box::new(MemoryPool::from(map.clone()));
+        let mut destination: Box<dyn ItemPool> = Box::new(MemoryPool::from(map));
+        let mut source_ids: Box<dyn IdPool> = Box::new(TriePool::new());
+        let mut destination_ids: Box<dyn IdPool> = Box::new(TriePool::new());
+
+        let res = execute(
+            source.as_mut(),
+            source_ids.as_mut(),
+            destination.as_mut(),
+            destination_ids.as_mut(),
+            Request { id },
+        );
+
+        assert_eq!(res, Err(RelocateError::Conflict));
+        assert_eq!(source.get(id).ok(), Some(item));
+    }
+}
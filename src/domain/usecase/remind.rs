@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+
+use chrono::{Duration, NaiveDateTime};
+
+use crate::domain::entity::TagSet;
+use crate::repository::item::Pool as ItemPool;
+use crate::repository::reminder::Pool as ReminderPool;
+
+pub struct Request {
+    pub now: NaiveDateTime,
+    pub leads: Vec<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reminder {
+    pub id: u64,
+    pub summary: String,
+    pub deadline: NaiveDateTime,
+    pub lead: Duration,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    pub reminders: Vec<Reminder>,
+}
+
+/// Finds items of the `open` pools whose deadline is within a lead time from now, unless
+/// that reminder was fired already, see [`fire`]. When several lead times have passed at
+/// once, only the shortest one is returned. Expired items are left to listing, and
+/// reminders of items which are no longer open are forgotten.
+pub fn execute(open: &[&dyn ItemPool], fired: &mut dyn ReminderPool, request: Request) -> Response {
+    let Request { now, mut leads } = request;
+    leads.sort_unstable();
+
+    let items = open
+        .iter()
+        .flat_map(|pool| pool.select(TagSet::new(), None, None).unwrap_or_default())
+        .collect::<Vec<_>>();
+
+    fired.retain(&items.iter().map(|item| item.id()).collect::<HashSet<_>>());
+
+    let mut reminders = items
+        .into_iter()
+        .filter(|item| !item.is_expired(now))
+        .filter_map(|item| {
+            let lead = *leads.iter().find(|&&lead| *item.deadline() - lead <= now)?;
+
+            (!fired.contains(item.id(), lead)).then(|| Reminder {
+                id: item.id(),
+                summary: item.summary().to_owned(),
+                deadline: *item.deadline(),
+                lead,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    reminders.sort_unstable_by_key(|reminder| (reminder.deadline, reminder.id));
+    Response { reminders }
+}
+
+/// Marks `reminder` as fired once it's delivered, along with the longer ones of `leads`
+/// which passed with it, so that it's only returned once.
+pub fn fire(fired: &mut dyn ReminderPool, reminder: &Reminder, leads: &[Duration]) {
+    for &lead in leads.iter().filter(|&&lead| lead >= reminder.lead) {
+        fired.add(reminder.id, lead);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::domain::entity::Item;
+    use crate::repository::item::MemoryPool as ItemMemoryPool;
+    use crate::repository::reminder::MemoryPool as ReminderMemoryPool;
+
+    use super::*;
+
+    #[test]
+    fn it_should_fire_each_reminder_once() {
+        let item = Item::new_test();
+        let planned = planned(item.clone());
+        let mut fired: Box<dyn ReminderPool> = Box::new(ReminderMemoryPool::new());
+
+        let now = *item.deadline() - Duration::minutes(30);
+        let response = execute(&[planned.as_ref()], fired.as_mut(), request(now));
+
+        assert_eq!(
+            response.reminders,
+            vec![Reminder {
+                id: item.id(),
+                summary: item.summary().to_owned(),
+                deadline: *item.deadline(),
+                lead: Duration::hours(1),
+            }]
+        );
+
+        // Until it's delivered, the reminder is due again.
+        let response = execute(&[planned.as_ref()], fired.as_mut(), request(now));
+        assert_eq!(response.reminders.len(), 1);
+
+        fire(fired.as_mut(), &response.reminders[0], &request(now).leads);
+        let response = execute(&[planned.as_ref()], fired.as_mut(), request(now));
+        assert!(response.reminders.is_empty());
+        assert!(fired.contains(item.id(), Duration::days(1)));
+    }
+
+    #[test]
+    fn it_should_fire_nothing_before_the_longest_lead_or_after_the_deadline() {
+        let item = Item::new_test();
+        let planned = planned(item.clone());
+        let mut fired: Box<dyn ReminderPool> = Box::new(ReminderMemoryPool::new());
+
+        let now = *item.deadline() - Duration::days(2);
+        let response = execute(&[planned.as_ref()], fired.as_mut(), request(now));
+        assert!(response.reminders.is_empty());
+
+        let now = *item.deadline();
+        let response = execute(&[planned.as_ref()], fired.as_mut(), request(now));
+        assert!(response.reminders.is_empty());
+    }
+
+    #[test]
+    fn it_should_forget_reminders_of_items_no_longer_planned() {
+        let planned: Box<dyn ItemPool> = Box::new(ItemMemoryPool::new());
+        let mut fired: Box<dyn ReminderPool> = Box::new(ReminderMemoryPool::new());
+        fired.add(1, Duration::hours(1));
+
+        let now = Item::new_test().deadline().to_owned();
+        let _ = execute(&[planned.as_ref()], fired.as_mut(), request(now));
+        assert!(!fired.contains(1, Duration::hours(1)));
+    }
+
+    #[test]
+    fn it_should_remind_of_items_in_every_open_state() {
+        let item = Item::new_test();
+        let doing = planned(item.clone());
+        let planned: Box<dyn ItemPool> = Box::new(ItemMemoryPool::new());
+        let mut fired: Box<dyn ReminderPool> = Box::new(ReminderMemoryPool::new());
+
+        let now = *item.deadline() - Duration::minutes(30);
+        let open = [planned.as_ref(), doing.as_ref()];
+        let response = execute(&open, fired.as_mut(), request(now));
+
+        assert_eq!(response.reminders.len(), 1);
+        assert_eq!(response.reminders[0].id, item.id());
+    }
+
+    fn planned(item: Item) -> Box<dyn ItemPool> {
+        let mut map = HashMap::new();
+        let _ = map.insert(item.id(), item);
+        Box::new(ItemMemoryPool::from(map))
+    }
+
+    fn request(now: NaiveDateTime) -> Request {
+        Request {
+            now,
+            leads: vec![Duration::hours(1), Duration::days(1)],
+        }
+    }
+}
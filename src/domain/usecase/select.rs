@@ -1,4 +1,5 @@
 use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
 use snafu::prelude::*;
 
 use crate::domain::entity::{Item, TagSet};
@@ -10,7 +11,7 @@ pub struct Request {
     pub after: Option<NaiveDateTime>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Response {
     pub items: Vec<Item>,
 }
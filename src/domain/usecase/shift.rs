@@ -0,0 +1,140 @@
+use chrono::NaiveDateTime;
+use snafu::prelude::*;
+
+use crate::domain::entity::Workflow;
+use crate::repository::id::Pool as IdPool;
+use crate::repository::item::Pool as ItemPool;
+
+use super::transition::{self, TransitionError};
+
+pub struct Request<'a> {
+    pub id: u64,
+    pub from: &'a str,
+    pub to: &'a str,
+    /// When the item is closed, if `to` is a final state.
+    pub time: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+pub enum ShiftError {
+    #[snafu(display("Target isn't found"))]
+    NotFound,
+    #[snafu(display("Item is {state} already"))]
+    Already { state: String },
+    #[snafu(display("Items can't go from {from} to {to}"))]
+    NotAllowed { from: String, to: String },
+    #[snafu(display("{source}"))]
+    Transition { source: TransitionError },
+}
+
+/// Whether `workflow` lets items go from the state `from` to `to`. Callers check it
+/// before taking the pools of both states, which must differ.
+pub fn check(workflow: &Workflow, from: &str, to: &str) -> Result<(), ShiftError> {
+    ensure!(from != to, AlreadySnafu { state: to });
+    ensure!(workflow.allows(from, to), NotAllowedSnafu { from, to });
+    Ok(())
+}
+
+/// Moves an item from the pool of the state `from` to the one of `to`, as far as
+/// `workflow` allows it. Items moved to a final state are closed.
+pub fn execute(
+    workflow: &Workflow,
+    source: &mut dyn ItemPool,
+    destination: &mut dyn ItemPool,
+    ids: &mut dyn IdPool,
+    request: Request,
+) -> Result<(), ShiftError> {
+    let Request { id, from, to, time } = request;
+    check(workflow, from, to)?;
+
+    let request = transition::Request {
+        id,
+        closed: workflow.is_final(to).then_some(time),
+    };
+    transition::execute(source, destination, ids, request).context(TransitionSnafu)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::entity::{Item, State};
+    use crate::repository::id::TriePool;
+    use crate::repository::item::MemoryPool;
+
+    use super::*;
+
+    fn workflow() -> Workflow {
+        let state = |name: &str, to: &[&str]| State {
+            name: name.to_owned(),
+            to: to.iter().map(|&name| name.to_owned()).collect(),
+        };
+
+        Workflow::new(vec![
+            state("planned", &["review", "canceled"]),
+            state("review", &["finished"]),
+            state("finished", &[]),
+            state("canceled", &[]),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn it_should_only_take_allowed_transitions() {
+        let workflow = workflow();
+        let item = Item::new_test();
+        let id = item.id();
+        let time = *item.deadline();
+
+        let mut planned = MemoryPool::new();
+        let mut review = MemoryPool::new();
+        let mut finished = MemoryPool::new();
+        let mut ids = TriePool::new();
+        assert!(planned.add(item).is_ok());
+
+        let request = |from, to| Request { id, from, to, time };
+        let skipped = execute(
+            &workflow,
+            &mut planned,
+            &mut finished,
+            &mut ids,
+            request("planned", "finished"),
+        );
+        assert_eq!(
+            skipped,
+            Err(ShiftError::NotAllowed {
+                from: "planned".to_owned(),
+                to: "finished".to_owned(),
+            })
+        );
+        assert!(planned.get(id).is_ok());
+
+        let res = execute(
+            &workflow,
+            &mut planned,
+            &mut review,
+            &mut ids,
+            request("planned", "review"),
+        );
+        assert_eq!(res, Ok(()));
+        assert!(matches!(review.get(id), Ok(item) if item.closed().is_none()));
+
+        let res = execute(
+            &workflow,
+            &mut review,
+            &mut finished,
+            &mut ids,
+            request("review", "finished"),
+        );
+        assert_eq!(res, Ok(()));
+        assert!(matches!(finished.get(id), Ok(item) if item.closed() == Some(&time)));
+    }
+
+    #[test]
+    fn it_should_refuse_to_stay_in_the_same_state() {
+        assert_eq!(
+            check(&workflow(), "review", "review"),
+            Err(ShiftError::Already {
+                state: "review".to_owned()
+            })
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use chrono::{NaiveDateTime, Timelike};
+use snafu::prelude::*;
+
+use crate::repository::item::Pool as ItemPool;
+use crate::repository::timer::Pool as TimerPool;
+
+pub struct Request {
+    pub id: u64,
+    pub time: NaiveDateTime,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    pub id: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub enum StartError {
+    #[snafu(display("Target isn't found"))]
+    NotFound,
+    #[snafu(display("The timer of {id} is running, stop it first"))]
+    Running { id: u64 },
+}
+
+/// Starts the timer of an item in `pool`. Only one timer may run at a time.
+pub fn execute(
+    pool: &dyn ItemPool,
+    timers: &mut dyn TimerPool,
+    request: Request,
+) -> Result<Response, StartError> {
+    let Request { id, time } = request;
+
+    if let Some((id, _)) = timers.active() {
+        return RunningSnafu { id }.fail();
+    }
+
+    ensure!(pool.get(id).is_ok(), NotFoundSnafu);
+
+    // Tracked times are kept to the second, like deadlines.
+    timers.set_active(Some((id, time.with_nanosecond(0).unwrap_or(time))));
+    Ok(Response { id })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::domain::entity::Item;
+    use crate::repository::item::MemoryPool;
+    use crate::repository::timer::MemoryPool as MemoryTimerPool;
+
+    use super::*;
+
+    #[test]
+    fn it_should_allow_only_one_running_timer() {
+        let item = Item::new_test();
+        let id = item.id();
+        let time = *item.deadline();
+
+        let mut map = HashMap::new();
+        let _ = map.insert(id, item);
+        let pool: Box<dyn ItemPool> = Box::new(MemoryPool::from(map));
+        let mut timers: Box<dyn TimerPool> = Box::new(MemoryTimerPool::new());
+
+        let res = execute(pool.as_ref(), timers.as_mut(), Request { id, time });
+        assert_eq!(res, Ok(Response { id }));
+        assert_eq!(timers.active(), Some((id, time)));
+
+        let res = execute(pool.as_ref(), timers.as_mut(), Request { id, time });
+        assert_eq!(res, Err(StartError::Running { id }));
+    }
+
+    #[test]
+    fn it_should_return_not_found_error_when_the_target_does_not_exist() {
+        let pool: Box<dyn ItemPool> = Box::new(MemoryPool::new());
+        let mut timers: Box<dyn TimerPool> = Box::new(MemoryTimerPool::new());
+        let time = *Item::new_test().deadline();
+
+        let res = execute(pool.as_ref(), timers.as_mut(), Request { id: 0, time });
+        assert_eq!(res, Err(StartError::NotFound));
+        assert_eq!(timers.active(), None);
+    }
+}
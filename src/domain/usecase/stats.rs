@@ -0,0 +1,350 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+
+use crate::domain::entity::{Group, Item, Tag, TagSet, Workflow};
+use crate::repository::item::Pool as ItemPool;
+
+pub struct Request {
+    pub now: NaiveDateTime,
+    /// Only items in final states closed since then are counted, which leaves out items
+    /// closed before closing times were recorded.
+    pub since: Option<NaiveDateTime>,
+}
+
+/// Items by the built-in group their state counts as.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Counts {
+    pub planned: usize,
+    pub finished: usize,
+    pub canceled: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Bucket {
+    pub start: NaiveDate,
+    pub count: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Response {
+    pub total: Counts,
+    pub overdue: usize,
+    /// Finished items among closed ones.
+    pub completion_ratio: Option<f64>,
+    /// How long after their deadline items are finished on average, in seconds, which
+    /// is negative when they're finished early.
+    pub average_lateness: Option<i64>,
+    pub finished_per_day: Vec<Bucket>,
+    pub finished_per_week: Vec<Bucket>,
+    pub tags: BTreeMap<Tag, Counts>,
+    pub priorities: BTreeMap<i32, Counts>,
+}
+
+/// Summarizes the pools of all states, which count towards the built-in groups as
+/// [`Workflow::group`] maps them.
+pub fn execute(
+    workflow: &Workflow,
+    pools: Vec<(&str, &dyn ItemPool)>,
+    request: Request,
+) -> Response {
+    let Request { now, since } = request;
+
+    let closed = |pool: &dyn ItemPool| {
+        all(pool)
+            .into_iter()
+            .filter(|item| match (since, item.closed()) {
+                (None, _) => true,
+                (Some(since), Some(closed)) => *closed >= since,
+                (Some(_), None) => false,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let groups = pools
+        .into_iter()
+        .map(|(name, pool)| match workflow.group(name) {
+            Group::Planned => (Group::Planned, all(pool)),
+            group => (group, closed(pool)),
+        })
+        .collect::<Vec<_>>();
+
+    let mut total = Counts::default();
+    let mut tags = BTreeMap::<Tag, Counts>::new();
+    let mut priorities = BTreeMap::<i32, Counts>::new();
+
+    for (group, items) in &groups {
+        for item in items {
+            total.add(*group);
+            priorities
+                .entry(item.priority().value())
+                .or_default()
+                .add(*group);
+
+            for tag in item.tags() {
+                tags.entry(tag.clone()).or_default().add(*group);
+            }
+        }
+    }
+
+    let items = |group| {
+        groups
+            .iter()
+            .filter(move |(other, _)| *other == group)
+            .flat_map(|(_, items)| items)
+    };
+    let overdue = items(Group::Planned)
+        .filter(|item| item.is_expired(now))
+        .count();
+
+    let closed_count = total.finished + total.canceled;
+    let completion_ratio = (closed_count > 0).then(|| total.finished as f64 / closed_count as f64);
+
+    let lateness = items(Group::Finished)
+        .filter_map(|item| Some((*item.closed()? - *item.deadline()).num_seconds()))
+        .collect::<Vec<_>>();
+    let average_lateness =
+        (!lateness.is_empty()).then(|| lateness.iter().sum::<i64>() / lateness.len() as i64);
+
+    let dates = items(Group::Finished)
+        .filter_map(|item| item.closed().map(NaiveDateTime::date))
+        .collect::<Vec<_>>();
+
+    let today = now.date();
+    let first = since
+        .map(|since| since.date())
+        .or_else(|| dates.iter().min().copied())
+        .unwrap_or(today);
+
+    let finished_per_day = buckets(&dates, first, today, |date| date);
+    let finished_per_week = buckets(&dates, first, today, |date| {
+        date - Days::new(date.weekday().num_days_from_monday().into())
+    });
+
+    Response {
+        total,
+        overdue,
+        completion_ratio,
+        average_lateness,
+        finished_per_day,
+        finished_per_week,
+        tags,
+        priorities,
+    }
+}
+
+impl Counts {
+    fn add(&mut self, group: Group) {
+        match group {
+            Group::Planned => self.planned += 1,
+            Group::Finished => self.finished += 1,
+            Group::Canceled => self.canceled += 1,
+        }
+    }
+}
+
+fn all(pool: &dyn ItemPool) -> Vec<Item> {
+    pool.select(TagSet::new(), None, None).unwrap_or_default()
+}
+
+/// Counts dates into consecutive buckets from `first` to `last`, each starting at the
+/// date `start` maps its dates to.
+fn buckets<F>(dates: &[NaiveDate], first: NaiveDate, last: NaiveDate, start: F) -> Vec<Bucket>
+where
+    F: Fn(NaiveDate) -> NaiveDate,
+{
+    let mut buckets = Vec::<Bucket>::new();
+
+    for date in first.iter_days().take_while(|date| *date <= last) {
+        let start = start(date);
+
+        if buckets.last().map(|bucket| bucket.start) != Some(start) {
+            buckets.push(Bucket { start, count: 0 });
+        }
+    }
+
+    for date in dates {
+        let start = start(*date);
+
+        if let Some(bucket) = buckets.iter_mut().find(|bucket| bucket.start == start) {
+            bucket.count += 1;
+        }
+    }
+
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::Duration;
+
+    use crate::domain::entity::State;
+    use crate::repository::item::MemoryPool;
+
+    use super::*;
+
+    #[test]
+    fn it_should_summarize_all_pools() {
+        let now = datetime("2026-10-18 12:00:00");
+
+        let planned = pool(vec![
+            item("Overdue", "2026-10-01 10:00:00", &["a"], 1, None),
+            item("Later", "2026-10-30 10:00:00", &[], 0, None),
+        ]);
+        let finished = pool(vec![
+            item(
+                "Late",
+                "2026-10-16 10:00:00",
+                &["a"],
+                1,
+                Some("2026-10-17 10:00:00"),
+            ),
+            item(
+                "Early",
+                "2026-10-18 10:00:00",
+                &[],
+                0,
+                Some("2026-10-17 12:00:00"),
+            ),
+            item("Old", "2026-09-01 10:00:00", &[], 0, None),
+        ]);
+        let canceled = pool(vec![item(
+            "Dropped",
+            "2026-10-20 10:00:00",
+            &["a"],
+            0,
+            Some("2026-10-18 09:00:00"),
+        )]);
+
+        let request = Request {
+            now,
+            since: Some(now - Duration::days(3)),
+        };
+
+        let pools = vec![
+            ("planned", planned.as_ref()),
+            ("finished", finished.as_ref()),
+            ("canceled", canceled.as_ref()),
+        ];
+        let response = execute(&Workflow::default(), pools, request);
+
+        assert_eq!(
+            response.total,
+            Counts {
+                planned: 2,
+                finished: 2,
+                canceled: 1,
+            }
+        );
+        assert_eq!(response.overdue, 1);
+        assert_eq!(response.completion_ratio, Some(2.0 / 3.0));
+        assert_eq!(response.average_lateness, Some(3600));
+        assert_eq!(
+            response
+                .finished_per_day
+                .iter()
+                .map(|bucket| bucket.count)
+                .collect::<Vec<_>>(),
+            vec![0, 0, 2, 0]
+        );
+        assert_eq!(
+            response.finished_per_week,
+            vec![Bucket {
+                start: NaiveDate::from_ymd_opt(2026, 10, 12).unwrap(),
+                count: 2,
+            }]
+        );
+        assert_eq!(
+            response.tags["a"],
+            Counts {
+                planned: 1,
+                finished: 1,
+                canceled: 1,
+            }
+        );
+        assert_eq!(response.priorities[&0].finished, 1);
+    }
+
+    #[test]
+    fn it_should_count_custom_states_towards_the_built_in_groups() {
+        let now = datetime("2026-10-18 12:00:00");
+        let state = |name: &str, to: &[&str]| State {
+            name: name.to_owned(),
+            to: to.iter().map(|&name| name.to_owned()).collect(),
+        };
+        let workflow = Workflow::new(vec![
+            state("planned", &["doing", "canceled"]),
+            state("doing", &["finished", "rejected"]),
+            state("finished", &[]),
+            state("canceled", &[]),
+            state("rejected", &[]),
+        ])
+        .unwrap();
+
+        let empty = pool(Vec::new());
+        let doing = pool(vec![item("Overdue", "2026-10-01 10:00:00", &[], 0, None)]);
+        let rejected = pool(vec![item(
+            "Rejected",
+            "2026-10-01 10:00:00",
+            &[],
+            0,
+            Some("2026-10-02 10:00:00"),
+        )]);
+
+        let pools = vec![
+            ("planned", empty.as_ref()),
+            ("doing", doing.as_ref()),
+            ("finished", empty.as_ref()),
+            ("canceled", empty.as_ref()),
+            ("rejected", rejected.as_ref()),
+        ];
+        let request = Request { now, since: None };
+        let response = execute(&workflow, pools, request);
+
+        assert_eq!(
+            response.total,
+            Counts {
+                planned: 1,
+                finished: 0,
+                canceled: 1,
+            }
+        );
+        assert_eq!(response.overdue, 1);
+        assert_eq!(response.completion_ratio, Some(0.0));
+    }
+
+    fn item(
+        summary: &str,
+        deadline: &str,
+        tags: &[&str],
+        priority: i32,
+        closed: Option<&str>,
+    ) -> Item {
+        let mut item = Item::new(
+            summary,
+            "",
+            datetime(deadline),
+            tags.iter().map(|tag| tag.to_string()).collect(),
+            priority.try_into().unwrap(),
+        );
+
+        item.set_closed(closed.map(datetime));
+        item
+    }
+
+    fn pool(items: Vec<Item>) -> Box<dyn ItemPool> {
+        let map = items
+            .into_iter()
+            .map(|item| (item.id(), item))
+            .collect::<HashMap<_, _>>();
+
+        Box::new(MemoryPool::from(map))
+    }
+
+    fn datetime(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::{NaiveDateTime, Timelike};
+use snafu::prelude::*;
+
+use crate::domain::entity::Interval;
+use crate::repository::timer::Pool as TimerPool;
+
+pub struct Request {
+    pub time: NaiveDateTime,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    pub interval: Interval,
+}
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub enum StopError {
+    #[snafu(display("No timer is running"))]
+    Idle,
+}
+
+/// Stops the running timer and records the tracked interval. A timer stopped before it
+/// started, such as after the clock was turned back, tracks nothing.
+pub fn execute(timers: &mut dyn TimerPool, request: Request) -> Result<Response, StopError> {
+    let Some((id, start)) = timers.active() else {
+        return IdleSnafu.fail();
+    };
+
+    let time = request.time;
+    let end = time.with_nanosecond(0).unwrap_or(time).max(start);
+    let interval = Interval { id, start, end };
+
+    timers.set_active(None);
+    timers.add(interval);
+    Ok(Response { interval })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use crate::repository::timer::MemoryPool;
+
+    use super::*;
+
+    #[test]
+    fn it_should_record_the_interval_of_the_running_timer() {
+        let start =
+            NaiveDateTime::parse_from_str("2023-06-17 09:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let mut timers: Box<dyn TimerPool> = Box::new(MemoryPool::new());
+
+        assert_eq!(
+            execute(timers.as_mut(), Request { time: start }),
+            Err(StopError::Idle)
+        );
+
+        timers.set_active(Some((1, start)));
+        let time = start + Duration::minutes(90);
+        let res = execute(timers.as_mut(), Request { time });
+
+        let interval = Interval {
+            id: 1,
+            start,
+            end: time,
+        };
+        assert_eq!(res, Ok(Response { interval }));
+        assert_eq!(timers.active(), None);
+        assert_eq!(timers.intervals(), [interval]);
+    }
+}
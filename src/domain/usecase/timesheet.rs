@@ -0,0 +1,155 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Days, Duration, NaiveDate, NaiveDateTime};
+
+use crate::domain::entity::{Interval, Item, Tag};
+use crate::repository::timer::Pool as TimerPool;
+
+pub struct Request {
+    pub since: NaiveDateTime,
+    pub now: NaiveDateTime,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    /// Tracked time by day, then by tag, with untagged time under `None`. Time spent on
+    /// an item with several tags counts towards each of them.
+    pub days: BTreeMap<NaiveDate, BTreeMap<Option<Tag>, Duration>>,
+    /// Tracked time by day, counting each interval once.
+    pub totals: BTreeMap<NaiveDate, Duration>,
+    pub total: Duration,
+}
+
+/// Sums up the time tracked between `since` and `now` by day and tag, with the running
+/// timer counted up to now. `items` are looked up for tags, and time of items which are
+/// no longer around is untagged. Intervals spanning midnight are split between days.
+pub fn execute(timers: &dyn TimerPool, items: &[Item], request: Request) -> Response {
+    let Request { since, now } = request;
+    let items = items
+        .iter()
+        .map(|item| (item.id(), item))
+        .collect::<HashMap<_, _>>();
+
+    let mut days = BTreeMap::<NaiveDate, BTreeMap<Option<Tag>, Duration>>::new();
+    let mut totals = BTreeMap::<NaiveDate, Duration>::new();
+    let mut total = Duration::zero();
+
+    for Interval { id, start, end } in intervals(timers, now) {
+        let mut start = start.max(since);
+        let end = end.min(now);
+
+        while start < end {
+            let midnight = (start.date() + Days::new(1)).and_hms_opt(0, 0, 0).unwrap();
+            let piece = end.min(midnight) - start;
+            let day = start.date();
+
+            let tags = match items.get(&id) {
+                Some(item) if !item.tags().is_empty() => {
+                    item.tags().iter().cloned().map(Some).collect()
+                }
+                _ => vec![None],
+            };
+
+            for tag in tags {
+                let sum = days.entry(day).or_default().entry(tag);
+                add(sum.or_insert_with(Duration::zero), piece);
+            }
+
+            add(totals.entry(day).or_insert_with(Duration::zero), piece);
+            add(&mut total, piece);
+            start = end.min(midnight);
+        }
+    }
+
+    Response {
+        days,
+        totals,
+        total,
+    }
+}
+
+/// The total time tracked against each item, with the running timer counted up to now.
+pub fn tracked(timers: &dyn TimerPool, now: NaiveDateTime) -> HashMap<u64, Duration> {
+    let mut res = HashMap::<u64, Duration>::new();
+
+    for interval in intervals(timers, now) {
+        add(
+            res.entry(interval.id).or_insert_with(Duration::zero),
+            interval.duration(),
+        );
+    }
+
+    res
+}
+
+fn add(sum: &mut Duration, duration: Duration) {
+    *sum = *sum + duration;
+}
+
+fn intervals(timers: &dyn TimerPool, now: NaiveDateTime) -> Vec<Interval> {
+    let mut intervals = timers.intervals();
+
+    if let Some((id, start)) = timers.active() {
+        let end = now.max(start);
+        intervals.push(Interval { id, start, end });
+    }
+
+    intervals
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repository::timer::MemoryPool;
+
+    use super::*;
+
+    #[test]
+    fn it_should_sum_up_time_by_day_and_tag() {
+        let item = Item::new(
+            "Report",
+            "",
+            parse("2023-06-20 12:00:00"),
+            ["work", "writing"]
+                .iter()
+                .map(|&tag| tag.to_owned())
+                .collect(),
+            0.try_into().unwrap(),
+        );
+
+        let mut timers: Box<dyn TimerPool> = Box::new(MemoryPool::new());
+        timers.add(Interval {
+            id: item.id(),
+            start: parse("2023-06-16 23:00:00"),
+            end: parse("2023-06-17 01:00:00"),
+        });
+        timers.add(Interval {
+            id: 0,
+            start: parse("2023-06-17 09:00:00"),
+            end: parse("2023-06-17 09:30:00"),
+        });
+        timers.set_active(Some((item.id(), parse("2023-06-17 10:00:00"))));
+
+        let request = Request {
+            since: parse("2023-06-16 23:30:00"),
+            now: parse("2023-06-17 11:00:00"),
+        };
+        let res = execute(timers.as_ref(), std::slice::from_ref(&item), request);
+
+        let day = parse("2023-06-17 00:00:00").date();
+        let previous = day.pred_opt().unwrap();
+        let work = Some("work".to_owned());
+
+        assert_eq!(res.days[&previous][&work], Duration::minutes(30));
+        assert_eq!(res.days[&day][&work], Duration::hours(2));
+        assert_eq!(res.days[&day][&None], Duration::minutes(30));
+        assert_eq!(res.totals[&day], Duration::minutes(150));
+        assert_eq!(res.total, Duration::hours(3));
+
+        let tracked = tracked(timers.as_ref(), parse("2023-06-17 11:00:00"));
+        assert_eq!(tracked[&item.id()], Duration::hours(3));
+    }
+
+    fn parse(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+}
@@ -0,0 +1,137 @@
+use chrono::{NaiveDateTime, Timelike};
+use snafu::prelude::*;
+
+use crate::repository::id::Pool as IdPool;
+use crate::repository::item::{AddError, Pool as ItemPool, RemoveError};
+
+pub struct Request {
+    pub id: u64,
+    /// When the item is closed, if the destination is a final state. Closed items no
+    /// longer have their IDs registered.
+    pub closed: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+pub enum TransitionError {
+    #[snafu(display("Target isn't found"))]
+    NotFound,
+    #[snafu(display("The same item already exists in the destination"))]
+    Conflict,
+}
+
+/// Moves an item from the pool of one state to another's. The item is put back if the
+/// destination already has it.
+pub fn execute(
+    source: &mut dyn ItemPool,
+    destination: &mut dyn ItemPool,
+    ids: &mut dyn IdPool,
+    request: Request,
+) -> Result<(), TransitionError> {
+    let Request { id, closed } = request;
+
+    let item = match source.remove(id) {
+        Ok(item) => item,
+        Err(RemoveError::NotFound) => return Err(TransitionError::NotFound),
+    };
+
+    let mut moved = item.clone();
+    moved.set_closed(closed.and_then(|time| time.with_nanosecond(0)));
+
+    match destination.add(moved) {
+        Ok(_) => {
+            if closed.is_some() {
+                ids.remove(id);
+            }
+            Ok(())
+        }
+        Err(AddError::Conflict) => {
+            let _ = source.add(item);
+            Err(TransitionError::Conflict)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::domain::entity::Item;
+    use crate::repository::id::{Trie, TriePool};
+    use crate::repository::item::MemoryPool;
+
+    use super::*;
+
+    #[test]
+    fn it_should_keep_the_id_when_moving_to_an_open_state() {
+        let item = Item::new_test();
+        let id = item.id();
+
+        let mut map = HashMap::new();
+        let _ = map.insert(id, item.clone());
+        let mut source: Box<dyn ItemPool> = Box::new(MemoryPool::from(map));
+        let mut destination: Box<dyn ItemPool> = Box::new(MemoryPool::new());
+
+        let mut trie = Trie::new();
+        trie.insert(id);
+        let mut ids: Box<dyn IdPool> = Box::new(TriePool::from(trie));
+
+        let request = Request { id, closed: None };
+        let res = execute(source.as_mut(), destination.as_mut(), ids.as_mut(), request);
+
+        assert_eq!(res, Ok(()));
+        assert!(source.get(id).is_err());
+        assert_eq!(destination.get(id).ok(), Some(item));
+        assert!(ids.remove(id));
+    }
+
+    #[test]
+    fn it_should_close_the_item_when_moving_to_a_final_state() {
+        let item = Item::new_test();
+        let id = item.id();
+        let time = *item.deadline();
+
+        let mut map = HashMap::new();
+        let _ = map.insert(id, item);
+        let mut source: Box<dyn ItemPool> = Box::new(MemoryPool::from(map));
+        let mut destination: Box<dyn ItemPool> = Box::new(MemoryPool::new());
+
+        let mut trie = Trie::new();
+        trie.insert(id);
+        let mut ids: Box<dyn IdPool> = Box::new(TriePool::from(trie));
+
+        let request = Request {
+            id,
+            closed: Some(time),
+        };
+        let res = execute(source.as_mut(), destination.as_mut(), ids.as_mut(), request);
+
+        assert_eq!(res, Ok(()));
+        assert!(matches!(destination.get(id), Ok(item) if item.closed() == Some(&time)));
+        assert!(!ids.remove(id));
+    }
+
+    #[test]
+    fn it_should_put_the_item_back_when_the_destination_has_it() {
+        let item = Item::new_test();
+        let id = item.id();
+
+        let mut map = HashMap::new();
+        let _ = map.insert(id, item.clone());
+        let mut source: Box<dyn ItemPool> = Box::new(MemoryPool::from(map.clone()));
+        let mut destination: Box<dyn ItemPool> = Box::new(MemoryPool::from(map));
+
+        let mut trie = Trie::new();
+        trie.insert(id);
+        let mut ids: Box<dyn IdPool> = Box::new(TriePool::from(trie));
+
+        let request = Request {
+            id,
+            closed: Some(*item.deadline()),
+        };
+        let res = execute(source.as_mut(), destination.as_mut(), ids.as_mut(), request);
+
+        assert_eq!(res, Err(TransitionError::Conflict));
+        assert!(source.get(id).is_ok());
+        assert_eq!(ids.find(id), Some(vec![id]));
+    }
+}
@@ -0,0 +1,203 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::Deserialize;
+use snafu::prelude::*;
+
+use crate::domain::entity::{Group, Item};
+use crate::domain::usecase::import::Entry;
+use crate::domain::usecase::plan::Request;
+
+use super::{end_of_day, sorted_tags, CsvSnafu, MissingDeadlineSnafu, ParseError, RenderError};
+
+/// Tags are kept in a single column and separated by `;`.
+pub const TAG_SEPARATOR: char = ';';
+
+#[derive(Debug, Deserialize)]
+struct Record {
+    summary: String,
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    deadline: Option<String>,
+    #[serde(default)]
+    tags: String,
+    #[serde(default)]
+    priority: Option<i32>,
+    #[serde(default)]
+    group: Option<Group>,
+}
+
+/// Parses a CSV file with a header row. Only `summary` is mandatory; `content`,
+/// `deadline`, `tags`, `priority` and `group` are optional and unknown columns are
+/// ignored.
+pub fn parse(
+    input: &str,
+    default_deadline: Option<NaiveDateTime>,
+) -> Result<Vec<Entry>, ParseError> {
+    ::csv::Reader::from_reader(input.as_bytes())
+        .deserialize::<Record>()
+        .enumerate()
+        .map(|(index, record)| {
+            let record_number = index + 1;
+            let record = record.map_err(|err| ParseError::Invalid {
+                record: record_number,
+                reason: err.to_string(),
+            })?;
+            convert(record, record_number, default_deadline)
+        })
+        .collect()
+}
+
+/// Writes items with the same columns `parse` reads, plus their IDs.
+pub fn render(items: &[(Group, Item)]) -> Result<String, RenderError> {
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+
+    writer
+        .write_record([
+            "id", "summary", "content", "deadline", "tags", "priority", "group",
+        ])
+        .context(CsvSnafu)?;
+
+    for (group, item) in items {
+        writer
+            .write_record([
+                item.id().to_string(),
+                item.summary().to_owned(),
+                item.content().to_owned(),
+                item.deadline().format("%Y-%m-%d %H:%M:%S").to_string(),
+                sorted_tags(item).join(&TAG_SEPARATOR.to_string()),
+                item.priority().value().to_string(),
+                group.to_string(),
+            ])
+            .context(CsvSnafu)?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|err| ::csv::Error::from(err.into_error()))
+        .context(CsvSnafu)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn convert(
+    value: Record,
+    record: usize,
+    default_deadline: Option<NaiveDateTime>,
+) -> Result<Entry, ParseError> {
+    let deadline = match value.deadline.as_deref().map(str::trim) {
+        Some(deadline) if !deadline.is_empty() => {
+            Some(parse_date(deadline).ok_or_else(|| ParseError::Invalid {
+                record,
+                reason: format!("invalid deadline `{deadline}`"),
+            })?)
+        }
+        _ => None,
+    };
+
+    let deadline = deadline
+        .or(default_deadline)
+        .context(MissingDeadlineSnafu { record })?;
+
+    let tags = value
+        .tags
+        .split(TAG_SEPARATOR)
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_owned)
+        .collect();
+
+    Ok(Entry {
+        group: value.group.unwrap_or(Group::Planned),
+        request: Request {
+            summary: value.summary,
+            content: value.content,
+            deadline,
+            tags,
+            priority: value.priority.unwrap_or_default(),
+            estimate: None,
+        },
+        closed: None,
+    })
+}
+
+fn parse_date(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .map(end_of_day)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_read_records_by_header_names() {
+        let input = "id,summary,deadline,tags,priority,group\n\
+                     1,Pay rent,2023-06-18 12:00:00,home;money,2,\n\
+                     2,Walk,2023-06-18,,,finished\n";
+        let entries = parse(input, None).unwrap();
+
+        assert_eq!(
+            entries[0],
+            Entry {
+                group: Group::Planned,
+                request: Request {
+                    summary: "Pay rent".to_owned(),
+                    content: String::new(),
+                    deadline: parse_date("2023-06-18 12:00:00").unwrap(),
+                    tags: ["home", "money"].iter().map(|&t| t.to_owned()).collect(),
+                    priority: 2,
+                    estimate: None,
+                },
+                closed: None,
+            }
+        );
+        assert_eq!(entries[1].group, Group::Finished);
+        assert_eq!(
+            entries[1].request.deadline,
+            parse_date("2023-06-18 23:59:59").unwrap()
+        );
+    }
+
+    #[test]
+    fn it_should_read_back_rendered_items() {
+        let item = Item::new(
+            "Pay rent",
+            "Before noon, please",
+            parse_date("2023-06-18 12:00:00").unwrap(),
+            ["home", "money"].iter().map(|&t| t.to_owned()).collect(),
+            2.try_into().unwrap(),
+        );
+
+        let output = render(&[(Group::Finished, item.clone())]).unwrap();
+        let entries = parse(&output, None).unwrap();
+
+        assert_eq!(
+            entries,
+            vec![Entry {
+                group: Group::Finished,
+                request: Request {
+                    summary: item.summary().to_owned(),
+                    content: item.content().to_owned(),
+                    deadline: *item.deadline(),
+                    tags: item.tags().clone(),
+                    priority: 2,
+                    estimate: None,
+                },
+                closed: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn it_should_report_records_with_an_invalid_deadline() {
+        let input = "summary,deadline\nPay rent,tomorrow\n";
+        assert!(matches!(
+            parse(input, None),
+            Err(ParseError::Invalid { record: 1, .. })
+        ));
+    }
+}
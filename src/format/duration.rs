@@ -0,0 +1,99 @@
+use chrono::Duration;
+use snafu::prelude::*;
+
+const UNITS: [(char, i64); 5] = [
+    ('w', 7 * 24 * 3600),
+    ('d', 24 * 3600),
+    ('h', 3600),
+    ('m', 60),
+    ('s', 1),
+];
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+#[snafu(display("`{value}` is not a valid duration, try e.g. `1d`, `2h` or `1h30m`"))]
+pub struct DurationError {
+    value: String,
+}
+
+/// Parses durations such as `1d`, `90m` or `1h30m` made of weeks, days, hours, minutes
+/// and seconds.
+pub fn parse(value: &str) -> Result<Duration, DurationError> {
+    let mut seconds = 0;
+    let mut number = String::new();
+
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let unit = UNITS.iter().find(|(unit, _)| *unit == c);
+
+        match (number.parse::<i64>(), unit) {
+            (Ok(n), Some((_, size))) => seconds += n * size,
+            _ => return DurationSnafu { value }.fail(),
+        }
+
+        number.clear();
+    }
+
+    ensure!(
+        number.is_empty() && !value.is_empty(),
+        DurationSnafu { value }
+    );
+
+    Ok(Duration::seconds(seconds))
+}
+
+/// Renders the largest whole unit of a duration, e.g. `2d` for 50 hours. The sign is
+/// dropped, as callers word it themselves.
+pub fn render(duration: Duration) -> String {
+    let seconds = duration.num_seconds().abs();
+
+    UNITS
+        .iter()
+        .skip(1)
+        .find(|(_, size)| seconds >= *size)
+        .map(|(unit, size)| format!("{}{unit}", seconds / size))
+        .unwrap_or_else(|| "0s".to_owned())
+}
+
+/// Renders a duration to the minute in hours and minutes, e.g. `26h5m`, as tracked time
+/// is counted by the hour rather than by the day.
+pub fn render_minutes(duration: Duration) -> String {
+    let minutes = duration.num_minutes().abs();
+
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{minutes}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, minutes) => format!("{hours}h{minutes}m"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_parse_combined_units() {
+        assert_eq!(parse("1d"), Ok(Duration::days(1)));
+        assert_eq!(parse("1h30m"), Ok(Duration::minutes(90)));
+        assert_eq!(parse("2w"), Ok(Duration::weeks(2)));
+        assert!(parse("").is_err());
+        assert!(parse("10").is_err());
+        assert!(parse("h").is_err());
+        assert!(parse("1y").is_err());
+    }
+
+    #[test]
+    fn it_should_render_the_largest_unit() {
+        assert_eq!(render(Duration::hours(50)), "2d");
+        assert_eq!(render(Duration::minutes(-300)), "5h");
+        assert_eq!(render(Duration::seconds(59)), "59s");
+        assert_eq!(render_minutes(Duration::minutes(1565)), "26h5m");
+        assert_eq!(render_minutes(Duration::hours(2)), "2h");
+        assert_eq!(render_minutes(Duration::seconds(59)), "0m");
+        assert_eq!(render(Duration::zero()), "0s");
+        assert_eq!(render(Duration::days(20)), "20d");
+    }
+}
@@ -0,0 +1,453 @@
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use snafu::prelude::*;
+
+use crate::domain::entity::{Group, Item, TagSet};
+use crate::domain::usecase::import::Entry;
+use crate::domain::usecase::plan::Request;
+
+use super::{end_of_day, sorted_tags, MissingDeadlineSnafu, ParseError};
+
+const PRODUCT_ID: &str = "-//todo//todo//EN";
+const LINE_LIMIT: usize = 75;
+
+/// UIDs only depend on item IDs, so exporting the same item twice yields the same UID
+/// and calendar clients update the existing entry instead of duplicating it.
+pub fn uid(id: u64) -> String {
+    format!("{id}@todo")
+}
+
+/// Maps [-3, 3] onto the iCalendar scale where 1 is the highest and 9 the lowest
+/// priority, keeping the default priority at the medium value 5.
+pub fn priority_to_ical(priority: i32) -> u8 {
+    match priority {
+        3 => 1,
+        2 => 2,
+        1 => 4,
+        -1 => 6,
+        -2 => 8,
+        -3 => 9,
+        _ => 5,
+    }
+}
+
+/// The inverse of [`priority_to_ical`], where 0 means undefined and counts as the
+/// default priority.
+pub fn priority_from_ical(priority: u8) -> i32 {
+    match priority {
+        1 => 3,
+        2 => 2,
+        3 | 4 => 1,
+        6 | 7 => -1,
+        8 => -2,
+        9 => -3,
+        _ => 0,
+    }
+}
+
+pub fn status(group: Group) -> &'static str {
+    match group {
+        Group::Planned => "NEEDS-ACTION",
+        Group::Finished => "COMPLETED",
+        Group::Canceled => "CANCELLED",
+    }
+}
+
+/// Anything but completed or cancelled, such as `IN-PROCESS`, is still planned.
+pub fn group(status: &str) -> Group {
+    match status {
+        "COMPLETED" => Group::Finished,
+        "CANCELLED" => Group::Canceled,
+        _ => Group::Planned,
+    }
+}
+
+/// Reads the VTODOs of a calendar, numbered from 1 as records. Deadlines in UTC are
+/// converted to local time and date-only ones fall due at the end of the day, while
+/// other components such as VEVENTs are skipped, as are components nested in a VTODO
+/// such as VALARMs, whose properties aren't the item's.
+pub fn parse(
+    input: &str,
+    default_deadline: Option<NaiveDateTime>,
+) -> Result<Vec<Entry>, ParseError> {
+    let mut entries = Vec::new();
+    let mut todo = None::<Vec<(String, String)>>;
+    // How deep in components nested in the current VTODO the line is.
+    let mut depth = 0;
+
+    for line in unfold(input) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+
+        match (name, value, todo.as_mut()) {
+            ("BEGIN", _, Some(_)) => depth += 1,
+            ("END", _, Some(_)) if depth > 0 => depth -= 1,
+            (_, _, Some(_)) if depth > 0 => {}
+            ("BEGIN", "VTODO", None) => todo = Some(Vec::new()),
+            ("END", "VTODO", Some(_)) => {
+                let record = entries.len() + 1;
+                let props = todo.take().unwrap_or_default();
+                entries.push(convert(props, record, default_deadline)?);
+            }
+            (name, value, Some(props)) => props.push((name.to_owned(), value.to_owned())),
+            (_, _, None) => {}
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Renders a calendar with one VTODO per item. Deadlines are written as floating local
+/// times and `stamp` is the UTC time the calendar is produced at.
+pub fn render(items: &[(Group, Item)], stamp: NaiveDateTime) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        format!("PRODID:{PRODUCT_ID}"),
+    ];
+
+    for (group, item) in items {
+        lines.push("BEGIN:VTODO".to_owned());
+        lines.push(format!("UID:{}", uid(item.id())));
+        lines.push(format!("DTSTAMP:{}Z", format_time(&stamp)));
+        lines.push(format!("SUMMARY:{}", escape(item.summary())));
+
+        if !item.content().is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape(item.content())));
+        }
+
+        lines.push(format!("DUE:{}", format_time(item.deadline())));
+        lines.push(format!(
+            "PRIORITY:{}",
+            priority_to_ical(item.priority().value())
+        ));
+
+        let tags = sorted_tags(item);
+
+        if !tags.is_empty() {
+            let tags = tags.into_iter().map(escape).collect::<Vec<_>>();
+            lines.push(format!("CATEGORIES:{}", tags.join(",")));
+        }
+
+        lines.push(format!("STATUS:{}", status(*group)));
+        lines.push("END:VTODO".to_owned());
+    }
+
+    lines.push("END:VCALENDAR".to_owned());
+
+    lines
+        .iter()
+        .map(|line| fold(line) + "\r\n")
+        .collect::<String>()
+}
+
+fn convert(
+    props: Vec<(String, String)>,
+    record: usize,
+    default_deadline: Option<NaiveDateTime>,
+) -> Result<Entry, ParseError> {
+    let mut summary = String::new();
+    let mut content = String::new();
+    let mut deadline = None;
+    let mut tags = TagSet::new();
+    let mut priority = 0;
+    let mut group = Group::Planned;
+
+    for (name, value) in props {
+        // Parameters such as `VALUE=DATE` follow the name after semicolons.
+        let (name, params) = name.split_once(';').unwrap_or((&name, ""));
+
+        match name {
+            "SUMMARY" => summary = unescape(&value),
+            "DESCRIPTION" => content = unescape(&value),
+            "DUE" => {
+                deadline = Some(
+                    parse_time(&value, params).ok_or_else(|| ParseError::Invalid {
+                        record,
+                        reason: format!("`{value}` is not a valid due time"),
+                    })?,
+                )
+            }
+            "PRIORITY" => priority = value.parse().map_or(0, priority_from_ical),
+            "CATEGORIES" => tags.extend(split(&value).iter().map(|tag| unescape(tag))),
+            "STATUS" => group = self::group(&value),
+            _ => {}
+        }
+    }
+
+    let deadline = deadline
+        .or(default_deadline)
+        .context(MissingDeadlineSnafu { record })?;
+
+    Ok(Entry {
+        group,
+        request: Request {
+            summary,
+            content,
+            deadline,
+            tags,
+            priority,
+            estimate: None,
+        },
+        closed: None,
+    })
+}
+
+fn parse_time(value: &str, params: &str) -> Option<NaiveDateTime> {
+    if params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME") {
+        return NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()
+            .map(end_of_day);
+    }
+
+    match value.strip_suffix('Z') {
+        Some(value) => {
+            let utc = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+            Some(
+                Utc.from_utc_datetime(&utc)
+                    .with_timezone(&Local)
+                    .naive_local(),
+            )
+        }
+        None => NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok(),
+    }
+}
+
+/// Joins continuation lines, which start with a space or a tab, to the previous line.
+fn unfold(input: &str) -> Vec<String> {
+    let mut lines = Vec::<String>::new();
+
+    for line in input.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_owned()),
+        }
+    }
+
+    lines
+}
+
+/// Splits a list value on commas which aren't escaped.
+fn split(value: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                parts.last_mut().unwrap().push(c);
+                parts.last_mut().unwrap().extend(chars.next());
+            }
+            ',' => parts.push(String::new()),
+            c => parts.last_mut().unwrap().push(c),
+        }
+    }
+
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+fn unescape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            res.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n' | 'N') => res.push('\n'),
+            Some(c) => res.push(c),
+            None => res.push(c),
+        }
+    }
+
+    res
+}
+
+fn format_time(time: &NaiveDateTime) -> String {
+    time.format("%Y%m%dT%H%M%S").to_string()
+}
+
+fn escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\\' => res.push_str("\\\\"),
+            ';' => res.push_str("\\;"),
+            ',' => res.push_str("\\,"),
+            '\n' => res.push_str("\\n"),
+            '\r' => {}
+            c => res.push(c),
+        }
+    }
+
+    res
+}
+
+/// Content lines longer than 75 octets are split, with continuation lines starting
+/// with a space.
+fn fold(line: &str) -> String {
+    let mut res = String::with_capacity(line.len());
+    let mut len = 0;
+
+    for c in line.chars() {
+        if len + c.len_utf8() > LINE_LIMIT {
+            res.push_str("\r\n ");
+            len = 1;
+        }
+
+        res.push(c);
+        len += c.len_utf8();
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_render_a_vtodo_per_item() {
+        let item = Item::new(
+            "Pay rent, finally",
+            "Line 1\nLine 2",
+            parse("2023-06-18 12:00:00"),
+            ["home", "money"].iter().map(|&t| t.to_owned()).collect(),
+            2.try_into().unwrap(),
+        );
+        let id = item.id();
+
+        let output = render(&[(Group::Canceled, item)], parse("2023-06-17 08:00:00"));
+
+        assert_eq!(
+            output,
+            [
+                "BEGIN:VCALENDAR",
+                "VERSION:2.0",
+                "PRODID:-//todo//todo//EN",
+                "BEGIN:VTODO",
+                &format!("UID:{id}@todo"),
+                "DTSTAMP:20230617T080000Z",
+                "SUMMARY:Pay rent\\, finally",
+                "DESCRIPTION:Line 1\\nLine 2",
+                "DUE:20230618T120000",
+                "PRIORITY:2",
+                "CATEGORIES:home,money",
+                "STATUS:CANCELLED",
+                "END:VTODO",
+                "END:VCALENDAR",
+                "",
+            ]
+            .join("\r\n")
+        );
+    }
+
+    #[test]
+    fn it_should_fold_long_lines() {
+        let line = "x".repeat(160);
+        let folded = fold(&line);
+        let parts = folded.split("\r\n").collect::<Vec<_>>();
+
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].len(), 75);
+        assert_eq!(parts[1].len(), 75);
+        assert_eq!(parts.concat().replace(' ', ""), line);
+    }
+
+    #[test]
+    fn it_should_map_ical_priorities_back() {
+        let priorities = (0..=9).map(priority_from_ical).collect::<Vec<_>>();
+        assert_eq!(priorities, vec![0, 3, 2, 1, 1, 0, -1, -1, -2, -3]);
+
+        for priority in -3..=3 {
+            assert_eq!(priority_from_ical(priority_to_ical(priority)), priority);
+        }
+    }
+
+    #[test]
+    fn it_should_read_back_rendered_items() {
+        let item = Item::new(
+            "Pay rent, finally",
+            "Line 1\nLine 2; done",
+            parse("2023-06-18 12:00:00"),
+            ["home", "money"].iter().map(|&t| t.to_owned()).collect(),
+            (-2).try_into().unwrap(),
+        );
+        let output = render(
+            &[(Group::Finished, item.clone())],
+            parse("2023-06-17 08:00:00"),
+        );
+
+        assert_eq!(
+            super::parse(&output, None),
+            Ok(vec![Entry {
+                group: Group::Finished,
+                request: Request {
+                    summary: item.summary().to_owned(),
+                    content: item.content().to_owned(),
+                    deadline: *item.deadline(),
+                    tags: item.tags().clone(),
+                    priority: -2,
+                    estimate: None,
+                },
+                closed: None,
+            }])
+        );
+    }
+
+    #[test]
+    fn it_should_unfold_lines_and_accept_dates_and_utc_times() {
+        let input = "BEGIN:VCALENDAR\r\n\
+                     BEGIN:VEVENT\r\nSUMMARY:Skipped\r\nEND:VEVENT\r\n\
+                     BEGIN:VTODO\r\nSUMMARY:Water\r\n  plants\r\n\
+                     DUE;VALUE=DATE:20230618\r\nSTATUS:IN-PROCESS\r\nEND:VTODO\r\n\
+                     BEGIN:VTODO\r\nSUMMARY:Call\r\nDUE:20230618T120000Z\r\nEND:VTODO\r\n\
+                     BEGIN:VTODO\r\nSUMMARY:Read\r\nEND:VTODO\r\n\
+                     END:VCALENDAR\r\n";
+        let default = parse("2023-06-20 00:00:00");
+        let entries = super::parse(input, Some(default)).unwrap();
+
+        let utc = Utc.from_utc_datetime(&parse("2023-06-18 12:00:00"));
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].request.summary, "Water plants");
+        assert_eq!(entries[0].group, Group::Planned);
+        assert_eq!(entries[0].request.deadline, parse("2023-06-18 23:59:59"));
+        assert_eq!(
+            entries[1].request.deadline,
+            utc.with_timezone(&Local).naive_local()
+        );
+        assert_eq!(entries[2].request.deadline, default);
+
+        assert_eq!(
+            super::parse(input, None),
+            Err(ParseError::MissingDeadline { record: 3 })
+        );
+    }
+
+    #[test]
+    fn it_should_skip_properties_of_components_nested_in_a_vtodo() {
+        let input = "BEGIN:VCALENDAR\r\n\
+                     BEGIN:VTODO\r\nSUMMARY:Water plants\r\nDESCRIPTION:In the garden\r\n\
+                     BEGIN:VALARM\r\nACTION:DISPLAY\r\nDESCRIPTION:Reminder\r\n\
+                     TRIGGER:-PT15M\r\nEND:VALARM\r\n\
+                     DUE:20230618T120000\r\nEND:VTODO\r\n\
+                     BEGIN:VTODO\r\nSUMMARY:Call\r\nDUE:20230618T120000\r\nEND:VTODO\r\n\
+                     END:VCALENDAR\r\n";
+        let entries = super::parse(input, None).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].request.summary, "Water plants");
+        assert_eq!(entries[0].request.content, "In the garden");
+        assert_eq!(entries[1].request.summary, "Call");
+        assert_eq!(entries[1].request.content, "");
+    }
+
+    fn parse(time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+}
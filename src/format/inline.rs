@@ -0,0 +1,228 @@
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use snafu::prelude::*;
+
+use crate::domain::entity::TagSet;
+
+use super::end_of_day;
+
+/// A line of free text with inline `#tag`, `!N` or `!!!` priority and `due:` or `@`
+/// deadline tokens taken out of it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Inline {
+    pub summary: String,
+    pub tags: TagSet,
+    pub priority: Option<i32>,
+    pub deadline: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub enum InlineError {
+    #[snafu(display("`{token}` is not a valid deadline"))]
+    InvalidDeadline { token: String },
+}
+
+/// Parses `text`, resolving relative deadlines such as `due:friday` against `now`.
+/// Words starting with `@` are only taken as deadlines if they are valid ones.
+pub fn parse(text: &str, now: NaiveDateTime) -> Result<Inline, InlineError> {
+    let mut inline = Inline::default();
+    let mut summary = Vec::new();
+
+    for word in text.split_whitespace() {
+        if let Some(tag) = word.strip_prefix('#').filter(|tag| !tag.is_empty()) {
+            inline.tags.insert(tag.to_owned());
+        } else if let Some(priority) = parse_priority(word) {
+            inline.priority = Some(priority);
+        } else if let Some(deadline) = word.strip_prefix("due:") {
+            let deadline =
+                parse_deadline(deadline, now).context(InvalidDeadlineSnafu { token: word })?;
+            inline.deadline = Some(deadline);
+        } else if let Some(deadline) = word
+            .strip_prefix('@')
+            .and_then(|deadline| parse_deadline(deadline, now))
+        {
+            inline.deadline = Some(deadline);
+        } else {
+            summary.push(word);
+        }
+    }
+
+    inline.summary = summary.join(" ");
+    Ok(inline)
+}
+
+/// Renders the tokens `parse` understands, leaving out the default priority and the
+/// time of deadlines falling due at the end of a day.
+pub fn render(summary: &str, tags: &[&str], priority: i32, deadline: &NaiveDateTime) -> String {
+    let mut words = vec![summary.to_owned()];
+    words.extend(tags.iter().map(|tag| format!("#{tag}")));
+
+    if priority != 0 {
+        words.push(format!("!{priority}"));
+    }
+
+    if *deadline == end_of_day(deadline.date()) {
+        words.push(format!("due:{}", deadline.format("%Y-%m-%d")));
+    } else {
+        words.push(format!("due:{}", deadline.format("%Y-%m-%dT%H:%M:%S")));
+    }
+
+    words.join(" ")
+}
+
+/// Takes `!N`, `!!` or `!!!`, leaving a bare `!` to the summary.
+fn parse_priority(word: &str) -> Option<i32> {
+    let value = word.strip_prefix('!').filter(|value| !value.is_empty())?;
+
+    if value.len() < 3 && value.chars().all(|c| c == '!') {
+        Some(value.len() as i32 + 1)
+    } else {
+        value.parse().ok()
+    }
+}
+
+/// Accepts absolute dates with an optional time, a bare time of today, `today`,
+/// `tomorrow` and weekday names, which refer to the next such day including today.
+fn parse_deadline(value: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let today = now.date();
+
+    if let Ok(deadline) = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+    {
+        return Some(deadline);
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Some(end_of_day(date));
+    }
+
+    if let Ok(time) = NaiveTime::parse_from_str(value, "%H:%M") {
+        return Some(today.and_time(time));
+    }
+
+    let date = match value.to_lowercase().as_str() {
+        "today" => today,
+        "tomorrow" => today + Days::new(1),
+        name => {
+            let weekday = name.parse::<Weekday>().ok()?;
+            let offset =
+                (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+            today + Days::new(offset as u64)
+        }
+    };
+
+    Some(end_of_day(date))
+}
+
+/// Resolves the start of a past period: an absolute date, `today`, `yesterday`, or a
+/// weekday name, which refers to the last such day including today, all from midnight.
+pub fn parse_since(value: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let today = now.date();
+
+    let date = match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Ok(date) => date,
+        Err(_) => match value.to_lowercase().as_str() {
+            "today" => today,
+            "yesterday" => today - Days::new(1),
+            name => {
+                let weekday = name.parse::<Weekday>().ok()?;
+                let offset = (7 + today.weekday().num_days_from_monday()
+                    - weekday.num_days_from_monday())
+                    % 7;
+                today - Days::new(offset as u64)
+            }
+        },
+    };
+
+    date.and_hms_opt(0, 0, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_take_tokens_out_of_the_summary() {
+        let inline = parse("Pay #home rent !2 due:2026-10-20", now()).unwrap();
+
+        assert_eq!(
+            inline,
+            Inline {
+                summary: "Pay rent".to_owned(),
+                tags: ["home"].iter().map(|&t| t.to_owned()).collect(),
+                priority: Some(2),
+                deadline: parse_deadline("2026-10-20T23:59:59", now()),
+            }
+        );
+    }
+
+    #[test]
+    fn it_should_keep_words_that_only_look_like_tokens() {
+        let inline = parse("Say hi! # !loud @home !", now()).unwrap();
+        assert_eq!(inline.summary, "Say hi! # !loud @home !");
+        assert_eq!(inline.deadline, None);
+        assert_eq!(inline.priority, None);
+    }
+
+    #[test]
+    fn it_should_reject_malformed_deadlines() {
+        assert_eq!(
+            parse("Pay rent due:soon", now()),
+            Err(InlineError::InvalidDeadline {
+                token: "due:soon".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn it_should_count_exclamation_marks_as_priority() {
+        assert_eq!(parse("A !!!", now()).unwrap().priority, Some(3));
+        assert_eq!(parse("A !!", now()).unwrap().priority, Some(2));
+        assert_eq!(parse("A !", now()).unwrap().priority, None);
+        assert_eq!(parse("A !-2", now()).unwrap().priority, Some(-2));
+        assert_eq!(parse("A !!!!", now()).unwrap().priority, None);
+    }
+
+    #[test]
+    fn it_should_resolve_relative_deadlines() {
+        let date = |day| end_of_day(NaiveDate::from_ymd_opt(2026, 10, day).unwrap());
+
+        // 2026-10-16 is a Friday
+        assert_eq!(parse_deadline("friday", now()), Some(date(16)));
+        assert_eq!(parse_deadline("Mon", now()), Some(date(19)));
+        assert_eq!(parse_deadline("tomorrow", now()), Some(date(17)));
+        assert_eq!(
+            parse_deadline("18:30", now()),
+            NaiveDate::from_ymd_opt(2026, 10, 16)
+                .unwrap()
+                .and_hms_opt(18, 30, 0)
+        );
+        assert_eq!(
+            parse("Pay rent #home !2 @thursday", now())
+                .unwrap()
+                .deadline,
+            Some(date(22))
+        );
+    }
+
+    #[test]
+    fn it_should_resolve_past_days() {
+        let date = |day| {
+            NaiveDate::from_ymd_opt(2026, 10, day)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+        };
+
+        assert_eq!(parse_since("monday", now()), date(12));
+        assert_eq!(parse_since("Fri", now()), date(16));
+        assert_eq!(parse_since("yesterday", now()), date(15));
+        assert_eq!(parse_since("2026-10-01", now()), date(1));
+        assert_eq!(parse_since("someday", now()), None);
+    }
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 16)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap()
+    }
+}
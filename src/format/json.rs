@@ -0,0 +1,35 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use snafu::prelude::*;
+
+use crate::domain::entity::{Group, Item};
+
+use super::{sorted_tags, JsonSnafu, RenderError};
+
+#[derive(Debug, Serialize)]
+struct Record<'a> {
+    id: u64,
+    group: Group,
+    summary: &'a str,
+    content: &'a str,
+    deadline: NaiveDateTime,
+    tags: Vec<&'a str>,
+    priority: i32,
+}
+
+pub fn render(items: &[(Group, Item)]) -> Result<String, RenderError> {
+    let records = items
+        .iter()
+        .map(|(group, item)| Record {
+            id: item.id(),
+            group: *group,
+            summary: item.summary(),
+            content: item.content(),
+            deadline: *item.deadline(),
+            tags: sorted_tags(item),
+            priority: item.priority().value(),
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::to_string_pretty(&records).context(JsonSnafu)
+}
@@ -0,0 +1,286 @@
+use chrono::{Local, NaiveDateTime};
+use snafu::prelude::*;
+
+use crate::domain::entity::{Group, Item};
+use crate::domain::usecase::import::Entry;
+use crate::domain::usecase::plan::Request;
+
+use super::inline::{self, Inline};
+use super::{sorted_tags, MissingDeadlineSnafu, ParseError};
+
+struct Checkbox<'a> {
+    checked: bool,
+    text: &'a str,
+}
+
+/// Parses every `- [ ]` and `- [x]` line of a Markdown document, ignoring anything else
+/// but lines indented by two spaces right below an item, which become its content.
+/// Checklists nested deeper than an item are part of that content too. Checked items
+/// are finished, unless their text is struck through, in which case they are canceled.
+pub fn parse(
+    input: &str,
+    default_deadline: Option<NaiveDateTime>,
+) -> Result<Vec<Entry>, ParseError> {
+    let mut entries = Vec::new();
+    let mut current: Option<(usize, Checkbox<'_>, Vec<&str>)> = None;
+    let mut indent = 0;
+
+    for (index, line) in input.lines().enumerate() {
+        let nested = current.is_some() && line.starts_with("  ") && indentation(line) > indent;
+
+        if let Some(checkbox) = parse_checkbox(line).filter(|_| !nested) {
+            if let Some(item) = current.take() {
+                entries.push(convert(item, default_deadline)?);
+            }
+
+            current = Some((index + 1, checkbox, Vec::new()));
+            indent = indentation(line);
+        } else if let Some(line) = line.strip_prefix("  ") {
+            // Blank lines indented like the rest keep paragraphs of the content together.
+            if let Some((_, _, content)) = current.as_mut() {
+                content.push(line);
+            }
+        } else if let Some(item) = current.take() {
+            entries.push(convert(item, default_deadline)?);
+        }
+    }
+
+    if let Some(item) = current.take() {
+        entries.push(convert(item, default_deadline)?);
+    }
+
+    Ok(entries)
+}
+
+/// Renders items as a checklist with a section per group.
+pub fn render(items: &[(Group, Item)]) -> String {
+    let mut sections = Vec::new();
+
+    for group in Group::ALL {
+        let mut lines = Vec::new();
+
+        for (_, item) in items.iter().filter(|(g, _)| *g == group) {
+            let text = inline::render(
+                item.summary(),
+                &sorted_tags(item),
+                item.priority().value(),
+                item.deadline(),
+            );
+
+            lines.push(match group {
+                Group::Planned => format!("- [ ] {text}"),
+                Group::Finished => format!("- [x] {text}"),
+                Group::Canceled => format!("- [x] ~~{text}~~"),
+            });
+
+            lines.extend(item.content().lines().map(|line| format!("  {line}")));
+        }
+
+        if !lines.is_empty() {
+            let mut title = group.to_string();
+            title[..1].make_ascii_uppercase();
+            sections.push(format!("## {title}\n\n{}\n", lines.join("\n")));
+        }
+    }
+
+    sections.join("\n")
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn parse_checkbox(line: &str) -> Option<Checkbox<'_>> {
+    let line = line.trim_start();
+    let rest = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))?;
+
+    let (checked, text) = if let Some(text) = rest.strip_prefix("[ ]") {
+        (false, text)
+    } else if let Some(text) = rest
+        .strip_prefix("[x]")
+        .or_else(|| rest.strip_prefix("[X]"))
+    {
+        (true, text)
+    } else {
+        return None;
+    };
+
+    Some(Checkbox {
+        checked,
+        text: text.trim(),
+    })
+}
+
+fn convert(
+    (record, checkbox, content): (usize, Checkbox<'_>, Vec<&str>),
+    default_deadline: Option<NaiveDateTime>,
+) -> Result<Entry, ParseError> {
+    let struck = checkbox
+        .text
+        .strip_prefix("~~")
+        .and_then(|text| text.strip_suffix("~~"));
+
+    let group = match (checkbox.checked, struck) {
+        (false, _) => Group::Planned,
+        (true, None) => Group::Finished,
+        (true, Some(_)) => Group::Canceled,
+    };
+
+    let Inline {
+        summary,
+        tags,
+        priority,
+        deadline,
+    } = inline::parse(struck.unwrap_or(checkbox.text), Local::now().naive_local()).map_err(
+        |err| ParseError::Invalid {
+            record,
+            reason: err.to_string(),
+        },
+    )?;
+
+    let deadline = deadline
+        .or(default_deadline)
+        .context(MissingDeadlineSnafu { record })?;
+
+    Ok(Entry {
+        group,
+        request: Request {
+            summary,
+            content: content.join("\n"),
+            deadline,
+            tags,
+            priority: priority.unwrap_or_default(),
+            estimate: None,
+        },
+        closed: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::domain::entity::TagSet;
+    use crate::format::end_of_day;
+
+    use super::*;
+
+    #[test]
+    fn it_should_turn_checklists_into_entries() {
+        let input = "# Meeting\n\
+                     \n\
+                     Some notes.\n\
+                     - [ ] Book a room #office !2 due:2026-10-20\n  \
+                       Ask Alice first\n\
+                     - [x] Send agenda due:2026-10-18T09:30\n\
+                     * [X] ~~Order pizza~~\n\
+                     - plain bullet\n";
+        let deadline = end_of_day(NaiveDate::from_ymd_opt(2026, 10, 19).unwrap());
+        let entries = parse(input, Some(deadline)).unwrap();
+
+        assert_eq!(
+            entries[0],
+            Entry {
+                group: Group::Planned,
+                request: Request {
+                    summary: "Book a room".to_owned(),
+                    content: "Ask Alice first".to_owned(),
+                    deadline: end_of_day(NaiveDate::from_ymd_opt(2026, 10, 20).unwrap()),
+                    tags: ["office"].iter().map(|&t| t.to_owned()).collect(),
+                    priority: 2,
+                    estimate: None,
+                },
+                closed: None,
+            }
+        );
+        assert_eq!(entries[1].group, Group::Finished);
+        assert_eq!(
+            entries[1].request.deadline,
+            NaiveDate::from_ymd_opt(2026, 10, 18)
+                .unwrap()
+                .and_hms_opt(9, 30, 0)
+                .unwrap()
+        );
+        assert_eq!(entries[2].group, Group::Canceled);
+        assert_eq!(entries[2].request.summary, "Order pizza");
+        assert_eq!(entries[2].request.deadline, deadline);
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn it_should_read_back_rendered_items() {
+        let deadline = end_of_day(NaiveDate::from_ymd_opt(2026, 10, 20).unwrap());
+        let tags: TagSet = ["a", "b"].iter().map(|&t| t.to_owned()).collect();
+        let items = [
+            (
+                Group::Planned,
+                Item::new("A", "1\n2", deadline, tags.clone(), 1.try_into().unwrap()),
+            ),
+            (
+                Group::Canceled,
+                Item::new("B", "", deadline, tags, 0.try_into().unwrap()),
+            ),
+        ];
+
+        let output = render(&items);
+        assert_eq!(
+            output,
+            "## Planned\n\n\
+             - [ ] A #a #b !1 due:2026-10-20\n  1\n  2\n\n\
+             ## Canceled\n\n\
+             - [x] ~~B #a #b due:2026-10-20~~\n"
+        );
+
+        let entries = parse(&output, None).unwrap();
+        assert_eq!(entries[0].request.content, "1\n2");
+        assert_eq!(entries[0].request.priority, 1);
+        assert_eq!(entries[1].group, Group::Canceled);
+        assert_eq!(entries[1].request.summary, "B");
+    }
+
+    #[test]
+    fn it_should_keep_paragraphs_and_indentation_of_content() {
+        let deadline = end_of_day(NaiveDate::from_ymd_opt(2026, 10, 20).unwrap());
+        let content = "First paragraph.\n\nSecond one:\n  - nested\n    deeper";
+        let items = [
+            (
+                Group::Planned,
+                Item::new("A", content, deadline, TagSet::new(), 0.try_into().unwrap()),
+            ),
+            (
+                Group::Planned,
+                Item::new("B", "Last", deadline, TagSet::new(), 0.try_into().unwrap()),
+            ),
+        ];
+
+        let entries = parse(&render(&items), None).unwrap();
+
+        assert_eq!(entries[0].request.content, content);
+        assert_eq!(entries[1].request.content, "Last");
+        assert_eq!(entries.len(), 2);
+    }
+
+    #[test]
+    fn it_should_keep_checklists_nested_in_an_item_as_its_content() {
+        let input = "- [ ] Pack #trip\n  \
+                       - [ ] Passport\n  \
+                       - [x] Tickets\n    \
+                         - [ ] Print them\n\
+                     - [ ] Leave\n  \
+                     - [ ] Lock the door\n";
+        let deadline = end_of_day(NaiveDate::from_ymd_opt(2026, 10, 19).unwrap());
+        let entries = parse(input, Some(deadline)).unwrap();
+
+        assert_eq!(entries[0].request.summary, "Pack");
+        assert_eq!(
+            entries[0].request.content,
+            "- [ ] Passport\n- [x] Tickets\n  - [ ] Print them"
+        );
+        assert_eq!(entries[1].request.summary, "Leave");
+        assert_eq!(entries[1].request.content, "- [ ] Lock the door");
+        assert_eq!(entries.len(), 2);
+    }
+}
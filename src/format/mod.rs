@@ -0,0 +1,68 @@
+pub mod csv;
+pub mod duration;
+pub mod ics;
+pub mod inline;
+pub mod json;
+pub mod markdown;
+pub mod taskwarrior;
+pub mod todotxt;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde_json::Error as SerdeError;
+use snafu::prelude::*;
+
+use crate::domain::entity::Item;
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub enum ParseError {
+    #[snafu(display("Failed to parse record {record}: {reason}"))]
+    Invalid { record: usize, reason: String },
+    #[snafu(display("Record {record} has no deadline and no default deadline is given"))]
+    MissingDeadline { record: usize },
+}
+
+#[derive(Debug, Snafu)]
+pub enum RenderError {
+    #[snafu(display("Failed to write CSV: {source}"))]
+    Csv { source: ::csv::Error },
+    #[snafu(display("Failed to dump items to JSON: {source}"))]
+    Json { source: SerdeError },
+}
+
+/// Date-only deadlines fall due at the very end of that day.
+pub fn end_of_day(date: NaiveDate) -> NaiveDateTime {
+    date.and_hms_opt(23, 59, 59).unwrap()
+}
+
+/// Maps todo.txt style priority letters onto [-3, 3]: `A`-`C` are above the default
+/// priority and `D`-`F` below it, while any later letter is the lowest priority.
+pub fn priority_from_letter(letter: char) -> Option<i32> {
+    match letter {
+        'A' => Some(3),
+        'B' => Some(2),
+        'C' => Some(1),
+        'D' => Some(-1),
+        'E' => Some(-2),
+        'F'..='Z' => Some(-3),
+        _ => None,
+    }
+}
+
+pub fn priority_to_letter(priority: i32) -> Option<char> {
+    match priority {
+        3 => Some('A'),
+        2 => Some('B'),
+        1 => Some('C'),
+        -1 => Some('D'),
+        -2 => Some('E'),
+        -3 => Some('F'),
+        _ => None,
+    }
+}
+
+/// Tags in a stable order, as exported files should not depend on hashing.
+pub fn sorted_tags(item: &Item) -> Vec<&str> {
+    let mut tags = item.tags().iter().map(String::as_str).collect::<Vec<_>>();
+    tags.sort_unstable();
+    tags
+}
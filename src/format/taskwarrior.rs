@@ -0,0 +1,297 @@
+use std::cell::Cell;
+use std::fmt;
+
+use chrono::{Local, NaiveDateTime, TimeZone, Utc};
+use serde::de::{Deserializer, SeqAccess, Visitor};
+use serde::Deserialize;
+use serde_json::Value;
+use snafu::prelude::*;
+
+use crate::domain::entity::{Group, TagSet};
+use crate::domain::usecase::import::Entry;
+use crate::domain::usecase::plan::Request;
+
+use super::{MissingDeadlineSnafu, ParseError};
+
+#[derive(Debug, Deserialize)]
+struct Task {
+    description: String,
+    #[serde(default)]
+    status: String,
+    project: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    priority: Option<String>,
+    due: Option<String>,
+    end: Option<String>,
+    #[serde(default)]
+    annotations: Vec<Annotation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Annotation {
+    description: String,
+}
+
+/// Parses the output of `task export`, which is either a JSON array or, for older
+/// versions, one JSON object per line. Recurring templates are skipped since their
+/// pending instances are exported as well.
+pub fn parse(
+    input: &str,
+    default_deadline: Option<NaiveDateTime>,
+) -> Result<Vec<Entry>, ParseError> {
+    // Tasks are numbered before recurring templates are skipped, so that records point
+    // at the input.
+    let tasks = if input.trim_start().starts_with('[') {
+        records(input)?
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| (index + 1, serde_json::from_value::<Task>(value)))
+            .collect::<Vec<_>>()
+    } else {
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| (index + 1, serde_json::from_str::<Task>(line)))
+            .collect()
+    };
+
+    let mut entries = Vec::new();
+    for (record, task) in tasks {
+        let task = task.map_err(|err| ParseError::Invalid {
+            record,
+            reason: err.to_string(),
+        })?;
+
+        if task.status != "recurring" {
+            entries.push(convert(task, record, default_deadline)?);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Splits a JSON array into its elements. A malformed array is reported at the element
+/// being read, rather than at a line which may hold the whole export.
+fn records(input: &str) -> Result<Vec<Value>, ParseError> {
+    struct Records<'a>(&'a Cell<usize>);
+
+    impl<'de> Visitor<'de> for Records<'_> {
+        type Value = Vec<Value>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an array of tasks")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<Value>, A::Error> {
+            let mut values = Vec::new();
+            loop {
+                self.0.set(values.len() + 1);
+                match seq.next_element()? {
+                    Some(value) => values.push(value),
+                    None => return Ok(values),
+                }
+            }
+        }
+    }
+
+    let record = Cell::new(1);
+    let mut deserializer = serde_json::Deserializer::from_str(input);
+    deserializer
+        .deserialize_seq(Records(&record))
+        .and_then(|values| deserializer.end().map(|()| values))
+        .map_err(|err| ParseError::Invalid {
+            record: record.get(),
+            reason: err.to_string(),
+        })
+}
+
+fn convert(
+    task: Task,
+    record: usize,
+    default_deadline: Option<NaiveDateTime>,
+) -> Result<Entry, ParseError> {
+    let group = match task.status.as_str() {
+        "" | "pending" | "waiting" => Group::Planned,
+        "completed" => Group::Finished,
+        "deleted" => Group::Canceled,
+        status => {
+            return Err(ParseError::Invalid {
+                record,
+                reason: format!("unknown status `{status}`"),
+            })
+        }
+    };
+
+    let priority = match task.priority.as_deref() {
+        None | Some("") => 0,
+        Some("H") => 3,
+        Some("M") => 1,
+        Some("L") => -1,
+        Some(priority) => {
+            return Err(ParseError::Invalid {
+                record,
+                reason: format!("unknown priority `{priority}`"),
+            })
+        }
+    };
+
+    let deadline = match task.due {
+        Some(due) => Some(parse_date(&due).ok_or_else(|| ParseError::Invalid {
+            record,
+            reason: format!("invalid due date `{due}`"),
+        })?),
+        None => None,
+    };
+
+    // Pending tasks may keep the end of a former completion, which doesn't close them.
+    let closed = match task.end {
+        Some(end) if group != Group::Planned => {
+            Some(parse_date(&end).ok_or_else(|| ParseError::Invalid {
+                record,
+                reason: format!("invalid end date `{end}`"),
+            })?)
+        }
+        _ => None,
+    };
+
+    let deadline = deadline
+        .or(default_deadline)
+        .context(MissingDeadlineSnafu { record })?;
+
+    let mut tags: TagSet = task.tags.into_iter().collect();
+    tags.extend(task.project);
+
+    let content = task
+        .annotations
+        .into_iter()
+        .map(|annotation| annotation.description)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(Entry {
+        group,
+        request: Request {
+            summary: task.description,
+            content,
+            deadline,
+            tags,
+            priority,
+            estimate: None,
+        },
+        closed,
+    })
+}
+
+/// Taskwarrior stores timestamps in UTC, while deadlines are kept in local time.
+fn parse_date(value: &str) -> Option<NaiveDateTime> {
+    let utc = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ").ok()?;
+    Some(
+        Utc.from_utc_datetime(&utc)
+            .with_timezone(&Local)
+            .naive_local(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_map_tasks_from_an_exported_array() {
+        let input = serde_json::json!([
+            {
+                "uuid": "a",
+                "description": "Fix the fence",
+                "status": "pending",
+                "project": "home",
+                "tags": ["outdoor"],
+                "priority": "H",
+                "due": "20230618T120000Z",
+                "annotations": [{ "entry": "20230601T000000Z", "description": "Buy nails" }]
+            },
+            {
+                "uuid": "b",
+                "description": "Old",
+                "status": "completed",
+                "due": "20230618T120000Z",
+                "end": "20230617T080000Z"
+            },
+            { "uuid": "c", "description": "Template", "status": "recurring" },
+            { "uuid": "d", "description": "Gone", "status": "deleted", "priority": "L" }
+        ])
+        .to_string();
+
+        let deadline = parse_date("20230618T120000Z").unwrap();
+        let entries = parse(&input, Some(deadline)).unwrap();
+
+        assert_eq!(
+            entries[0],
+            Entry {
+                group: Group::Planned,
+                request: Request {
+                    summary: "Fix the fence".to_owned(),
+                    content: "Buy nails".to_owned(),
+                    deadline,
+                    tags: ["home", "outdoor"].iter().map(|&t| t.to_owned()).collect(),
+                    priority: 3,
+                    estimate: None,
+                },
+                closed: None,
+            }
+        );
+        assert_eq!(entries[1].group, Group::Finished);
+        assert_eq!(entries[1].closed, parse_date("20230617T080000Z"));
+        assert_eq!(entries[2].closed, None);
+        assert_eq!(entries[2].group, Group::Canceled);
+        assert_eq!(entries[2].request.priority, -1);
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn it_should_report_a_task_of_a_line_export_by_its_line() {
+        let input = "{\"description\":\"A\",\"status\":\"pending\",\"due\":\"20230618T120000Z\"}\n\
+                     {\"description\":\"B\",\"status\":\"waiting\"}\n";
+
+        assert_eq!(
+            parse(input, None),
+            Err(ParseError::MissingDeadline { record: 2 })
+        );
+    }
+
+    #[test]
+    fn it_should_number_records_as_they_are_in_the_input() {
+        let input = serde_json::json!([
+            { "description": "Template", "status": "recurring" },
+            { "description": "A", "status": "pending", "due": "20230618T120000Z" },
+            { "description": "B", "status": "pending" }
+        ])
+        .to_string();
+
+        assert_eq!(
+            parse(&input, None),
+            Err(ParseError::MissingDeadline { record: 3 })
+        );
+    }
+
+    #[test]
+    fn it_should_report_a_broken_task_of_an_array_by_its_position() {
+        let input = "[{\"description\":\"A\"},{\"description\":\"B\",},{\"description\":\"C\"}]";
+
+        assert!(matches!(
+            parse(input, None),
+            Err(ParseError::Invalid { record: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn it_should_reject_an_invalid_end_date() {
+        let input = "{\"description\":\"A\",\"status\":\"completed\",\"end\":\"yesterday\"}\n";
+
+        assert!(matches!(
+            parse(input, parse_date("20230618T120000Z")),
+            Err(ParseError::Invalid { record: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,228 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use snafu::prelude::*;
+
+use crate::domain::entity::{Group, Item, TagSet};
+use crate::domain::usecase::import::Entry;
+use crate::domain::usecase::plan::Request;
+
+use super::{
+    end_of_day, priority_from_letter, priority_to_letter, sorted_tags, MissingDeadlineSnafu,
+    ParseError,
+};
+
+pub fn parse(
+    input: &str,
+    default_deadline: Option<NaiveDateTime>,
+) -> Result<Vec<Entry>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| parse_line(line, index + 1, default_deadline))
+        .collect()
+}
+
+/// Writes one task per line. Tags become projects, and closed items keep their
+/// priority as `pri:` since todo.txt drops it on completion. Canceled items are
+/// completed tasks marked with `status:canceled`.
+pub fn render(items: &[(Group, Item)]) -> String {
+    let mut output = String::new();
+
+    for (group, item) in items {
+        let mut words = Vec::new();
+        let letter = priority_to_letter(item.priority().value());
+
+        match (group, letter) {
+            (Group::Planned, Some(letter)) => words.push(format!("({letter})")),
+            (Group::Planned, None) => {}
+            (_, _) => words.push("x".to_owned()),
+        }
+
+        words.push(item.summary().to_owned());
+        words.extend(sorted_tags(item).into_iter().map(|tag| format!("+{tag}")));
+        words.push(format!("due:{}", item.deadline().format("%Y-%m-%d")));
+
+        if let (Group::Finished | Group::Canceled, Some(letter)) = (group, letter) {
+            words.push(format!("pri:{letter}"));
+        }
+
+        if *group == Group::Canceled {
+            words.push("status:canceled".to_owned());
+        }
+
+        output.push_str(&words.join(" "));
+        output.push('\n');
+    }
+
+    output
+}
+
+fn parse_line(
+    line: &str,
+    record: usize,
+    default_deadline: Option<NaiveDateTime>,
+) -> Result<Entry, ParseError> {
+    let mut words = line.split_whitespace().peekable();
+    let mut group = Group::Planned;
+    let mut priority = 0;
+
+    if words.next_if_eq(&"x").is_some() {
+        group = Group::Finished;
+        // Completion date, then creation date
+        words.next_if(|word| parse_date(word).is_some());
+        words.next_if(|word| parse_date(word).is_some());
+    } else {
+        if let Some(letter) = words.peek().and_then(|word| parse_priority(word)) {
+            priority = letter;
+            words.next();
+        }
+
+        // Creation date
+        words.next_if(|word| parse_date(word).is_some());
+    }
+
+    let mut summary = Vec::new();
+    let mut tags = TagSet::new();
+    let mut deadline = None;
+
+    for word in words {
+        if let Some(tag) = word
+            .strip_prefix('+')
+            .or_else(|| word.strip_prefix('@'))
+            .filter(|tag| !tag.is_empty())
+        {
+            tags.insert(tag.to_owned());
+        } else if let Some(date) = word.strip_prefix("due:") {
+            let date = parse_date(date).ok_or_else(|| ParseError::Invalid {
+                record,
+                reason: format!("invalid due date `{date}`"),
+            })?;
+            deadline = Some(end_of_day(date));
+        } else if let Some(letter) = word.strip_prefix("pri:") {
+            priority = letter
+                .chars()
+                .next()
+                .and_then(priority_from_letter)
+                .ok_or_else(|| ParseError::Invalid {
+                    record,
+                    reason: format!("invalid priority `{letter}`"),
+                })?;
+        } else if word == "status:canceled" {
+            group = Group::Canceled;
+        } else {
+            summary.push(word);
+        }
+    }
+
+    let deadline = deadline
+        .or(default_deadline)
+        .context(MissingDeadlineSnafu { record })?;
+
+    Ok(Entry {
+        group,
+        request: Request {
+            summary: summary.join(" "),
+            content: String::new(),
+            deadline,
+            tags,
+            priority,
+            estimate: None,
+        },
+        closed: None,
+    })
+}
+
+fn parse_priority(word: &str) -> Option<i32> {
+    let letter = word.strip_prefix('(')?.strip_suffix(')')?;
+
+    if letter.len() == 1 {
+        priority_from_letter(letter.chars().next()?)
+    } else {
+        None
+    }
+}
+
+fn parse_date(word: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(word, "%Y-%m-%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_map_projects_contexts_priority_and_due_date() {
+        let input = "(A) 2023-06-01 Call mom +family @phone due:2023-06-18\n";
+        let entries = parse(input, None).unwrap();
+
+        assert_eq!(
+            entries,
+            vec![Entry {
+                group: Group::Planned,
+                request: Request {
+                    summary: "Call mom".to_owned(),
+                    content: String::new(),
+                    deadline: end_of_day(NaiveDate::from_ymd_opt(2023, 6, 18).unwrap()),
+                    tags: ["family", "phone"].iter().map(|&t| t.to_owned()).collect(),
+                    priority: 3,
+                    estimate: None,
+                },
+                closed: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn it_should_put_completed_tasks_into_finished_or_canceled_group() {
+        let input = "x 2023-06-02 2023-06-01 Pay rent due:2023-06-01\n\n\
+                     x 2023-06-02 Water plants status:canceled pri:B due:2023-06-01\n";
+        let entries = parse(input, None).unwrap();
+
+        assert_eq!(entries[0].group, Group::Finished);
+        assert_eq!(entries[0].request.summary, "Pay rent");
+        assert_eq!(entries[1].group, Group::Canceled);
+        assert_eq!(entries[1].request.summary, "Water plants");
+        assert_eq!(entries[1].request.priority, 2);
+    }
+
+    #[test]
+    fn it_should_read_back_rendered_items() {
+        let deadline = end_of_day(NaiveDate::from_ymd_opt(2023, 6, 18).unwrap());
+        let tags: TagSet = ["home"].iter().map(|&t| t.to_owned()).collect();
+        let items = [
+            (
+                Group::Planned,
+                Item::new("A", "", deadline, tags.clone(), 3.try_into().unwrap()),
+            ),
+            (
+                Group::Canceled,
+                Item::new("B", "", deadline, tags, (-2).try_into().unwrap()),
+            ),
+        ];
+
+        let output = render(&items);
+        assert_eq!(
+            output,
+            "(A) A +home due:2023-06-18\nx B +home due:2023-06-18 pri:E status:canceled\n"
+        );
+
+        let entries = parse(&output, None).unwrap();
+        assert_eq!(entries[0].group, Group::Planned);
+        assert_eq!(entries[0].request.priority, 3);
+        assert_eq!(entries[1].group, Group::Canceled);
+        assert_eq!(entries[1].request.priority, -2);
+        assert_eq!(entries[1].request.deadline, deadline);
+    }
+
+    #[test]
+    fn it_should_fall_back_to_default_deadline_or_report_its_absence() {
+        let deadline = end_of_day(NaiveDate::from_ymd_opt(2023, 6, 18).unwrap());
+        let entries = parse("Read a book", Some(deadline)).unwrap();
+        assert_eq!(entries[0].request.deadline, deadline);
+
+        assert_eq!(
+            parse("\nRead a book", None),
+            Err(ParseError::MissingDeadline { record: 2 })
+        );
+    }
+}
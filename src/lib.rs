@@ -1,3 +1,8 @@
 pub mod cli;
 pub mod domain;
+pub mod format;
+pub mod notify;
 pub mod repository;
+pub mod server;
+pub mod sync;
+pub mod tui;
@@ -1,38 +1,100 @@
 use std::error::Error;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::process::ExitCode;
 
 use clap::Parser;
 
-use todo::cli::{self, Arg};
-use todo::repository::id::TriePool;
-use todo::repository::item::LocalPool;
-use todo::repository::{Data, Repository};
+use todo::cli::error::Error as CliError;
+use todo::cli::{self, completions, doctor, storage, workspace, Arg, Command, Context};
+use todo::repository::workflow;
+use todo::repository::workspace::Workspaces;
+use todo::tui;
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let Arg { storage, command } = Arg::parse();
-    let repo = init(storage);
+fn main() -> ExitCode {
+    let arg = Arg::parse();
+    let verbose = arg.verbose;
 
-    let command = match command {
-        Some(cmd) => cmd,
-        None => return Ok(()),
+    match run(arg) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            let err = CliError::from(err);
+            err.report(verbose);
+            err.exit_code()
+        }
+    }
+}
+
+fn run(arg: Arg) -> Result<(), Box<dyn Error>> {
+    let Arg {
+        storage,
+        workspace,
+        keyfile,
+        verbose,
+        command,
+    } = arg;
+
+    let workspaces = Workspaces::new(storage.unwrap_or(default_path()));
+
+    // These commands run without opening any pool.
+    match command {
+        Some(Command::Completions(args)) => return completions::run(args),
+        Some(Command::Workspace(args)) => return workspace::run(&workspaces, None, args),
+        _ => {}
+    }
+
+    let workspace = workspace.unwrap_or_else(|| workspaces.current());
+    let storage = workspaces.path(&workspace)?;
+    let states = workspaces.root().join("states.json");
+    let workflow = workflow::load(&states).map_err(|err| {
+        CliError::new(err).context(format!("Failed to load {}", states.display()))
+    })?;
+
+    // Pools are converted and repaired while closed, as open ones are written back on
+    // exit, and ones that fail to open can still be repaired.
+    match command {
+        Some(Command::Storage(args)) => {
+            return storage::run(&storage, &workflow, keyfile.as_deref(), args)
+        }
+        Some(Command::Doctor(args)) => {
+            return doctor::run(&storage, &workflow, keyfile.as_deref(), args)
+        }
+        _ => {}
+    }
+
+    let cipher = if matches!(command, Some(Command::Complete(_))) {
+        // Completing runs on every Tab with its output hidden, so it offers nothing
+        // rather than waiting for a passphrase.
+        match storage::unlock_quietly(&storage, &workflow, keyfile.as_deref()) {
+            Ok(cipher) => cipher,
+            Err(_) => return Ok(()),
+        }
+    } else {
+        storage::unlock(&storage, &workflow, keyfile.as_deref())?
     };
+    let repo = cli::open(&storage, &workflow, cipher.clone())?;
 
-    cli::run(repo, command)
-}
+    let context = Context {
+        workspaces,
+        workflow,
+        workspace,
+        storage,
+        keyfile,
+        cipher,
+        verbose,
+    };
+
+    let result = match command {
+        Some(cmd) => cli::run(repo.clone(), &context, cmd),
+        None => tui::run(repo.clone(), &context.storage),
+    };
+
+    // Saved even when the command failed, as some items may have changed already.
+    let saved = repo.sync().map_err(|err| {
+        CliError::new(err).context(format!("Failed to save {}", context.storage.display()))
+    });
 
-fn init(storage: Option<PathBuf>) -> Arc<Repository> {
-    let dir = storage.unwrap_or(default_path());
-    let planned_pool_path = dir.join("planned.json");
-    let finished_pool_path = dir.join("finished.json");
-    let canceled_pool_path = dir.join("canceled.json");
-
-    Arc::new(Repository::new(Data {
-        planned: Box::new(LocalPool::open(planned_pool_path).unwrap()),
-        finished: Box::new(LocalPool::open(finished_pool_path).unwrap()),
-        canceled: Box::new(LocalPool::open(canceled_pool_path).unwrap()),
-        ids: Box::new(TriePool::new()),
-    }))
+    result?;
+    Ok(saved?)
 }
 
 fn default_path() -> PathBuf {
@@ -0,0 +1,46 @@
+use std::process::Command;
+
+use snafu::prelude::*;
+
+use crate::domain::usecase::remind::Reminder;
+
+use super::{NotifyError, Sink, SpawnSnafu, StatusSnafu};
+
+/// Runs a shell command per reminder, e.g. `notify-send "$TODO_MESSAGE"`. The reminder
+/// is passed in the `TODO_ID`, `TODO_SUMMARY`, `TODO_DEADLINE` and `TODO_MESSAGE`
+/// environment variables.
+pub struct CommandSink {
+    command: String,
+}
+
+impl CommandSink {
+    pub fn new(command: String) -> Self {
+        Self { command }
+    }
+}
+
+impl Sink for CommandSink {
+    fn notify(&mut self, reminder: &Reminder, message: &str) -> Result<(), NotifyError> {
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .env("TODO_ID", reminder.id.to_string())
+            .env("TODO_SUMMARY", &reminder.summary)
+            .env("TODO_DEADLINE", reminder.deadline.to_string())
+            .env("TODO_MESSAGE", message)
+            .status()
+            .context(SpawnSnafu {
+                command: &self.command,
+            })?;
+
+        ensure!(
+            status.success(),
+            StatusSnafu {
+                command: &self.command,
+                status
+            }
+        );
+
+        Ok(())
+    }
+}
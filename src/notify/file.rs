@@ -0,0 +1,34 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use chrono::Local;
+use snafu::prelude::*;
+
+use crate::domain::usecase::remind::Reminder;
+
+use super::{NotifyError, Sink, WriteSnafu};
+
+/// Appends a timestamped line per reminder.
+pub struct FileSink {
+    path: PathBuf,
+}
+
+impl FileSink {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl Sink for FileSink {
+    fn notify(&mut self, _reminder: &Reminder, message: &str) -> Result<(), NotifyError> {
+        let mut file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&self.path)
+            .context(WriteSnafu { path: &self.path })?;
+
+        let now = Local::now().naive_local().format("%Y-%m-%d %H:%M:%S");
+        writeln!(file, "{now} {message}").context(WriteSnafu { path: &self.path })
+    }
+}
@@ -0,0 +1,43 @@
+mod command;
+mod file;
+mod stdout;
+
+use std::io::Error as IoError;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+
+use chrono::NaiveDateTime;
+use snafu::prelude::*;
+
+use crate::domain::usecase::remind::Reminder;
+use crate::format::duration;
+
+pub use command::CommandSink;
+pub use file::FileSink;
+pub use stdout::StdoutSink;
+
+#[derive(Debug, Snafu)]
+pub enum NotifyError {
+    #[snafu(display("Failed to run `{command}`: {source}"))]
+    Spawn { command: String, source: IoError },
+    #[snafu(display("`{command}` exited with {status}"))]
+    Status { command: String, status: ExitStatus },
+    #[snafu(display("Failed to write reminders to {}: {source}", path.display()))]
+    Write { path: PathBuf, source: IoError },
+}
+
+/// Somewhere to deliver reminders to, such as the terminal, a log file or a desktop
+/// notification command.
+pub trait Sink {
+    fn notify(&mut self, reminder: &Reminder, message: &str) -> Result<(), NotifyError>;
+}
+
+/// A one-line message such as `Buy milk is due in 5h (2026-10-20 23:59:59)`.
+pub fn message(reminder: &Reminder, now: NaiveDateTime) -> String {
+    format!(
+        "{} is due in {} ({})",
+        reminder.summary,
+        duration::render(reminder.deadline - now),
+        reminder.deadline
+    )
+}
@@ -0,0 +1,12 @@
+use crate::domain::usecase::remind::Reminder;
+
+use super::{NotifyError, Sink};
+
+pub struct StdoutSink;
+
+impl Sink for StdoutSink {
+    fn notify(&mut self, _reminder: &Reminder, message: &str) -> Result<(), NotifyError> {
+        println!("{message}");
+        Ok(())
+    }
+}
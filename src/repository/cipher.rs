@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use snafu::prelude::*;
+
+/// Starts every encrypted file, followed by the key derivation parameters, the salt and
+/// the nonce, so that a file can be decrypted with nothing but the passphrase.
+pub const MAGIC: &[u8; 8] = b"TODOENC\x01";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 3 * 4 + SALT_LEN + NONCE_LEN;
+
+/// How far key derivation parameters read from a file may exceed the defaults. They're
+/// used before the data is authenticated, so a tampered file mustn't make deriving the
+/// key take all memory or forever.
+const COST_FACTOR: u32 = 4;
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub enum CipherError {
+    #[snafu(display("Encrypted data is truncated or has an invalid header"))]
+    Corrupt,
+    #[snafu(display("Wrong passphrase, or the encrypted data was tampered with"))]
+    Passphrase,
+    #[snafu(display(
+        "Encrypted data asks for too costly key derivation: {memory} KiB, {iterations} \
+         iterations, {lanes} lanes"
+    ))]
+    Costly {
+        memory: u32,
+        iterations: u32,
+        lanes: u32,
+    },
+    #[snafu(display("Failed to derive a key from the passphrase: {reason}"))]
+    Derive { reason: String },
+}
+
+/// Key derivation parameters and salt, which together with the passphrase make a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Setup {
+    memory: u32,
+    iterations: u32,
+    lanes: u32,
+    salt: [u8; SALT_LEN],
+}
+
+/// Encrypts data with ChaCha20-Poly1305 under a key derived with Argon2id from a
+/// passphrase. Deriving is slow on purpose, so keys are cached and everything written
+/// by one cipher shares its salt, while each write gets a fresh nonce.
+pub struct Cipher {
+    passphrase: String,
+    setup: Setup,
+    keys: Mutex<HashMap<Setup, [u8; 32]>>,
+}
+
+impl Cipher {
+    pub fn new(passphrase: &str) -> Self {
+        Self::with_params(passphrase, Params::default())
+    }
+
+    pub fn with_params(passphrase: &str, params: Params) -> Self {
+        let mut salt = [0; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+
+        Self {
+            passphrase: passphrase.to_owned(),
+            setup: Setup {
+                memory: params.m_cost(),
+                iterations: params.t_cost(),
+                lanes: params.p_cost(),
+                salt,
+            },
+            keys: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CipherError> {
+        let key = self.key(self.setup)?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let mut data = header(self.setup);
+        data.extend_from_slice(&nonce);
+
+        // The header is authenticated too, so that its parameters can't be swapped.
+        let payload = Payload {
+            msg: plaintext,
+            aad: &data,
+        };
+        let ciphertext = ChaCha20Poly1305::new(Key::from_slice(&key))
+            .encrypt(&nonce, payload)
+            .map_err(|_| CipherError::Corrupt)?;
+
+        data.extend(ciphertext);
+        Ok(data)
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, CipherError> {
+        ensure!(is_encrypted(data) && data.len() >= HEADER_LEN, CorruptSnafu);
+
+        let word = |at: usize| u32::from_le_bytes(data[at..at + 4].try_into().unwrap());
+        let start = MAGIC.len() + 3 * 4;
+        let setup = Setup {
+            memory: word(MAGIC.len()),
+            iterations: word(MAGIC.len() + 4),
+            lanes: word(MAGIC.len() + 8),
+            salt: data[start..start + SALT_LEN].try_into().unwrap(),
+        };
+        ensure!(
+            setup.memory <= Params::DEFAULT_M_COST * COST_FACTOR
+                && setup.iterations <= Params::DEFAULT_T_COST * COST_FACTOR
+                && setup.lanes <= Params::DEFAULT_P_COST * COST_FACTOR,
+            CostlySnafu {
+                memory: setup.memory,
+                iterations: setup.iterations,
+                lanes: setup.lanes,
+            }
+        );
+
+        let key = self.key(setup)?;
+        let nonce = Nonce::from_slice(&data[start + SALT_LEN..HEADER_LEN]);
+        let payload = Payload {
+            msg: &data[HEADER_LEN..],
+            aad: &data[..HEADER_LEN],
+        };
+
+        ChaCha20Poly1305::new(Key::from_slice(&key))
+            .decrypt(nonce, payload)
+            .map_err(|_| CipherError::Passphrase)
+    }
+
+    fn key(&self, setup: Setup) -> Result<[u8; 32], CipherError> {
+        let mut keys = self.keys.lock().unwrap();
+        if let Some(key) = keys.get(&setup) {
+            return Ok(*key);
+        }
+
+        let params = Params::new(setup.memory, setup.iterations, setup.lanes, Some(32))
+            .map_err(|_| CipherError::Corrupt)?;
+
+        let mut key = [0; 32];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(self.passphrase.as_bytes(), &setup.salt, &mut key)
+            .map_err(|err| CipherError::Derive {
+                reason: err.to_string(),
+            })?;
+
+        keys.insert(setup, key);
+        Ok(key)
+    }
+}
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+fn header(setup: Setup) -> Vec<u8> {
+    let mut data = Vec::with_capacity(HEADER_LEN);
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&setup.memory.to_le_bytes());
+    data.extend_from_slice(&setup.iterations.to_le_bytes());
+    data.extend_from_slice(&setup.lanes.to_le_bytes());
+    data.extend_from_slice(&setup.salt);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheap parameters, since the default ones are slow without optimizations.
+    fn cipher(passphrase: &str) -> Cipher {
+        Cipher::with_params(passphrase, Params::new(64, 1, 1, None).unwrap())
+    }
+
+    #[test]
+    fn it_should_decrypt_what_it_encrypted_with_the_same_passphrase_only() {
+        let data = cipher("secret").encrypt(b"{\"items\":[]}").unwrap();
+
+        assert!(is_encrypted(&data));
+        assert!(!is_encrypted(b"{\"items\":[]}"));
+        assert_eq!(cipher("secret").decrypt(&data).unwrap(), b"{\"items\":[]}");
+        assert_eq!(cipher("guess").decrypt(&data), Err(CipherError::Passphrase));
+    }
+
+    #[test]
+    fn it_should_reject_tampered_or_truncated_data() {
+        let cipher = cipher("secret");
+        let mut data = cipher.encrypt(b"hello").unwrap();
+
+        assert_eq!(
+            cipher.decrypt(&data[..HEADER_LEN - 1]),
+            Err(CipherError::Corrupt)
+        );
+
+        let last = data.len() - 1;
+        data[last] ^= 1;
+        assert_eq!(cipher.decrypt(&data), Err(CipherError::Passphrase));
+    }
+
+    #[test]
+    fn it_should_refuse_costly_parameters_before_deriving_a_key() {
+        let cipher = cipher("secret");
+        let mut data = cipher.encrypt(b"hello").unwrap();
+
+        // Four billion passes over the memory would never end.
+        data[MAGIC.len() + 4..MAGIC.len() + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert_eq!(
+            cipher.decrypt(&data),
+            Err(CipherError::Costly {
+                memory: 64,
+                iterations: u32::MAX,
+                lanes: 1,
+            })
+        );
+    }
+}
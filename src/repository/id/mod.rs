@@ -2,7 +2,7 @@ mod trie;
 
 pub use trie::{Trie, TriePool};
 
-pub trait Pool {
+pub trait Pool: Send + Sync {
     fn add(&mut self, id: u64) -> bool;
 
     fn remove(&mut self, id: u64) -> bool;
@@ -0,0 +1,567 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs;
+use std::io::Error as IoError;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use snafu::prelude::*;
+
+use crate::domain::entity::{Priority, Workflow};
+use crate::repository::cipher::Cipher;
+
+use super::local::{Data, LocalPool, RawItem};
+use super::migrate::{self, MigrateError};
+use super::{InitError, SyncError};
+
+/// Where entries taken out of pools are kept, so that nothing is lost by a repair.
+pub const QUARANTINE: &str = "quarantine.json";
+
+#[derive(Debug, Snafu)]
+pub enum DoctorError {
+    #[snafu(display("Failed to read {QUARANTINE}: {source}"))]
+    Load { source: InitError },
+    #[snafu(display("{QUARANTINE} is invalid: {source}"))]
+    Corrupt { source: serde_json::Error },
+    #[snafu(display("Failed to write {}: {source}", path.display()))]
+    Save { path: PathBuf, source: SyncError },
+    #[snafu(display("Failed to remove {}: {source}", path.display()))]
+    Remove { path: PathBuf, source: IoError },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// The state whose pool the problem is in.
+    pub pool: String,
+    pub problem: Problem,
+}
+
+/// Entries are numbered from 1 in the order of the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// The file can't be read at all. Salvageable ones are invalid JSON, which can be
+    /// quarantined as a whole, while others such as a wrong passphrase are left alone.
+    Unreadable {
+        reason: String,
+        salvageable: bool,
+    },
+    Invalid {
+        entry: usize,
+        reason: String,
+    },
+    Priority {
+        entry: usize,
+        value: i32,
+    },
+    EmptySummary {
+        entry: usize,
+    },
+    /// Same ID as an earlier entry of the pool, which would shadow it when loaded.
+    Collision {
+        entry: usize,
+        id: u64,
+    },
+    /// Same ID as an item kept in another pool.
+    Duplicate {
+        entry: usize,
+        id: u64,
+        kept: String,
+    },
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Quarantine {
+    entries: Vec<Quarantined>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Quarantined {
+    pool: String,
+    reason: String,
+    /// The entry as it was, or the whole file as a string if it couldn't be parsed.
+    entry: Value,
+}
+
+/// The pools of a storage directory as found on disk, before any repair.
+pub struct Examination {
+    dir: PathBuf,
+    cipher: Option<Arc<Cipher>>,
+    pools: Vec<Scanned>,
+    findings: Vec<Finding>,
+}
+
+struct Scanned {
+    name: String,
+    path: PathBuf,
+    /// The text of the file if it couldn't be parsed, otherwise its entries.
+    content: Result<Vec<Value>, String>,
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Unreadable { reason, .. } => write!(f, "can't be read: {reason}"),
+            Self::Invalid { entry, reason } => write!(f, "item {entry} is invalid: {reason}"),
+            Self::Priority { entry, value } => write!(
+                f,
+                "item {entry} has priority {value} out of [{}, {}]",
+                Priority::MIN,
+                Priority::MAX
+            ),
+            Self::EmptySummary { entry } => write!(f, "item {entry} has an empty summary"),
+            Self::Collision { entry, id } => {
+                write!(f, "item {entry} has ID {id} like an earlier item")
+            }
+            Self::Duplicate { entry, id, kept } => {
+                write!(f, "item {entry} with ID {id} is also in {kept}")
+            }
+        }
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}: {}", self.pool, self.problem)
+    }
+}
+
+impl Problem {
+    pub fn is_fixable(&self) -> bool {
+        !matches!(
+            self,
+            Self::Unreadable {
+                salvageable: false,
+                ..
+            }
+        )
+    }
+
+    /// What `repair` does about the problem.
+    pub fn remedy(&self) -> &'static str {
+        match self {
+            Self::Unreadable {
+                salvageable: false, ..
+            } => "left alone",
+            Self::Unreadable { .. } => "file quarantined",
+            Self::Priority { .. } => "priority clamped",
+            _ => "item quarantined",
+        }
+    }
+
+    fn entry(&self) -> Option<usize> {
+        match self {
+            Self::Invalid { entry, .. }
+            | Self::Priority { entry, .. }
+            | Self::EmptySummary { entry }
+            | Self::Collision { entry, .. }
+            | Self::Duplicate { entry, .. } => Some(*entry),
+            Self::Unreadable { .. } => None,
+        }
+    }
+}
+
+impl Examination {
+    /// Checks the pool file of every state in `dir`, decrypting them with `cipher`.
+    pub fn new(dir: &Path, workflow: &Workflow, cipher: Option<Arc<Cipher>>) -> Self {
+        let texts = workflow
+            .names()
+            .map(|name| (name, dir.join(format!("{name}.json"))))
+            .filter(|(_, path)| path.is_file())
+            .map(|(name, path)| {
+                let text = LocalPool::read_file(path.clone(), cipher.as_deref());
+                (name.to_owned(), path, text)
+            })
+            .collect();
+
+        let (pools, findings) = diagnose(workflow, texts);
+
+        Self {
+            dir: dir.to_owned(),
+            cipher,
+            pools,
+            findings,
+        }
+    }
+
+    pub fn findings(&self) -> &[Finding] {
+        &self.findings
+    }
+
+    /// Applies the remedy of every finding and returns how many entries were
+    /// quarantined. They're appended to the quarantine file before the pools they were
+    /// taken from are rewritten.
+    pub fn repair(self) -> Result<usize, DoctorError> {
+        let cipher = self.cipher.as_deref();
+        let mut quarantine = Vec::new();
+        let mut rewrites = Vec::new();
+        let mut removals = Vec::new();
+
+        for pool in self.pools {
+            let findings = self
+                .findings
+                .iter()
+                .filter(|finding| finding.pool == pool.name)
+                .map(|finding| &finding.problem)
+                .collect::<Vec<_>>();
+
+            if findings.iter().all(|problem| !problem.is_fixable()) {
+                continue;
+            }
+
+            let entries = match pool.content {
+                Ok(entries) => entries,
+                Err(text) => {
+                    let reason = findings.first().map(ToString::to_string);
+                    quarantine.push(Quarantined {
+                        pool: pool.name,
+                        reason: reason.unwrap_or_default(),
+                        entry: Value::String(text),
+                    });
+                    removals.push(pool.path);
+                    continue;
+                }
+            };
+
+            let mut items = HashSet::new();
+            let mut changed = false;
+
+            for (index, entry) in entries.into_iter().enumerate() {
+                let problems = findings
+                    .iter()
+                    .filter(|problem| problem.entry() == Some(index + 1))
+                    .collect::<Vec<_>>();
+
+                let fatal = problems
+                    .iter()
+                    .find(|problem| !matches!(problem, Problem::Priority { .. }));
+
+                if let Some(problem) = fatal {
+                    quarantine.push(Quarantined {
+                        pool: pool.name.clone(),
+                        reason: problem.to_string(),
+                        entry,
+                    });
+                    changed = true;
+                    continue;
+                }
+
+                let Ok(mut item) = serde_json::from_value::<RawItem>(entry) else {
+                    continue;
+                };
+
+                if !problems.is_empty() {
+                    let value = item.priority.value().clamp(Priority::MIN, Priority::MAX);
+                    item.priority = Priority::try_from(value).unwrap_or_default();
+                    changed = true;
+                }
+
+                items.insert(item);
+            }
+
+            if changed {
+                rewrites.push((pool.path, Data { items }));
+            }
+        }
+
+        let quarantined = quarantine.len();
+        let mut staged = Vec::new();
+
+        if quarantined > 0 {
+            let path = self.dir.join(QUARANTINE);
+            let json = LocalPool::read_file(path.clone(), cipher).context(LoadSnafu)?;
+
+            let mut existing = if json.is_empty() {
+                Quarantine::default()
+            } else {
+                serde_json::from_str::<Quarantine>(&json).context(CorruptSnafu)?
+            };
+            existing.entries.extend(quarantine);
+
+            let json = serde_json::to_string_pretty(&existing).context(CorruptSnafu)? + "\n";
+            staged.push(stage(path, json, cipher)?);
+        }
+
+        for (path, data) in rewrites {
+            let json = LocalPool::serialize(data).context(SaveSnafu { path: &path })?;
+            staged.push(stage(path, json, cipher)?);
+        }
+
+        // No file is replaced before all of them are written, like pools in a transaction.
+        for path in staged {
+            fs::rename(LocalPool::staged_path(&path), &path)
+                .map_err(|err| SyncError::Replace { source: err })
+                .context(SaveSnafu { path })?;
+        }
+
+        for path in removals {
+            fs::remove_file(&path).context(RemoveSnafu { path })?;
+        }
+
+        Ok(quarantined)
+    }
+}
+
+/// Writes the next version of the file at `path` next to it, and returns `path`.
+fn stage(path: PathBuf, json: String, cipher: Option<&Cipher>) -> Result<PathBuf, DoctorError> {
+    let bytes = match cipher {
+        Some(cipher) => cipher
+            .encrypt(json.as_bytes())
+            .map_err(|err| SyncError::Encrypt { source: err })
+            .context(SaveSnafu { path: &path })?,
+        None => json.into_bytes(),
+    };
+
+    LocalPool::sync_file(LocalPool::staged_path(&path), &bytes)
+        .context(SaveSnafu { path: &path })?;
+    Ok(path)
+}
+
+type Texts = Vec<(String, PathBuf, Result<String, InitError>)>;
+
+fn diagnose(workflow: &Workflow, texts: Texts) -> (Vec<Scanned>, Vec<Finding>) {
+    let mut pools = Vec::new();
+    let mut findings = Vec::new();
+    // The pool each kept item is in, by ID.
+    let mut kept = HashMap::<u64, String>::new();
+
+    // Closed items are kept over open ones with the same ID, as closing comes later.
+    let mut texts = texts;
+    texts.sort_by_key(|(name, _, _)| !workflow.is_final(name));
+
+    for (name, path, text) in texts {
+        let mut found = |problem| {
+            findings.push(Finding {
+                pool: name.clone(),
+                problem,
+            })
+        };
+
+        let parsed = text
+            .map_err(|err| (err.to_string(), None))
+            .and_then(|text| {
+                parse(&text).map_err(|(reason, salvageable)| (reason, salvageable.then_some(text)))
+            });
+
+        let entries = match parsed {
+            Ok(entries) => entries,
+            Err((reason, text)) => {
+                found(Problem::Unreadable {
+                    reason,
+                    salvageable: text.is_some(),
+                });
+                pools.push(Scanned {
+                    name,
+                    path,
+                    content: Err(text.unwrap_or_default()),
+                });
+                continue;
+            }
+        };
+
+        for (index, entry) in entries.iter().enumerate() {
+            let entry_no = index + 1;
+            let item = match serde_json::from_value::<RawItem>(entry.clone()) {
+                Ok(item) => item,
+                Err(err) => {
+                    found(Problem::Invalid {
+                        entry: entry_no,
+                        reason: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let value = item.priority.value();
+            if !(Priority::MIN..=Priority::MAX).contains(&value) {
+                found(Problem::Priority {
+                    entry: entry_no,
+                    value,
+                });
+            }
+
+            if item.summary.is_empty() {
+                found(Problem::EmptySummary { entry: entry_no });
+                continue;
+            }
+
+            let id = item.id();
+            match kept.get(&id) {
+                Some(pool) if *pool == name => found(Problem::Collision {
+                    entry: entry_no,
+                    id,
+                }),
+                Some(pool) => found(Problem::Duplicate {
+                    entry: entry_no,
+                    id,
+                    kept: pool.clone(),
+                }),
+                None => {
+                    kept.insert(id, name.clone());
+                }
+            }
+        }
+
+        pools.push(Scanned {
+            name,
+            path,
+            content: Ok(entries),
+        });
+    }
+
+    let position = |name: &str| workflow.names().position(|other| other == name);
+    findings.sort_by_key(|finding| position(&finding.pool));
+
+    (pools, findings)
+}
+
+/// Reads the entries of a pool file migrated to the current version. An error tells
+/// whether the file may be quarantined, which isn't the case for newer files.
+fn parse(text: &str) -> Result<Vec<Value>, (String, bool)> {
+    if text.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let value = serde_json::from_str::<Value>(text).map_err(|err| (err.to_string(), true))?;
+    let value = migrate::migrate(value).map_err(|err| {
+        let salvageable = !matches!(err, MigrateError::Newer { .. });
+        (err.to_string(), salvageable)
+    })?;
+
+    match value.get("items") {
+        Some(Value::Array(entries)) => Ok(entries.clone()),
+        _ => Err(("`items` isn't a list".to_owned(), true)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn item(summary: &str, priority: i32) -> Value {
+        json!({
+            "summary": summary,
+            "content": "",
+            "deadline": "2023-06-18T12:00:00",
+            "tags": [],
+            "priority": priority
+        })
+    }
+
+    fn text(entries: &[Value]) -> Result<String, InitError> {
+        Ok(json!({ "items": entries }).to_string())
+    }
+
+    fn problems(findings: &[Finding]) -> Vec<(&str, &Problem)> {
+        findings
+            .iter()
+            .map(|finding| (finding.pool.as_str(), &finding.problem))
+            .collect()
+    }
+
+    #[test]
+    fn it_should_find_invalid_items_and_duplicates() {
+        let planned = [
+            item("A", 0),
+            item("", 0),
+            item("B", 7),
+            json!({ "summary": "C" }),
+            item("A", 2),
+            item("D", 0),
+        ];
+        let finished = [item("D", 0)];
+        let texts = vec![
+            ("planned".to_owned(), PathBuf::new(), text(&planned)),
+            ("finished".to_owned(), PathBuf::new(), text(&finished)),
+            ("canceled".to_owned(), PathBuf::new(), Ok("{".to_owned())),
+        ];
+
+        let (_, findings) = diagnose(&Workflow::default(), texts);
+        let id = |value: &Value| {
+            serde_json::from_value::<RawItem>(value.clone())
+                .unwrap()
+                .id()
+        };
+
+        assert_eq!(findings.len(), 6);
+        assert_eq!(
+            problems(&findings)[..5],
+            [
+                ("planned", &Problem::EmptySummary { entry: 2 }),
+                ("planned", &Problem::Priority { entry: 3, value: 7 }),
+                (
+                    "planned",
+                    &Problem::Invalid {
+                        entry: 4,
+                        reason: "missing field `content`".to_owned()
+                    }
+                ),
+                (
+                    "planned",
+                    &Problem::Collision {
+                        entry: 5,
+                        id: id(&planned[0])
+                    }
+                ),
+                (
+                    "planned",
+                    &Problem::Duplicate {
+                        entry: 6,
+                        id: id(&planned[5]),
+                        kept: "finished".to_owned()
+                    }
+                ),
+            ]
+        );
+        assert!(matches!(
+            findings[5].problem,
+            Problem::Unreadable {
+                salvageable: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn it_should_quarantine_offending_items_and_clamp_priorities() {
+        let dir = std::env::temp_dir().join(format!("todo-doctor-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let planned = [item("A", 0), item("", 0), item("B", -9)];
+        fs::write(dir.join("planned.json"), text(&planned).unwrap()).unwrap();
+        fs::write(dir.join("canceled.json"), "not json").unwrap();
+
+        let examination = Examination::new(&dir, &Workflow::default(), None);
+        assert_eq!(examination.findings().len(), 3);
+        assert_eq!(examination.repair().unwrap(), 2);
+
+        assert!(Examination::new(&dir, &Workflow::default(), None)
+            .findings()
+            .is_empty());
+        assert!(!dir.join("canceled.json").exists());
+        assert!(!LocalPool::staged_path(&dir.join("planned.json")).exists());
+
+        let data =
+            LocalPool::deserialize(fs::read_to_string(dir.join("planned.json")).unwrap()).unwrap();
+        let mut priorities = data
+            .items
+            .iter()
+            .map(|item| (item.summary.as_str(), item.priority.value()))
+            .collect::<Vec<_>>();
+        priorities.sort();
+        assert_eq!(priorities, [("A", 0), ("B", -3)]);
+
+        let quarantine = fs::read_to_string(dir.join(QUARANTINE)).unwrap();
+        let quarantine = serde_json::from_str::<Quarantine>(&quarantine).unwrap();
+        assert_eq!(quarantine.entries.len(), 2);
+        assert_eq!(
+            quarantine.entries[0].entry,
+            Value::String("not json".to_owned())
+        );
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
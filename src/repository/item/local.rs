@@ -1,39 +1,58 @@
-use std::collections::{HashMap, HashSet};
-use std::fs::OpenOptions;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::{self, OpenOptions};
 use std::hash::{Hash, Hasher};
-use std::io::{BufReader, BufWriter, Error as IoError, Read, Write};
-use std::path::PathBuf;
+use std::io::{BufReader, BufWriter, Error as IoError, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use chrono::NaiveDateTime;
-use serde::{Deserialize, Serialize};
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize, Serializer};
 use serde_json::Error as SerdeError;
 use snafu::prelude::*;
 
 use crate::domain::entity::{Item, Priority, TagSet};
+use crate::repository::cipher::{self, Cipher, CipherError};
 use crate::repository::item::memory::MemoryPool;
+use crate::repository::item::migrate::{self, MigrateError};
 
 use super::{
     AddError, AddTagError, GetError, Pool, RemoveError, RemoveTagError, SelectError,
     SetPriorityError,
 };
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
-struct RawItem {
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct RawItem {
     pub summary: String,
     pub content: String,
     pub deadline: NaiveDateTime,
+    #[serde(serialize_with = "serialize_tags")]
     pub tags: TagSet,
     pub priority: Priority,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed: Option<NaiveDateTime>,
+    /// In seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<i64>,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
-struct Data {
-    items: HashSet<RawItem>,
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+pub(super) struct Data {
+    pub items: HashSet<RawItem>,
+}
+
+/// Items are written in a stable order, so that the file diffs and merges well when the
+/// storage is kept under version control.
+#[derive(Serialize)]
+struct SortedData<'a> {
+    version: u64,
+    items: Vec<&'a RawItem>,
 }
 
 pub struct LocalPool {
     pool: MemoryPool,
     path: PathBuf,
+    /// Encrypts the file when written, if set.
+    cipher: Option<Arc<Cipher>>,
 }
 
 #[derive(Debug, Snafu)]
@@ -45,6 +64,14 @@ pub enum InitError {
     Open { source: IoError },
     #[snafu(display("Failed to read items: {source}"))]
     Read { source: IoError },
+    #[snafu(display("{source}"))]
+    Migrate { source: MigrateError },
+    #[snafu(display("Storage is encrypted, a passphrase is needed to open it"))]
+    Locked,
+    #[snafu(display("Failed to decrypt storage: {source}"))]
+    Decrypt { source: CipherError },
+    #[snafu(display("Several items share ID {id}, run `todo doctor --fix` to settle them"))]
+    Collision { id: u64 },
 }
 
 #[derive(Debug, Snafu)]
@@ -56,6 +83,20 @@ pub enum SyncError {
     Open { source: IoError },
     #[snafu(display("Failed to write items: {source}"))]
     Write { source: IoError },
+    #[snafu(display("Failed to encrypt items: {source}"))]
+    Encrypt { source: CipherError },
+    #[snafu(display("Failed to replace storage: {source}"))]
+    Replace { source: IoError },
+}
+
+fn serialize_tags<S: Serializer>(tags: &TagSet, serializer: S) -> Result<S::Ok, S::Error> {
+    tags.iter().collect::<BTreeSet<_>>().serialize(serializer)
+}
+
+impl RawItem {
+    pub fn id(&self) -> u64 {
+        Item::from(self.clone()).id()
+    }
 }
 
 impl Hash for RawItem {
@@ -68,13 +109,17 @@ impl Hash for RawItem {
 
 impl From<RawItem> for Item {
     fn from(value: RawItem) -> Self {
-        Item::new(
+        let mut item = Item::new(
             value.summary.as_str(),
             value.content.as_str(),
             value.deadline,
             value.tags,
             value.priority,
-        )
+        );
+
+        item.set_closed(value.closed);
+        item.set_estimate(value.estimate.map(Duration::seconds));
+        item
     }
 }
 
@@ -86,6 +131,8 @@ impl From<Item> for RawItem {
             deadline: *value.deadline(),
             tags: value.tags().clone(),
             priority: value.priority().clone(),
+            closed: value.closed().copied(),
+            estimate: value.estimate().map(|estimate| estimate.num_seconds()),
         }
     }
 }
@@ -112,20 +159,55 @@ impl From<HashMap<u64, Item>> for Data {
 
 impl LocalPool {
     pub fn open(path: PathBuf) -> Result<Self, InitError> {
-        let json = Self::read_file(path.clone())?;
+        Self::with_cipher(path, None)
+    }
+
+    /// Opens a pool whose file is decrypted with `cipher` when encrypted, and always
+    /// written encrypted. Without a cipher, an encrypted file can't be opened.
+    pub fn with_cipher(path: PathBuf, cipher: Option<Arc<Cipher>>) -> Result<Self, InitError> {
+        let json = Self::read_file(path.clone(), cipher.as_deref())?;
         let data = Self::deserialize(json)?;
 
+        // Items sharing an ID would be merged into one, so the file is left alone instead.
+        let mut seen = HashSet::new();
+        if let Some(id) = data
+            .items
+            .iter()
+            .map(RawItem::id)
+            .find(|&id| !seen.insert(id))
+        {
+            return Err(InitError::Collision { id });
+        }
+
         Ok(Self {
             pool: MemoryPool::from(HashMap::from(data)),
             path,
+            cipher,
         })
     }
 
-    fn read_file(path: PathBuf) -> Result<String, InitError> {
+    /// Reads the format version of the file at `path` and checks that it can be
+    /// migrated to the current one, without opening the pool.
+    pub fn version(path: PathBuf, cipher: Option<&Cipher>) -> Result<u64, InitError> {
+        let json = Self::read_file(path, cipher)?;
+        if json.is_empty() {
+            return Ok(migrate::VERSION);
+        }
+
+        let value =
+            serde_json::from_str(&json).map_err(|err| InitError::Invalid { source: err })?;
+        let version = migrate::version(&value).map_err(|err| InitError::Migrate { source: err })?;
+
+        Self::deserialize(json)?;
+        Ok(version)
+    }
+
+    pub(super) fn read_file(path: PathBuf, cipher: Option<&Cipher>) -> Result<String, InitError> {
         let file = match OpenOptions::new()
             .read(true)
             .write(true)
             .create(true)
+            .truncate(false)
             .open(path)
         {
             Ok(file) => file,
@@ -133,15 +215,30 @@ impl LocalPool {
         };
 
         let mut reader = BufReader::new(file);
-        let mut json = String::new();
+        let mut bytes = Vec::new();
 
         reader
-            .read_to_string(&mut json)
-            .map(|_| json)
-            .map_err(|err| InitError::Read { source: err })
+            .read_to_end(&mut bytes)
+            .map_err(|err| InitError::Read { source: err })?;
+
+        Self::decode(bytes, cipher)
     }
 
-    fn sync_file(path: PathBuf, json: String) -> Result<(), SyncError> {
+    /// Turns the contents of a pool file into JSON, decrypting them if needed.
+    pub(super) fn decode(mut bytes: Vec<u8>, cipher: Option<&Cipher>) -> Result<String, InitError> {
+        if cipher::is_encrypted(&bytes) {
+            let cipher = cipher.ok_or(InitError::Locked)?;
+            bytes = cipher
+                .decrypt(&bytes)
+                .map_err(|err| InitError::Decrypt { source: err })?;
+        }
+
+        String::from_utf8(bytes).map_err(|err| InitError::Read {
+            source: IoError::new(ErrorKind::InvalidData, err),
+        })
+    }
+
+    pub(super) fn sync_file(path: PathBuf, bytes: &[u8]) -> Result<(), SyncError> {
         let file = match OpenOptions::new()
             .write(true)
             .truncate(true)
@@ -152,38 +249,90 @@ impl LocalPool {
             Err(err) => return Err(SyncError::Open { source: err }),
         };
 
+        // Errors of the last buffered chunk only show up when flushing, and the file
+        // must be on disk before it's renamed over the pool.
         let mut writer = BufWriter::new(file);
         writer
-            .write_all(json.as_bytes())
+            .write_all(bytes)
+            .and_then(|()| writer.flush())
+            .and_then(|()| writer.get_ref().sync_all())
             .map_err(|err| SyncError::Write { source: err })
     }
 
+    /// Writes through a staged file, so that the file is never left half written.
     pub fn sync(&self) -> Result<(), SyncError> {
+        self.stage()?;
+        self.commit()
+    }
+
+    fn stage(&self) -> Result<(), SyncError> {
         let data: Data = self.pool.clone_inner().into();
         let json = Self::serialize(data)?;
-        Self::sync_file(self.path.clone(), json)
+
+        match &self.cipher {
+            Some(cipher) => {
+                let bytes = cipher
+                    .encrypt(json.as_bytes())
+                    .map_err(|err| SyncError::Encrypt { source: err })?;
+                Self::sync_file(self.staged(), &bytes)
+            }
+            None => Self::sync_file(self.staged(), json.as_bytes()),
+        }
     }
 
-    fn deserialize(json: String) -> Result<Data, InitError> {
-        if !json.is_empty() {
-            serde_json::from_str::<Data>(json.as_str())
-                .map_err(|err| InitError::Invalid { source: err })
-        } else {
-            Ok(Data {
+    fn commit(&self) -> Result<(), SyncError> {
+        fs::rename(self.staged(), &self.path).map_err(|err| SyncError::Replace { source: err })
+    }
+
+    /// The file written by [`LocalPool::stage`], next to the pool's own.
+    fn staged(&self) -> PathBuf {
+        Self::staged_path(&self.path)
+    }
+
+    /// Where the next version of the file at `path` is written before it replaces it.
+    pub(super) fn staged_path(path: &Path) -> PathBuf {
+        let mut path = path.to_owned().into_os_string();
+        path.push(".tmp");
+        path.into()
+    }
+
+    /// Files written by older versions are migrated in memory, and written back in the
+    /// current format on the next sync.
+    pub(super) fn deserialize(json: String) -> Result<Data, InitError> {
+        if json.is_empty() {
+            return Ok(Data {
                 items: HashSet::new(),
-            })
+            });
         }
+
+        let value = serde_json::from_str(json.as_str())
+            .map_err(|err| InitError::Invalid { source: err })?;
+        let value = migrate::migrate(value).map_err(|err| InitError::Migrate { source: err })?;
+
+        serde_json::from_value::<Data>(value).map_err(|err| InitError::Invalid { source: err })
     }
 
-    fn serialize(data: Data) -> Result<String, SyncError> {
-        serde_json::to_string(&data).map_err(|err| SyncError::Dump { source: err })
+    pub(super) fn serialize(data: Data) -> Result<String, SyncError> {
+        let mut items = data.items.iter().collect::<Vec<_>>();
+        items.sort_by(|a, b| {
+            (a.deadline, &a.summary, &a.content).cmp(&(b.deadline, &b.summary, &b.content))
+        });
+
+        let data = SortedData {
+            version: migrate::VERSION,
+            items,
+        };
+
+        serde_json::to_string_pretty(&data)
+            .map(|json| json + "\n")
+            .map_err(|err| SyncError::Dump { source: err })
     }
 }
 
 impl Drop for LocalPool {
     fn drop(&mut self) {
         if let Err(err) = self.sync() {
-            panic!("{err}");
+            eprintln!("Error: failed to save {}: {err}", self.path.display());
         }
     }
 }
@@ -225,10 +374,35 @@ impl Pool for LocalPool {
     fn clear(&mut self) {
         self.pool.clear();
     }
+
+    fn sync(&self) -> Result<(), SyncError> {
+        LocalPool::sync(self)
+    }
+
+    fn stage(&self) -> Result<(), SyncError> {
+        LocalPool::stage(self)
+    }
+
+    fn commit(&self) -> Result<(), SyncError> {
+        LocalPool::commit(self)
+    }
+
+    fn discard(&self) {
+        let _ = fs::remove_file(self.staged());
+    }
+
+    fn reload(&mut self) -> Result<(), InitError> {
+        let json = Self::read_file(self.path.clone(), self.cipher.as_deref())?;
+        let data = Self::deserialize(json)?;
+        self.pool = MemoryPool::from(HashMap::from(data));
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::Timelike;
+
     use super::*;
 
     #[test]
@@ -268,6 +442,8 @@ mod tests {
                     deadline: get_deadline(),
                     tags: TagSet::new(),
                     priority: 1.try_into().unwrap(),
+                    closed: None,
+                    estimate: None,
                 },
                 RawItem {
                     summary: "2".to_owned(),
@@ -275,6 +451,8 @@ mod tests {
                     deadline: get_deadline(),
                     tags: TagSet::new(),
                     priority: 2.try_into().unwrap(),
+                    closed: None,
+                    estimate: None,
                 },
                 RawItem {
                     summary: "3".to_owned(),
@@ -282,6 +460,8 @@ mod tests {
                     deadline: get_deadline(),
                     tags: TagSet::new(),
                     priority: 3.try_into().unwrap(),
+                    closed: None,
+                    estimate: None,
                 },
             ]
             .into_iter()
@@ -324,6 +504,86 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn it_should_serialize_items_and_tags_in_a_stable_order() {
+        let item = |summary: &str, hour: u32, tags: &[&str]| RawItem {
+            summary: summary.to_owned(),
+            content: String::new(),
+            deadline: get_deadline().with_hour(hour).unwrap(),
+            tags: tags.iter().map(|&tag| tag.to_owned()).collect(),
+            priority: Priority::default(),
+            closed: None,
+            estimate: None,
+        };
+
+        let items = [
+            item("b", 12, &[]),
+            item("a", 12, &["z", "m", "a"]),
+            item("c", 8, &[]),
+        ]
+        .into_iter()
+        .collect();
+
+        let json = LocalPool::serialize(Data { items }).unwrap();
+        let summaries = json
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("\"summary\": "))
+            .collect::<Vec<_>>();
+
+        assert_eq!(summaries, ["\"c\",", "\"a\",", "\"b\","]);
+        assert!(json.contains("\"a\",\n        \"m\",\n        \"z\""));
+        assert!(json.starts_with(&format!("{{\n  \"version\": {},", migrate::VERSION)));
+        assert!(json.ends_with("}\n"));
+    }
+
+    #[test]
+    fn it_should_only_open_an_encrypted_file_with_a_cipher() {
+        let path = std::env::temp_dir().join(format!("todo-encrypted-{}.json", std::process::id()));
+        let cipher = || {
+            let params = argon2::Params::new(64, 1, 1, None).unwrap();
+            Some(Arc::new(Cipher::with_params("secret", params)))
+        };
+
+        let mut pool = LocalPool::with_cipher(path.clone(), cipher()).unwrap();
+        let _ = pool.add(Item::new_test());
+        drop(pool);
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(cipher::is_encrypted(&bytes));
+        assert!(matches!(
+            LocalPool::open(path.clone()),
+            Err(InitError::Locked)
+        ));
+
+        let pool = LocalPool::with_cipher(path.clone(), cipher()).unwrap();
+        assert!(pool.get(Item::new_test().id()).is_ok());
+
+        // The pool writes its file back when dropped.
+        drop(pool);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn it_should_refuse_to_open_a_file_whose_items_share_an_id() {
+        let path = std::env::temp_dir().join(format!("todo-collision-{}.json", std::process::id()));
+        let json = serde_json::json!({
+            "version": migrate::VERSION,
+            "items": [
+                { "summary": "a", "content": "", "deadline": "2023-06-17T23:20:00", "tags": [], "priority": 0 },
+                { "summary": "a", "content": "", "deadline": "2023-06-17T23:20:00", "tags": ["x"], "priority": 0 },
+            ]
+        });
+        std::fs::write(&path, json.to_string()).unwrap();
+
+        assert!(matches!(
+            LocalPool::open(path.clone()),
+            Err(InitError::Collision { .. })
+        ));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), json.to_string());
+
+        let _ = std::fs::remove_file(path);
+    }
+
     #[inline]
     fn get_deadline() -> NaiveDateTime {
         NaiveDateTime::parse_from_str("2023-06-17 23:20:00", "%Y-%m-%d %H:%M:%S").unwrap()
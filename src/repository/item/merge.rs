@@ -0,0 +1,211 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use snafu::prelude::*;
+
+use crate::repository::cipher::{self, Cipher};
+
+use super::local::{Data, LocalPool, RawItem};
+use super::{InitError, SyncError};
+
+#[derive(Debug, Snafu)]
+pub enum MergeError {
+    #[snafu(display("Failed to read the {side} version: {source}"))]
+    Read {
+        side: &'static str,
+        source: InitError,
+    },
+    #[snafu(display("Failed to write the merged version: {source}"))]
+    Write { source: SyncError },
+}
+
+/// Merges two versions of a pool file changed from a common `base`, item by item keyed
+/// by ID. Items added on either side are kept, items deleted on one side are dropped
+/// unless the other side changed them, and items changed on both sides are merged field
+/// by field: tags added or removed on either side are applied, and for any other field a
+/// change on our side wins over one on theirs. An empty `base` stands for a file added
+/// on both sides.
+pub fn merge(base: &str, ours: &str, theirs: &str) -> Result<String, MergeError> {
+    let read = |json: &str, side| {
+        LocalPool::deserialize(json.to_owned())
+            .map(|data| {
+                data.items
+                    .into_iter()
+                    .map(|item| (item.id(), item))
+                    .collect::<HashMap<_, _>>()
+            })
+            .context(ReadSnafu { side })
+    };
+
+    let base = read(base, "base")?;
+    let ours = read(ours, "our")?;
+    let theirs = read(theirs, "their")?;
+
+    let ids = base
+        .keys()
+        .chain(ours.keys())
+        .chain(theirs.keys())
+        .collect::<BTreeSet<_>>();
+
+    let items = ids
+        .into_iter()
+        .filter_map(|id| match (base.get(id), ours.get(id), theirs.get(id)) {
+            (base, Some(ours), Some(theirs)) => Some(merge_item(base, ours, theirs)),
+            (Some(base), Some(item), None) | (Some(base), None, Some(item)) => {
+                (item != base).then(|| item.clone())
+            }
+            (None, Some(item), None) | (None, None, Some(item)) => Some(item.clone()),
+            (_, None, None) => None,
+        })
+        .collect::<HashSet<_>>();
+
+    LocalPool::serialize(Data { items }).context(WriteSnafu)
+}
+
+/// Like [`merge`], for versions of a pool file as they're stored. Encrypted versions
+/// are decrypted with `cipher`, and the merged version is encrypted again if ours was.
+pub fn merge_files(
+    base: &[u8],
+    ours: &[u8],
+    theirs: &[u8],
+    cipher: Option<&Cipher>,
+) -> Result<Vec<u8>, MergeError> {
+    let read =
+        |bytes: &[u8], side| LocalPool::decode(bytes.to_vec(), cipher).context(ReadSnafu { side });
+    let merged = merge(
+        &read(base, "base")?,
+        &read(ours, "our")?,
+        &read(theirs, "their")?,
+    )?;
+
+    match cipher.filter(|_| cipher::is_encrypted(ours)) {
+        Some(cipher) => cipher
+            .encrypt(merged.as_bytes())
+            .map_err(|err| SyncError::Encrypt { source: err })
+            .context(WriteSnafu),
+        None => Ok(merged.into_bytes()),
+    }
+}
+
+fn merge_item(base: Option<&RawItem>, ours: &RawItem, theirs: &RawItem) -> RawItem {
+    let Some(base) = base else {
+        let mut item = ours.clone();
+        item.tags.extend(theirs.tags.iter().cloned());
+        return item;
+    };
+
+    let mut tags = base.tags.clone();
+    for side in [ours, theirs] {
+        tags.retain(|tag| side.tags.contains(tag));
+    }
+    for side in [ours, theirs] {
+        tags.extend(side.tags.difference(&base.tags).cloned());
+    }
+
+    RawItem {
+        summary: ours.summary.clone(),
+        content: ours.content.clone(),
+        deadline: ours.deadline,
+        tags,
+        priority: pick(&base.priority, &ours.priority, &theirs.priority),
+        closed: pick(&base.closed, &ours.closed, &theirs.closed),
+        estimate: pick(&base.estimate, &ours.estimate, &theirs.estimate),
+    }
+}
+
+fn pick<T: PartialEq + Clone>(base: &T, ours: &T, theirs: &T) -> T {
+    if ours == base {
+        theirs.clone()
+    } else {
+        ours.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    #[test]
+    fn it_should_merge_items_by_id() {
+        let item = |summary: &str, tags: &[&str], priority: i32| {
+            json!({
+                "summary": summary,
+                "content": "",
+                "deadline": "2023-06-17T23:20:00",
+                "tags": tags,
+                "priority": priority
+            })
+        };
+        let pool = |items: Vec<Value>| json!({ "items": items }).to_string();
+
+        let base = pool(vec![
+            item("kept", &["a", "b"], 0),
+            item("deleted", &[], 0),
+            item("edited", &[], 0),
+        ]);
+        let ours = pool(vec![
+            item("kept", &["a", "c"], 1),
+            item("edited", &[], 2),
+            item("ours", &[], 0),
+        ]);
+        let theirs = pool(vec![
+            item("kept", &["b", "d"], 0),
+            item("deleted", &[], 0),
+            item("theirs", &[], 0),
+        ]);
+
+        let merged = merge(&base, &ours, &theirs).unwrap();
+        let merged = serde_json::from_str::<Value>(&merged).unwrap();
+        let items = merged["items"].as_array().unwrap();
+
+        let find = |summary: &str| items.iter().find(|item| item["summary"] == summary);
+
+        assert_eq!(items.len(), 4);
+        assert_eq!(find("kept").unwrap()["tags"], json!(["c", "d"]));
+        assert_eq!(find("kept").unwrap()["priority"], 1);
+        assert_eq!(find("edited").unwrap()["priority"], 2);
+        assert!(find("deleted").is_none());
+        assert!(find("ours").is_some());
+        assert!(find("theirs").is_some());
+    }
+
+    #[test]
+    fn it_should_merge_encrypted_versions_with_the_cipher() {
+        let params = argon2::Params::new(64, 1, 1, None).unwrap();
+        let cipher = Cipher::with_params("secret", params);
+        let pool = |summaries: &[&str]| {
+            let items = summaries
+                .iter()
+                .map(|summary| {
+                    json!({
+                        "summary": summary,
+                        "content": "",
+                        "deadline": "2023-06-17T23:20:00",
+                        "tags": [],
+                        "priority": 0
+                    })
+                })
+                .collect::<Vec<_>>();
+            let json = json!({ "items": items }).to_string();
+            cipher.encrypt(json.as_bytes()).unwrap()
+        };
+
+        let (base, ours, theirs) = (pool(&["a"]), pool(&["a", "b"]), pool(&["a", "c"]));
+
+        assert!(matches!(
+            merge_files(&base, &ours, &theirs, None),
+            Err(MergeError::Read {
+                source: InitError::Locked,
+                ..
+            })
+        ));
+
+        let merged = merge_files(&base, &ours, &theirs, Some(&cipher)).unwrap();
+        assert!(cipher::is_encrypted(&merged));
+
+        let merged = cipher.decrypt(&merged).unwrap();
+        let merged = serde_json::from_slice::<Value>(&merged).unwrap();
+        assert_eq!(merged["items"].as_array().unwrap().len(), 3);
+    }
+}
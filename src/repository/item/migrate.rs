@@ -0,0 +1,89 @@
+use serde_json::{Map, Value};
+use snafu::prelude::*;
+
+/// The version of the pool file format written by this build. Files without a
+/// `version` field predate versioning and count as version 0.
+pub const VERSION: u64 = 1;
+
+/// Takes a pool file from one version to the next.
+type Migration = fn(Map<String, Value>) -> Map<String, Value>;
+
+/// Migrations by the version they start from.
+const MIGRATIONS: [Migration; VERSION as usize] = [v0_to_v1];
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub enum MigrateError {
+    #[snafu(display(
+        "Pool file has version {version}, while at most {VERSION} is supported, please upgrade"
+    ))]
+    Newer { version: u64 },
+    #[snafu(display("Pool file isn't a JSON object with a numeric version"))]
+    Malformed,
+}
+
+pub fn version(value: &Value) -> Result<u64, MigrateError> {
+    let object = value.as_object().context(MalformedSnafu)?;
+
+    match object.get("version") {
+        None => Ok(0),
+        Some(version) => version.as_u64().context(MalformedSnafu),
+    }
+}
+
+/// Brings a pool file up to [`VERSION`] by running every migration from its version on.
+pub fn migrate(value: Value) -> Result<Value, MigrateError> {
+    let version = version(&value)?;
+    ensure!(version <= VERSION, NewerSnafu { version });
+
+    let Value::Object(mut object) = value else {
+        return Err(MigrateError::Malformed);
+    };
+
+    for migration in &MIGRATIONS[version as usize..] {
+        object = migration(object);
+    }
+
+    object.insert("version".to_owned(), VERSION.into());
+    Ok(Value::Object(object))
+}
+
+/// Introduces the version field, with items as they were.
+fn v0_to_v1(mut object: Map<String, Value>) -> Map<String, Value> {
+    object.insert("version".to_owned(), 1.into());
+    object
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn it_should_migrate_unversioned_files() {
+        let value = json!({ "items": [] });
+
+        assert_eq!(version(&value), Ok(0));
+        assert_eq!(
+            migrate(value),
+            Ok(json!({ "version": VERSION, "items": [] }))
+        );
+    }
+
+    #[test]
+    fn it_should_refuse_newer_or_malformed_files() {
+        let newer = json!({ "version": VERSION + 1, "items": [] });
+
+        assert_eq!(
+            migrate(newer),
+            Err(MigrateError::Newer {
+                version: VERSION + 1
+            })
+        );
+        assert_eq!(migrate(json!([])), Err(MigrateError::Malformed));
+        assert_eq!(
+            migrate(json!({ "version": "1" })),
+            Err(MigrateError::Malformed)
+        );
+    }
+}
@@ -1,14 +1,19 @@
+pub mod doctor;
 pub mod local;
 pub mod memory;
+pub mod merge;
+pub mod migrate;
+#[cfg(feature = "async")]
+pub mod nonblocking;
 
 use crate::domain::entity::{Item, Priority, TagSet};
 
 use chrono::NaiveDateTime;
 
-pub use local::LocalPool;
+pub use local::{InitError, LocalPool, SyncError};
 pub use memory::MemoryPool;
 
-pub trait Pool {
+pub trait Pool: Send + Sync {
     fn add(&mut self, item: Item) -> Result<u64, AddError>;
 
     fn remove(&mut self, id: u64) -> Result<Item, RemoveError>;
@@ -29,6 +34,32 @@ pub trait Pool {
     fn set_priority(&mut self, id: u64, priority: Priority) -> Result<(), SetPriorityError>;
 
     fn clear(&mut self);
+
+    /// Writes the items to the underlying storage, if there is one.
+    fn sync(&self) -> Result<(), SyncError> {
+        Ok(())
+    }
+
+    /// Writes the items next to the underlying storage, if there is one, to be put in its
+    /// place by [`Pool::commit`]. Several pools are staged before any is committed, so
+    /// that a failed write leaves all of them as they were.
+    fn stage(&self) -> Result<(), SyncError> {
+        Ok(())
+    }
+
+    /// Puts the items written by [`Pool::stage`] in place of the underlying storage.
+    fn commit(&self) -> Result<(), SyncError> {
+        Ok(())
+    }
+
+    /// Drops the items written by [`Pool::stage`].
+    fn discard(&self) {}
+
+    /// Reads the items again from the underlying storage, if there is one, dropping any
+    /// change which hasn't been written.
+    fn reload(&mut self) -> Result<(), InitError> {
+        Ok(())
+    }
 }
 
 pub enum AddError {
@@ -0,0 +1,133 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+use crate::domain::entity::{Item, Priority, TagSet};
+
+use super::{
+    AddError, AddTagError, GetError, InitError, Pool as SyncPool, RemoveError, RemoveTagError,
+    SelectError, SetPriorityError, SyncError,
+};
+
+/// An item pool for async code, such as one kept by a remote service. It mirrors
+/// [`super::Pool`], whose pools can be used through an [`Adapter`].
+#[async_trait]
+pub trait Pool: Send + Sync {
+    async fn add(&mut self, item: Item) -> Result<u64, AddError>;
+
+    async fn remove(&mut self, id: u64) -> Result<Item, RemoveError>;
+
+    async fn get(&self, id: u64) -> Result<Item, GetError>;
+
+    async fn select(
+        &self,
+        tags: TagSet,
+        before: Option<NaiveDateTime>,
+        after: Option<NaiveDateTime>,
+    ) -> Result<Vec<Item>, SelectError>;
+
+    async fn add_tag(&mut self, id: u64, tags: TagSet) -> Result<(), AddTagError>;
+
+    async fn remove_tag(&mut self, id: u64, tags: TagSet) -> Result<(), RemoveTagError>;
+
+    async fn set_priority(&mut self, id: u64, priority: Priority) -> Result<(), SetPriorityError>;
+
+    async fn clear(&mut self);
+
+    /// Writes the items to the underlying storage, if there is one.
+    async fn sync(&self) -> Result<(), SyncError> {
+        Ok(())
+    }
+
+    /// Reads the items again from the underlying storage, if there is one, dropping any
+    /// change which hasn't been written.
+    async fn reload(&mut self) -> Result<(), InitError> {
+        Ok(())
+    }
+}
+
+/// Lets a sync pool be used as an async one. Its methods run in place, so pools which
+/// do slow I/O outside of [`SyncPool::sync`] and [`SyncPool::reload`] still block.
+pub struct Adapter<P>(pub P);
+
+impl<P> Adapter<P> {
+    pub fn into_inner(self) -> P {
+        self.0
+    }
+}
+
+#[async_trait]
+impl<P: SyncPool> Pool for Adapter<P> {
+    async fn add(&mut self, item: Item) -> Result<u64, AddError> {
+        self.0.add(item)
+    }
+
+    async fn remove(&mut self, id: u64) -> Result<Item, RemoveError> {
+        self.0.remove(id)
+    }
+
+    async fn get(&self, id: u64) -> Result<Item, GetError> {
+        self.0.get(id)
+    }
+
+    async fn select(
+        &self,
+        tags: TagSet,
+        before: Option<NaiveDateTime>,
+        after: Option<NaiveDateTime>,
+    ) -> Result<Vec<Item>, SelectError> {
+        self.0.select(tags, before, after)
+    }
+
+    async fn add_tag(&mut self, id: u64, tags: TagSet) -> Result<(), AddTagError> {
+        self.0.add_tag(id, tags)
+    }
+
+    async fn remove_tag(&mut self, id: u64, tags: TagSet) -> Result<(), RemoveTagError> {
+        self.0.remove_tag(id, tags)
+    }
+
+    async fn set_priority(&mut self, id: u64, priority: Priority) -> Result<(), SetPriorityError> {
+        self.0.set_priority(id, priority)
+    }
+
+    async fn clear(&mut self) {
+        self.0.clear()
+    }
+
+    async fn sync(&self) -> Result<(), SyncError> {
+        self.0.sync()
+    }
+
+    async fn reload(&mut self) -> Result<(), InitError> {
+        self.0.reload()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pollster::block_on;
+
+    use crate::repository::item::MemoryPool;
+
+    use super::*;
+
+    #[test]
+    fn it_should_use_a_sync_pool_through_the_adapter() {
+        let mut pool = Adapter(MemoryPool::new());
+        let item = Item::new_test();
+        let id = item.id();
+
+        let tags = TagSet::from(["a".to_owned()]);
+
+        block_on(async {
+            assert!(pool.add(item).await.is_ok());
+            assert!(pool.add_tag(id, tags.clone()).await.is_ok());
+
+            let selected = pool.select(tags, None, None).await;
+            assert!(matches!(selected, Ok(items) if items.len() == 1));
+
+            assert!(pool.remove(id).await.is_ok());
+            assert!(pool.get(id).await.is_err());
+        });
+    }
+}
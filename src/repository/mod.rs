@@ -1,105 +1,547 @@
+pub mod cipher;
 pub mod id;
 pub mod item;
+pub mod reminder;
+pub mod timer;
+mod transaction;
+pub mod workflow;
+pub mod workspace;
 
-use std::sync::Mutex;
+use std::collections::HashMap;
+
+use async_lock::RwLock;
+#[cfg(feature = "async")]
+use async_lock::{RwLockReadGuard, RwLockWriteGuard};
+use snafu::prelude::*;
+
+use crate::domain::entity::{Group, TagSet, Workflow};
 
 use id::Pool as IdPool;
-use item::Pool as ItemPool;
+use item::{InitError, Pool as ItemPool, SyncError};
+use transaction::{Staged, StagedIds};
 
+/// The pools of items, one for each state of the workflow, and the IDs of open items.
 pub struct Data {
-    pub planned: Box<dyn ItemPool>,
-    pub finished: Box<dyn ItemPool>,
-    pub canceled: Box<dyn ItemPool>,
-    pub ids: Box<dyn IdPool>,
+    workflow: Workflow,
+    pools: HashMap<String, Box<dyn ItemPool>>,
+    ids: Box<dyn IdPool>,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("Unknown state `{name}`"))]
+pub struct StateError {
+    name: String,
 }
 
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum TransactionError {
+    #[snafu(display("{source}"))]
+    State { source: StateError },
+    #[snafu(display("Failed to save changes, so they are taken back: {source}"))]
+    Commit { source: SyncError },
+}
+
+/// Readers share the lock, so listing and searching don't wait for each other, while
+/// changes take it alone. Methods wait for it by blocking the thread, async code with
+/// the `async` feature should use `Repository::read_async` and `write_async` instead.
 pub struct Repository {
-    inner: Mutex<Data>,
+    inner: RwLock<Data>,
+}
+
+impl Data {
+    /// Opens a pool for every state of `workflow` with `open`, which is given the state
+    /// name.
+    pub fn new<F, E>(workflow: Workflow, ids: Box<dyn IdPool>, mut open: F) -> Result<Self, E>
+    where
+        F: FnMut(&str) -> Result<Box<dyn ItemPool>, E>,
+    {
+        let pools = workflow
+            .names()
+            .map(|name| open(name).map(|pool| (name.to_owned(), pool)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            workflow,
+            pools,
+            ids,
+        })
+    }
+
+    pub fn workflow(&self) -> &Workflow {
+        &self.workflow
+    }
+
+    /// The state of the item with `id`, if any.
+    pub fn locate(&self, id: u64) -> Option<&str> {
+        self.workflow
+            .names()
+            .find(|&name| self.pools[name].get(id).is_ok())
+    }
+
+    fn check(&self, names: &[&str]) -> Result<(), StateError> {
+        match names.iter().find(|&&name| !self.pools.contains_key(name)) {
+            Some(&name) => StateSnafu { name }.fail(),
+            None => Ok(()),
+        }
+    }
+
+    /// The pools of the states called `names`, which must differ, along with the ID pool.
+    fn pools<const N: usize>(
+        &mut self,
+        names: [&str; N],
+    ) -> Result<([&mut dyn ItemPool; N], &mut dyn IdPool), StateError> {
+        self.check(&names)?;
+
+        let pools = self
+            .pools
+            .get_disjoint_mut(names)
+            .map(|pool| pool.unwrap().as_mut() as &mut dyn ItemPool);
+        Ok((pools, self.ids.as_mut()))
+    }
+
+    /// Applies `f` to the pools of the states called `names` along with the ID pool,
+    /// without changing any of them.
+    pub fn read<const N: usize, F, T>(&self, names: [&str; N], f: F) -> Result<T, StateError>
+    where
+        F: FnOnce([&dyn ItemPool; N], &dyn IdPool) -> T,
+    {
+        self.check(&names)?;
+
+        let pools = names.map(|name| self.pools[name].as_ref() as &dyn ItemPool);
+        Ok(f(pools, self.ids.as_ref()))
+    }
+
+    /// Applies `f` to the pools of all states by name, in the order of the workflow,
+    /// along with the ID pool, without changing any of them.
+    pub fn read_all<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce(Vec<(&str, &dyn ItemPool)>, &dyn IdPool) -> T,
+    {
+        let pools = self
+            .workflow
+            .names()
+            .map(|name| (name, self.pools[name].as_ref() as &dyn ItemPool))
+            .collect();
+        f(pools, self.ids.as_ref())
+    }
+
+    /// Applies `f` to the pools of the states called `names`, which must differ, along
+    /// with the ID pool. Changes are only kept in memory, see [`Data::transaction`] for
+    /// ones that are written at once.
+    pub fn apply<const N: usize, F, T>(&mut self, names: [&str; N], f: F) -> Result<T, StateError>
+    where
+        F: FnOnce([&mut dyn ItemPool; N], &mut dyn IdPool) -> T,
+    {
+        let (pools, ids) = self.pools(names)?;
+        Ok(f(pools, ids))
+    }
+
+    /// Applies `f` to the pools of all states by name, in the order of the workflow,
+    /// along with the ID pool.
+    pub fn apply_all<F, T>(&mut self, f: F) -> T
+    where
+        F: FnOnce(Vec<(&str, &mut dyn ItemPool)>, &mut dyn IdPool) -> T,
+    {
+        let mut pools = self
+            .pools
+            .iter_mut()
+            .map(|(name, pool)| (name.as_str(), pool.as_mut() as &mut dyn ItemPool))
+            .collect::<Vec<_>>();
+
+        let order = self.workflow.names().collect::<Vec<_>>();
+        pools.sort_by_key(|(name, _)| order.iter().position(|other| other == name));
+        f(pools, self.ids.as_mut())
+    }
+
+    /// Applies `f` to the pools of the states called `names`, which must differ, along
+    /// with the ID pool. Changes are kept only if `f` succeeds and the changed pools are
+    /// written to storage, otherwise every one of them is taken back.
+    pub fn transaction<const N: usize, F, T, E>(
+        &mut self,
+        names: [&str; N],
+        f: F,
+    ) -> Result<Result<T, E>, TransactionError>
+    where
+        F: FnOnce([&mut dyn ItemPool; N], &mut dyn IdPool) -> Result<T, E>,
+    {
+        let (pools, ids) = self
+            .pools(names)
+            .map_err(|source| TransactionError::State { source })?;
+
+        stage(Vec::from(pools), ids, |pools, ids| {
+            let Ok(pools) = pools.try_into() else {
+                unreachable!("as many pools are staged as given");
+            };
+            f(pools, ids)
+        })
+    }
+
+    /// Like [`Data::transaction`], for the pools of all states by name, in the order of
+    /// the workflow.
+    pub fn transaction_all<F, T, E>(&mut self, f: F) -> Result<Result<T, E>, TransactionError>
+    where
+        F: FnOnce(Vec<(&str, &mut dyn ItemPool)>, &mut dyn IdPool) -> Result<T, E>,
+    {
+        self.apply_all(|pools, ids| {
+            let (names, pools): (Vec<_>, Vec<_>) = pools.into_iter().unzip();
+            stage(pools, ids, |pools, ids| {
+                f(names.into_iter().zip(pools).collect(), ids)
+            })
+        })
+    }
+
+    pub fn sync(&self) -> Result<(), SyncError> {
+        for name in self.workflow.names() {
+            self.pools[name].sync()?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads all pools again from storage, dropping any change which hasn't been
+    /// written. IDs of open items are registered anew.
+    pub fn reload(&mut self) -> Result<(), InitError> {
+        let open = self.workflow.open().map(str::to_owned).collect::<Vec<_>>();
+
+        for name in &open {
+            for item in self.pools[name]
+                .select(TagSet::new(), None, None)
+                .unwrap_or_default()
+            {
+                self.ids.remove(item.id());
+            }
+        }
+
+        for pool in self.pools.values_mut() {
+            pool.reload()?;
+        }
+
+        for name in &open {
+            for item in self.pools[name]
+                .select(TagSet::new(), None, None)
+                .unwrap_or_default()
+            {
+                self.ids.add(item.id());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The open state of `workflow` which the item with `id` is in, with its pool out of
+/// `pools`, as given by [`Data::transaction_all`].
+pub fn locate_open<'a, 'b>(
+    workflow: &Workflow,
+    pools: Vec<(&'a str, &'b mut dyn ItemPool)>,
+    id: u64,
+) -> Option<(&'a str, &'b mut dyn ItemPool)> {
+    pools
+        .into_iter()
+        .find(|(name, pool)| !workflow.is_final(name) && pool.get(id).is_ok())
+}
+
+/// Applies `f` to `pools` and `ids`, keeping the changes only if `f` succeeds and the
+/// changed pools are written to storage, otherwise taking every one of them back.
+fn stage<F, T, E>(
+    mut pools: Vec<&mut dyn ItemPool>,
+    ids: &mut dyn IdPool,
+    f: F,
+) -> Result<Result<T, E>, TransactionError>
+where
+    F: FnOnce(Vec<&mut dyn ItemPool>, &mut dyn IdPool) -> Result<T, E>,
+{
+    let mut staged = pools
+        .iter_mut()
+        .map(|pool| Staged::new(&mut **pool))
+        .collect::<Vec<_>>();
+    let mut staged_ids = StagedIds::new(&mut *ids);
+
+    let result = f(
+        staged
+            .iter_mut()
+            .map(|pool| pool as &mut dyn ItemPool)
+            .collect(),
+        &mut staged_ids,
+    );
+
+    let logs = staged.into_iter().map(Staged::into_log).collect::<Vec<_>>();
+    let id_log = staged_ids.into_log();
+
+    let changed = pools
+        .iter()
+        .zip(&logs)
+        .filter(|(_, log)| !log.is_empty())
+        .map(|(pool, _)| &**pool)
+        .collect::<Vec<_>>();
+
+    let committed = match result {
+        Ok(_) => transaction::commit(&changed),
+        Err(_) => Ok(()),
+    };
+
+    if result.is_ok() && committed.is_ok() {
+        return Ok(result);
+    }
+
+    for (pool, log) in pools.iter_mut().zip(logs) {
+        let changed = !log.is_empty();
+        transaction::rollback(*pool, log);
+
+        // Pools put in place before the failure are written back as they were.
+        if changed && committed.is_err() {
+            let _ = pool.sync();
+        }
+    }
+    transaction::rollback_ids(ids, id_log);
+
+    match committed {
+        Ok(()) => Ok(result),
+        Err(source) => Err(TransactionError::Commit { source }),
+    }
 }
 
 impl Repository {
     pub fn new(data: Data) -> Self {
         Self {
-            inner: Mutex::new(data),
+            inner: RwLock::new(data),
         }
     }
 
-    pub fn apply_planned<F, T>(&self, f: F) -> T
+    pub fn workflow(&self) -> Workflow {
+        self.inner.read_blocking().workflow.clone()
+    }
+
+    pub fn sync(&self) -> Result<(), SyncError> {
+        self.inner.read_blocking().sync()
+    }
+
+    /// Reads all pools again from storage, for long-running processes to see changes
+    /// made by others. IDs of open items are registered anew.
+    pub fn reload(&self) -> Result<(), InitError> {
+        self.inner.write_blocking().reload()
+    }
+
+    /// The state of the item with `id`, if any.
+    pub fn locate(&self, id: u64) -> Option<String> {
+        self.inner.read_blocking().locate(id).map(str::to_owned)
+    }
+
+    /// See [`Data::transaction`].
+    pub fn transaction<const N: usize, F, T, E>(
+        &self,
+        names: [&str; N],
+        f: F,
+    ) -> Result<Result<T, E>, TransactionError>
     where
-        F: FnOnce(&mut dyn ItemPool) -> T,
+        F: FnOnce([&mut dyn ItemPool; N], &mut dyn IdPool) -> Result<T, E>,
     {
-        let data = &mut *self.inner.lock().unwrap();
-        let planned = &mut data.planned;
-        f(planned.as_mut())
+        self.inner.write_blocking().transaction(names, f)
     }
 
-    pub fn apply_finished<F, T>(&self, f: F) -> T
+    /// See [`Data::transaction_all`].
+    pub fn transaction_all<F, T, E>(&self, f: F) -> Result<Result<T, E>, TransactionError>
     where
-        F: FnOnce(&mut dyn ItemPool) -> T,
+        F: FnOnce(Vec<(&str, &mut dyn ItemPool)>, &mut dyn IdPool) -> Result<T, E>,
     {
-        let data = &mut *self.inner.lock().unwrap();
-        let finished = &mut data.finished;
-        f(finished.as_mut())
+        self.inner.write_blocking().transaction_all(f)
     }
 
-    pub fn apply_canceled<F, T>(&self, f: F) -> T
+    /// See [`Data::read`].
+    pub fn read<const N: usize, F, T>(&self, names: [&str; N], f: F) -> Result<T, StateError>
     where
-        F: FnOnce(&mut dyn ItemPool) -> T,
+        F: FnOnce([&dyn ItemPool; N], &dyn IdPool) -> T,
     {
-        let data = &mut *self.inner.lock().unwrap();
-        let canceled = &mut data.canceled;
-        f(canceled.as_mut())
+        self.inner.read_blocking().read(names, f)
     }
 
-    pub fn apply_planned_ids<F, T>(&self, f: F) -> T
+    /// Like [`Repository::read`], for the built-in groups which are states of every
+    /// workflow.
+    pub fn read_groups<const N: usize, F, T>(&self, groups: [Group; N], f: F) -> T
     where
-        F: FnOnce(&mut dyn ItemPool, &mut dyn IdPool) -> T,
+        F: FnOnce([&dyn ItemPool; N], &dyn IdPool) -> T,
     {
-        let data = &mut *self.inner.lock().unwrap();
-        let planned = &mut data.planned;
-        let ids = &mut data.ids;
-        f(planned.as_mut(), ids.as_mut())
+        match self.read(groups.map(|group| group.name()), f) {
+            Ok(value) => value,
+            Err(err) => unreachable!("{err}"),
+        }
     }
 
-    pub fn apply_planned_finished_canceled<F, T>(&self, f: F) -> T
+    /// See [`Data::read_all`].
+    pub fn read_all<F, T>(&self, f: F) -> T
     where
-        F: FnOnce(&mut dyn ItemPool, &mut dyn ItemPool, &mut dyn ItemPool) -> T,
+        F: FnOnce(Vec<(&str, &dyn ItemPool)>, &dyn IdPool) -> T,
     {
-        let data = &mut *self.inner.lock().unwrap();
-        let planned = &mut data.planned;
-        let finished = &mut data.finished;
-        let canceled = &mut data.canceled;
-        f(planned.as_mut(), finished.as_mut(), canceled.as_mut())
+        self.inner.read_blocking().read_all(f)
     }
 
-    pub fn apply_planned_finished_ids<F, T>(&self, f: F) -> T
+    /// Like [`Repository::read_all`], for the pools of the states items are still open
+    /// in, in the order of the workflow.
+    pub fn read_open<F, T>(&self, f: F) -> T
     where
-        F: FnOnce(&mut dyn ItemPool, &mut dyn ItemPool, &mut dyn IdPool) -> T,
+        F: FnOnce(Vec<&dyn ItemPool>, &dyn IdPool) -> T,
     {
-        let data = &mut *self.inner.lock().unwrap();
-        let planned = &mut data.planned;
-        let finished = &mut data.finished;
-        let ids = &mut data.ids;
-        f(planned.as_mut(), finished.as_mut(), ids.as_mut())
+        let data = self.inner.read_blocking();
+        let workflow = data.workflow();
+
+        data.read_all(|pools, ids| {
+            let open = pools
+                .into_iter()
+                .filter(|(name, _)| !workflow.is_final(name))
+                .map(|(_, pool)| pool)
+                .collect();
+            f(open, ids)
+        })
     }
 
-    pub fn apply_planned_canceled_ids<F, T>(&self, f: F) -> T
+    /// See [`Data::apply`].
+    pub fn apply<const N: usize, F, T>(&self, names: [&str; N], f: F) -> Result<T, StateError>
     where
-        F: FnOnce(&mut dyn ItemPool, &mut dyn ItemPool, &mut dyn IdPool) -> T,
+        F: FnOnce([&mut dyn ItemPool; N], &mut dyn IdPool) -> T,
     {
-        let data = &mut *self.inner.lock().unwrap();
-        let planned = &mut data.planned;
-        let canceled = &mut data.canceled;
-        let ids = &mut data.ids;
-        f(planned.as_mut(), canceled.as_mut(), ids.as_mut())
+        self.inner.write_blocking().apply(names, f)
     }
 
-    pub fn apply_ids<F, T>(&self, f: F) -> T
+    /// Like [`Repository::apply`], for the built-in groups which are states of every
+    /// workflow.
+    pub fn apply_groups<const N: usize, F, T>(&self, groups: [Group; N], f: F) -> T
     where
-        F: FnOnce(&mut dyn IdPool) -> T,
+        F: FnOnce([&mut dyn ItemPool; N], &mut dyn IdPool) -> T,
     {
-        let data = &mut *self.inner.lock().unwrap();
-        let ids = &mut data.ids;
-        f(ids.as_mut())
+        match self.apply(groups.map(|group| group.name()), f) {
+            Ok(value) => value,
+            Err(err) => unreachable!("{err}"),
+        }
+    }
+
+    /// See [`Data::apply_all`].
+    pub fn apply_all<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce(Vec<(&str, &mut dyn ItemPool)>, &mut dyn IdPool) -> T,
+    {
+        self.inner.write_blocking().apply_all(f)
+    }
+}
+
+#[cfg(feature = "async")]
+impl Repository {
+    /// Waits for the lock without blocking the thread, to read pools along with others.
+    pub async fn read_async(&self) -> RwLockReadGuard<'_, Data> {
+        self.inner.read().await
+    }
+
+    /// Waits for the lock without blocking the thread, to change pools alone.
+    pub async fn write_async(&self) -> RwLockWriteGuard<'_, Data> {
+        self.inner.write().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::entity::{Item, State};
+
+    use super::id::TriePool;
+    use super::item::MemoryPool;
+    use super::*;
+
+    fn open(workflow: Workflow) -> Repository {
+        let data = Data::new(workflow, Box::new(TriePool::new()), |_| {
+            Ok::<_, ()>(Box::new(MemoryPool::new()) as Box<dyn ItemPool>)
+        });
+        Repository::new(data.unwrap())
+    }
+
+    #[test]
+    fn it_should_apply_to_several_pools_at_once() {
+        let repo = open(Workflow::default());
+        let item = Item::new_test();
+        let id = item.id();
+
+        let names = [Group::Planned.name(), Group::Canceled.name()];
+        let moved = repo.apply(names, |[planned, canceled], ids| {
+            assert!(planned.add(item).is_ok());
+            ids.add(id);
+            planned.remove(id).map(|item| canceled.add(item).is_ok())
+        });
+
+        assert!(matches!(moved, Ok(Ok(true))));
+        assert_eq!(repo.locate(id), Some(Group::Canceled.name().to_owned()));
+        assert!(repo.apply(["doing"], |_, _| ()).is_err());
+    }
+
+    #[test]
+    fn it_should_apply_to_all_pools_in_order() {
+        let state = |name: &str, to: &[&str]| State {
+            name: name.to_owned(),
+            to: to.iter().map(|&name| name.to_owned()).collect(),
+        };
+        let workflow = Workflow::new(vec![
+            state("planned", &["doing", "canceled"]),
+            state("doing", &["finished"]),
+            state("finished", &[]),
+            state("canceled", &[]),
+        ]);
+        let repo = open(workflow.unwrap());
+
+        let names = repo.apply_all(|pools, _| {
+            pools
+                .into_iter()
+                .map(|(name, _)| name.to_owned())
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(names, ["planned", "doing", "finished", "canceled"]);
+        assert_eq!(repo.read_open(|open, _| open.len()), 2);
+    }
+
+    #[test]
+    fn it_should_take_back_changes_to_all_pools_at_once() {
+        let repo = open(Workflow::default());
+        let item = Item::new_test();
+        let id = item.id();
+
+        let res = repo.transaction_all(|pools, ids| {
+            let (_, planned) = pools.into_iter().next().unwrap();
+            assert!(planned.add(item).is_ok());
+            ids.add(id);
+            Err::<(), _>("failed later")
+        });
+
+        assert!(matches!(res, Ok(Err("failed later"))));
+        assert_eq!(repo.locate(id), None);
+        assert_eq!(repo.read_groups([], |[], ids| ids.find(id)), None);
+    }
+
+    #[test]
+    fn it_should_let_readers_share_the_lock() {
+        let repo = open(Workflow::default());
+
+        // A writer would wait for the outer reader forever.
+        let nested = repo.read_groups([Group::Planned], |_, _| {
+            repo.read_all(|pools, _| pools.len())
+        });
+
+        assert_eq!(nested, 3);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn it_should_wait_for_the_lock_without_blocking() {
+        let repo = open(Workflow::default());
+        let item = Item::new_test();
+        let id = item.id();
+
+        let state = pollster::block_on(async {
+            let res = repo
+                .write_async()
+                .await
+                .transaction([Group::Planned.name()], |[planned], _| planned.add(item));
+            assert!(matches!(res, Ok(Ok(_))));
+
+            repo.read_async().await.locate(id).map(str::to_owned)
+        });
+
+        assert_eq!(state.as_deref(), Some(Group::Planned.name()));
     }
 }
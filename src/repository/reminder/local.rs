@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::repository::item::local::{InitError, SyncError};
+
+use super::{MemoryPool, Pool};
+
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct RawReminder {
+    id: u64,
+    lead: i64,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Data {
+    fired: Vec<RawReminder>,
+}
+
+/// Fired reminders kept in a JSON file, which is written back when dropped.
+pub struct LocalPool {
+    pool: MemoryPool,
+    path: PathBuf,
+}
+
+impl LocalPool {
+    pub fn open(path: PathBuf) -> Result<Self, InitError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(|err| InitError::Open { source: err })?;
+
+        let mut json = String::new();
+        BufReader::new(file)
+            .read_to_string(&mut json)
+            .map_err(|err| InitError::Read { source: err })?;
+
+        let data = if !json.is_empty() {
+            serde_json::from_str::<Data>(&json).map_err(|err| InitError::Invalid { source: err })?
+        } else {
+            Data::default()
+        };
+
+        let fired = data
+            .fired
+            .into_iter()
+            .map(|reminder| (reminder.id, reminder.lead))
+            .collect::<HashSet<_>>();
+
+        Ok(Self {
+            pool: MemoryPool::from(fired),
+            path,
+        })
+    }
+
+    pub fn sync(&self) -> Result<(), SyncError> {
+        let mut fired = self
+            .pool
+            .clone_inner()
+            .into_iter()
+            .map(|(id, lead)| RawReminder { id, lead })
+            .collect::<Vec<_>>();
+
+        fired.sort_unstable_by_key(|reminder| (reminder.id, reminder.lead));
+
+        let json = serde_json::to_string(&Data { fired })
+            .map_err(|err| SyncError::Dump { source: err })?;
+
+        let file = OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(&self.path)
+            .map_err(|err| SyncError::Open { source: err })?;
+
+        let mut writer = BufWriter::new(file);
+        writer
+            .write_all(json.as_bytes())
+            .and_then(|()| writer.flush())
+            .map_err(|err| SyncError::Write { source: err })
+    }
+}
+
+impl Drop for LocalPool {
+    fn drop(&mut self) {
+        if let Err(err) = self.sync() {
+            eprintln!("Error: failed to save {}: {err}", self.path.display());
+        }
+    }
+}
+
+impl Pool for LocalPool {
+    fn add(&mut self, id: u64, lead: Duration) -> bool {
+        self.pool.add(id, lead)
+    }
+
+    fn contains(&self, id: u64, lead: Duration) -> bool {
+        self.pool.contains(id, lead)
+    }
+
+    fn retain(&mut self, ids: &HashSet<u64>) {
+        self.pool.retain(ids);
+    }
+}
@@ -0,0 +1,40 @@
+use std::collections::HashSet;
+
+use chrono::Duration;
+
+use super::Pool;
+
+#[derive(Debug, Default, Clone)]
+pub struct MemoryPool {
+    fired: HashSet<(u64, i64)>,
+}
+
+impl MemoryPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clone_inner(&self) -> HashSet<(u64, i64)> {
+        self.fired.clone()
+    }
+}
+
+impl From<HashSet<(u64, i64)>> for MemoryPool {
+    fn from(value: HashSet<(u64, i64)>) -> Self {
+        Self { fired: value }
+    }
+}
+
+impl Pool for MemoryPool {
+    fn add(&mut self, id: u64, lead: Duration) -> bool {
+        self.fired.insert((id, lead.num_seconds()))
+    }
+
+    fn contains(&self, id: u64, lead: Duration) -> bool {
+        self.fired.contains(&(id, lead.num_seconds()))
+    }
+
+    fn retain(&mut self, ids: &HashSet<u64>) {
+        self.fired.retain(|(id, _)| ids.contains(id));
+    }
+}
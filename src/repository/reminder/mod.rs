@@ -0,0 +1,20 @@
+pub mod local;
+pub mod memory;
+
+use std::collections::HashSet;
+
+use chrono::Duration;
+
+pub use local::LocalPool;
+pub use memory::MemoryPool;
+
+/// Remembers which reminders have fired, each being an item ID with the lead time
+/// before its deadline.
+pub trait Pool: Send {
+    fn add(&mut self, id: u64, lead: Duration) -> bool;
+
+    fn contains(&self, id: u64, lead: Duration) -> bool;
+
+    /// Forgets reminders of any item not in `ids`.
+    fn retain(&mut self, ids: &HashSet<u64>);
+}
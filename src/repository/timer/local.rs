@@ -0,0 +1,113 @@
+use std::fs::OpenOptions;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entity::Interval;
+use crate::repository::item::local::{InitError, SyncError};
+
+use super::{MemoryPool, Pool};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct RawTimer {
+    id: u64,
+    start: NaiveDateTime,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Data {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    active: Option<RawTimer>,
+    #[serde(default)]
+    intervals: Vec<Interval>,
+}
+
+/// Tracked time kept in a JSON file, which is written back when dropped.
+pub struct LocalPool {
+    pool: MemoryPool,
+    path: PathBuf,
+}
+
+impl LocalPool {
+    pub fn open(path: PathBuf) -> Result<Self, InitError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(|err| InitError::Open { source: err })?;
+
+        let mut json = String::new();
+        BufReader::new(file)
+            .read_to_string(&mut json)
+            .map_err(|err| InitError::Read { source: err })?;
+
+        let data = if !json.is_empty() {
+            serde_json::from_str::<Data>(&json).map_err(|err| InitError::Invalid { source: err })?
+        } else {
+            Data::default()
+        };
+
+        let active = data.active.map(|timer| (timer.id, timer.start));
+
+        Ok(Self {
+            pool: MemoryPool::from((active, data.intervals)),
+            path,
+        })
+    }
+
+    pub fn sync(&self) -> Result<(), SyncError> {
+        let data = Data {
+            active: self.pool.active().map(|(id, start)| RawTimer { id, start }),
+            intervals: self.pool.intervals(),
+        };
+
+        let json = serde_json::to_string(&data).map_err(|err| SyncError::Dump { source: err })?;
+
+        let file = OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(&self.path)
+            .map_err(|err| SyncError::Open { source: err })?;
+
+        let mut writer = BufWriter::new(file);
+        writer
+            .write_all(json.as_bytes())
+            .and_then(|()| writer.flush())
+            .map_err(|err| SyncError::Write { source: err })
+    }
+}
+
+impl Drop for LocalPool {
+    fn drop(&mut self) {
+        if let Err(err) = self.sync() {
+            eprintln!("Error: failed to save {}: {err}", self.path.display());
+        }
+    }
+}
+
+impl Pool for LocalPool {
+    fn active(&self) -> Option<(u64, NaiveDateTime)> {
+        self.pool.active()
+    }
+
+    fn set_active(&mut self, active: Option<(u64, NaiveDateTime)>) {
+        self.pool.set_active(active);
+    }
+
+    fn add(&mut self, interval: Interval) {
+        self.pool.add(interval);
+    }
+
+    fn intervals(&self) -> Vec<Interval> {
+        self.pool.intervals()
+    }
+
+    fn rekey(&mut self, from: u64, to: u64) {
+        self.pool.rekey(from, to);
+    }
+}
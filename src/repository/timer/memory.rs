@@ -0,0 +1,86 @@
+use chrono::NaiveDateTime;
+
+use crate::domain::entity::Interval;
+
+use super::Pool;
+
+#[derive(Debug, Default, Clone)]
+pub struct MemoryPool {
+    active: Option<(u64, NaiveDateTime)>,
+    intervals: Vec<Interval>,
+}
+
+impl MemoryPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl From<(Option<(u64, NaiveDateTime)>, Vec<Interval>)> for MemoryPool {
+    fn from((active, intervals): (Option<(u64, NaiveDateTime)>, Vec<Interval>)) -> Self {
+        Self { active, intervals }
+    }
+}
+
+impl Pool for MemoryPool {
+    fn active(&self) -> Option<(u64, NaiveDateTime)> {
+        self.active
+    }
+
+    fn set_active(&mut self, active: Option<(u64, NaiveDateTime)>) {
+        self.active = active;
+    }
+
+    fn add(&mut self, interval: Interval) {
+        self.intervals.push(interval);
+    }
+
+    fn intervals(&self) -> Vec<Interval> {
+        self.intervals.clone()
+    }
+
+    fn rekey(&mut self, from: u64, to: u64) {
+        if let Some((id, _)) = self.active.as_mut().filter(|(id, _)| *id == from) {
+            *id = to;
+        }
+
+        for interval in self
+            .intervals
+            .iter_mut()
+            .filter(|interval| interval.id == from)
+        {
+            interval.id = to;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn it_should_hand_tracked_time_over_to_the_new_id() {
+        let start =
+            NaiveDateTime::parse_from_str("2023-06-17 09:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let end = start + Duration::hours(1);
+        let intervals = vec![
+            Interval { id: 1, start, end },
+            Interval { id: 2, start, end },
+        ];
+        let mut timers = MemoryPool::from((Some((1, end)), intervals));
+
+        timers.rekey(1, 3);
+
+        assert_eq!(timers.active(), Some((3, end)));
+        assert_eq!(
+            timers
+                .intervals()
+                .iter()
+                .map(|interval| interval.id)
+                .collect::<Vec<_>>(),
+            [3, 2]
+        );
+    }
+}
@@ -0,0 +1,25 @@
+pub mod local;
+pub mod memory;
+
+use chrono::NaiveDateTime;
+
+use crate::domain::entity::Interval;
+
+pub use local::LocalPool;
+pub use memory::MemoryPool;
+
+/// Tracked time: the running timer, if any, as an item ID with when it started, and the
+/// intervals of stopped ones.
+pub trait Pool: Send {
+    fn active(&self) -> Option<(u64, NaiveDateTime)>;
+
+    fn set_active(&mut self, active: Option<(u64, NaiveDateTime)>);
+
+    fn add(&mut self, interval: Interval);
+
+    fn intervals(&self) -> Vec<Interval>;
+
+    /// Hands the running timer and the intervals of the item `from` over to `to`, for
+    /// an item which got a new ID when it was edited.
+    fn rekey(&mut self, from: u64, to: u64);
+}
@@ -0,0 +1,282 @@
+use chrono::NaiveDateTime;
+
+use crate::domain::entity::{Item, Priority, TagSet};
+
+use super::id::Pool as IdPool;
+use super::item::{
+    AddError, AddTagError, GetError, Pool as ItemPool, RemoveError, RemoveTagError, SelectError,
+    SetPriorityError, SyncError,
+};
+
+/// How to take back one change to an item pool.
+pub(super) enum Undo {
+    Remove(u64),
+    Add(Item),
+    /// Puts back the item as it was before its tags or priority changed.
+    Restore(Item),
+    /// Puts back the items of a cleared pool.
+    Refill(Vec<Item>),
+}
+
+/// An item pool which records how to take back every change made through it.
+pub(super) struct Staged<'a> {
+    pool: &'a mut dyn ItemPool,
+    log: Vec<Undo>,
+}
+
+/// An ID pool which records how to take back every change made through it.
+pub(super) struct StagedIds<'a> {
+    ids: &'a mut dyn IdPool,
+    /// Whether each ID was added or removed.
+    log: Vec<(u64, bool)>,
+}
+
+impl<'a> Staged<'a> {
+    pub fn new(pool: &'a mut dyn ItemPool) -> Self {
+        Self {
+            pool,
+            log: Vec::new(),
+        }
+    }
+
+    pub fn into_log(self) -> Vec<Undo> {
+        self.log
+    }
+
+    fn record(&mut self, id: u64) {
+        if let Ok(item) = self.pool.get(id) {
+            self.log.push(Undo::Restore(item));
+        }
+    }
+}
+
+impl<'a> StagedIds<'a> {
+    pub fn new(ids: &'a mut dyn IdPool) -> Self {
+        Self {
+            ids,
+            log: Vec::new(),
+        }
+    }
+
+    pub fn into_log(self) -> Vec<(u64, bool)> {
+        self.log
+    }
+}
+
+/// Takes back the changes of `log` in reverse order.
+pub(super) fn rollback(pool: &mut dyn ItemPool, log: Vec<Undo>) {
+    for undo in log.into_iter().rev() {
+        match undo {
+            Undo::Remove(id) => {
+                let _ = pool.remove(id);
+            }
+            Undo::Add(item) => {
+                let _ = pool.add(item);
+            }
+            Undo::Restore(item) => {
+                let _ = pool.remove(item.id());
+                let _ = pool.add(item);
+            }
+            Undo::Refill(items) => {
+                for item in items {
+                    let _ = pool.add(item);
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn rollback_ids(ids: &mut dyn IdPool, log: Vec<(u64, bool)>) {
+    for (id, added) in log.into_iter().rev() {
+        if added {
+            ids.remove(id);
+        } else {
+            ids.add(id);
+        }
+    }
+}
+
+/// Writes every pool before putting any in place. Pools already put in place when a
+/// later one fails are left to the caller, which rolls them back and writes them again.
+pub(super) fn commit(pools: &[&dyn ItemPool]) -> Result<(), SyncError> {
+    if let Err(err) = pools.iter().try_for_each(|pool| pool.stage()) {
+        pools.iter().for_each(|pool| pool.discard());
+        return Err(err);
+    }
+
+    pools.iter().try_for_each(|pool| pool.commit())
+}
+
+impl ItemPool for Staged<'_> {
+    fn add(&mut self, item: Item) -> Result<u64, AddError> {
+        let id = self.pool.add(item)?;
+        self.log.push(Undo::Remove(id));
+        Ok(id)
+    }
+
+    fn remove(&mut self, id: u64) -> Result<Item, RemoveError> {
+        let item = self.pool.remove(id)?;
+        self.log.push(Undo::Add(item.clone()));
+        Ok(item)
+    }
+
+    fn get(&self, id: u64) -> Result<Item, GetError> {
+        self.pool.get(id)
+    }
+
+    fn select(
+        &self,
+        tags: TagSet,
+        before: Option<NaiveDateTime>,
+        after: Option<NaiveDateTime>,
+    ) -> Result<Vec<Item>, SelectError> {
+        self.pool.select(tags, before, after)
+    }
+
+    // Tags may change before a conflict is found, so the item is recorded either way.
+    fn add_tag(&mut self, id: u64, tags: TagSet) -> Result<(), AddTagError> {
+        self.record(id);
+        self.pool.add_tag(id, tags)
+    }
+
+    fn remove_tag(&mut self, id: u64, tags: TagSet) -> Result<(), RemoveTagError> {
+        self.record(id);
+        self.pool.remove_tag(id, tags)
+    }
+
+    fn set_priority(&mut self, id: u64, priority: Priority) -> Result<(), SetPriorityError> {
+        self.record(id);
+        self.pool.set_priority(id, priority)
+    }
+
+    fn clear(&mut self) {
+        let items = self
+            .pool
+            .select(TagSet::new(), None, None)
+            .unwrap_or_default();
+        self.pool.clear();
+        self.log.push(Undo::Refill(items));
+    }
+}
+
+impl IdPool for StagedIds<'_> {
+    fn add(&mut self, id: u64) -> bool {
+        let added = self.ids.add(id);
+        if added {
+            self.log.push((id, true));
+        }
+        added
+    }
+
+    fn remove(&mut self, id: u64) -> bool {
+        let removed = self.ids.remove(id);
+        if removed {
+            self.log.push((id, false));
+        }
+        removed
+    }
+
+    fn find(&self, pattern: u64) -> Option<Vec<u64>> {
+        self.ids.find(pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use crate::domain::entity::{Group, Workflow};
+    use crate::repository::id::TriePool;
+    use crate::repository::item::{LocalPool, MemoryPool};
+    use crate::repository::{Data, Repository, TransactionError};
+
+    use super::*;
+
+    fn open<F>(pool: F) -> Repository
+    where
+        F: Fn(&str) -> Box<dyn ItemPool>,
+    {
+        let data = Data::new(Workflow::default(), Box::new(TriePool::new()), |name| {
+            Ok::<_, ()>(pool(name))
+        });
+        Repository::new(data.unwrap())
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("todo-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn it_should_take_back_every_change_when_failed() {
+        let repo = open(|_| Box::new(MemoryPool::new()));
+        let item = Item::new_test();
+        let id = item.id();
+
+        let names = [Group::Planned.name(), Group::Finished.name()];
+        let res = repo.transaction(names, |[planned, finished], ids| {
+            assert!(planned.add(item.clone()).is_ok());
+            ids.add(id);
+            planned.add_tag(id, TagSet::from(["a".to_owned()]))?;
+            if let Ok(item) = planned.remove(id) {
+                assert!(finished.add(item).is_ok());
+            }
+            finished.clear();
+            Err::<(), _>(AddTagError::NotFound)
+        });
+
+        assert!(matches!(res, Ok(Err(AddTagError::NotFound))));
+        assert_eq!(repo.locate(id), None);
+        assert_eq!(repo.apply_groups([], |[], ids| ids.find(id)), None);
+    }
+
+    #[test]
+    fn it_should_write_changed_pools_when_succeeded() {
+        let dir = temp_dir("transaction");
+        let repo = open(|name| {
+            let path = dir.join(format!("{name}.json"));
+            Box::new(LocalPool::open(path).unwrap())
+        });
+        let item = Item::new_test();
+
+        let res = repo.transaction([Group::Planned.name()], |[planned], _| {
+            planned.add(item.clone())
+        });
+
+        assert!(matches!(res, Ok(Ok(_))));
+        let written = fs::read_to_string(dir.join("planned.json")).unwrap();
+        assert!(written.contains(&format!("\"summary\": \"{}\"", item.summary())));
+        assert!(!dir.join("planned.json.tmp").exists());
+
+        drop(repo);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn it_should_take_back_every_change_when_not_written() {
+        let dir = temp_dir("transaction-unwritable");
+        let repo = open(|name| {
+            let path = dir.join(format!("{name}.json"));
+            Box::new(LocalPool::open(path).unwrap())
+        });
+        let item = Item::new_test();
+        let id = item.id();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let res = repo.transaction([Group::Planned.name()], |[planned], ids| {
+            ids.add(id);
+            planned.add(item.clone())
+        });
+
+        assert!(matches!(res, Err(TransactionError::Commit { .. })));
+        assert_eq!(repo.locate(id), None);
+        assert_eq!(repo.apply_groups([], |[], ids| ids.find(id)), None);
+
+        // Nothing is left to write when the repository is dropped.
+        fs::create_dir_all(&dir).unwrap();
+        drop(repo);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
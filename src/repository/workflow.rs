@@ -0,0 +1,39 @@
+use std::fs;
+use std::io::{Error as IoError, ErrorKind};
+use std::path::Path;
+
+use serde_json::Error as SerdeError;
+use snafu::prelude::*;
+
+use crate::domain::entity::Workflow;
+
+#[derive(Debug, Snafu)]
+pub enum LoadError {
+    #[snafu(display("Failed to read states: {source}"))]
+    Read { source: IoError },
+    #[snafu(display("Invalid states: {source}"))]
+    Invalid { source: SerdeError },
+}
+
+/// Reads the configured states from `path`, falling back to the default ones if there's
+/// no such file.
+pub fn load(path: &Path) -> Result<Workflow, LoadError> {
+    match fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json).context(InvalidSnafu),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(Workflow::default()),
+        Err(err) => Err(LoadError::Read { source: err }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn it_should_fall_back_to_default_states_without_a_file() {
+        let path = env::temp_dir().join("todo-workflow-test-missing.json");
+        assert_eq!(load(&path).unwrap(), Workflow::default());
+    }
+}
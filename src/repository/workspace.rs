@@ -0,0 +1,184 @@
+use std::fs;
+use std::io::Error as IoError;
+use std::path::{Path, PathBuf};
+
+use snafu::prelude::*;
+
+/// Named sets of pools under one storage root. The default workspace lives in the root
+/// itself, so storage from before workspaces existed keeps working, while the others
+/// live in `workspaces/<name>`. The current workspace is remembered in a `workspace`
+/// file in the root.
+pub struct Workspaces {
+    root: PathBuf,
+}
+
+#[derive(Debug, Snafu)]
+pub enum WorkspaceError {
+    #[snafu(display("Workspace `{name}` doesn't exist"))]
+    NotFound { name: String },
+    #[snafu(display("Workspace `{name}` already exists"))]
+    Conflict { name: String },
+    #[snafu(display("`{name}` is not a valid workspace name, use letters, digits, `-` and `_`"))]
+    Invalid { name: String },
+    #[snafu(display("The default workspace can't be deleted or renamed"))]
+    Default,
+    #[snafu(display("Failed to access workspaces: {source}"))]
+    Io { source: IoError },
+}
+
+impl Workspaces {
+    pub const DEFAULT: &'static str = "default";
+
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The directory of an existing workspace.
+    pub fn path(&self, name: &str) -> Result<PathBuf, WorkspaceError> {
+        let path = self.locate(name)?;
+
+        if name == Self::DEFAULT || path.is_dir() {
+            Ok(path)
+        } else {
+            NotFoundSnafu { name }.fail()
+        }
+    }
+
+    /// The remembered workspace, or the default one if none is remembered or it's gone.
+    pub fn current(&self) -> String {
+        fs::read_to_string(self.root.join("workspace"))
+            .ok()
+            .map(|name| name.trim().to_owned())
+            .filter(|name| self.path(name).is_ok())
+            .unwrap_or_else(|| Self::DEFAULT.to_owned())
+    }
+
+    pub fn switch(&self, name: &str) -> Result<(), WorkspaceError> {
+        self.path(name)?;
+        fs::create_dir_all(&self.root).context(IoSnafu)?;
+        fs::write(self.root.join("workspace"), name).context(IoSnafu)
+    }
+
+    pub fn list(&self) -> Result<Vec<String>, WorkspaceError> {
+        let mut names = Vec::new();
+
+        match fs::read_dir(self.root.join("workspaces")) {
+            Ok(entries) => {
+                for entry in entries {
+                    let entry = entry.context(IoSnafu)?;
+
+                    if entry.path().is_dir() {
+                        names.push(entry.file_name().to_string_lossy().into_owned());
+                    }
+                }
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(WorkspaceError::Io { source: err }),
+        }
+
+        names.sort_unstable();
+        names.insert(0, Self::DEFAULT.to_owned());
+        Ok(names)
+    }
+
+    pub fn create(&self, name: &str) -> Result<PathBuf, WorkspaceError> {
+        let path = self.locate(name)?;
+        ensure!(self.path(name).is_err(), ConflictSnafu { name });
+
+        fs::create_dir_all(&path).context(IoSnafu)?;
+        Ok(path)
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), WorkspaceError> {
+        ensure!(name != Self::DEFAULT, DefaultSnafu);
+
+        let current = self.current();
+        fs::remove_dir_all(self.path(name)?).context(IoSnafu)?;
+
+        if current == name {
+            self.switch(Self::DEFAULT)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn rename(&self, name: &str, new_name: &str) -> Result<(), WorkspaceError> {
+        ensure!(name != Self::DEFAULT, DefaultSnafu);
+
+        let path = self.path(name)?;
+        let new_path = self.locate(new_name)?;
+        ensure!(
+            self.path(new_name).is_err(),
+            ConflictSnafu { name: new_name }
+        );
+
+        let current = self.current();
+        fs::rename(path, new_path).context(IoSnafu)?;
+
+        if current == name {
+            self.switch(new_name)?;
+        }
+
+        Ok(())
+    }
+
+    fn locate(&self, name: &str) -> Result<PathBuf, WorkspaceError> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+        ensure!(valid, InvalidSnafu { name });
+
+        if name == Self::DEFAULT {
+            Ok(self.root.clone())
+        } else {
+            Ok(self.root.join("workspaces").join(name))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::process;
+
+    use super::*;
+
+    #[test]
+    fn it_should_manage_workspaces_and_remember_the_current_one() {
+        let root = env::temp_dir().join(format!("todo-workspaces-{}", process::id()));
+        let workspaces = Workspaces::new(root.clone());
+
+        assert_eq!(workspaces.current(), Workspaces::DEFAULT);
+        assert!(workspaces.create("work").is_ok());
+        assert!(matches!(
+            workspaces.create("work"),
+            Err(WorkspaceError::Conflict { .. })
+        ));
+        assert!(matches!(
+            workspaces.create("../x"),
+            Err(WorkspaceError::Invalid { .. })
+        ));
+
+        workspaces.switch("work").unwrap();
+        assert_eq!(workspaces.current(), "work");
+
+        workspaces.rename("work", "job").unwrap();
+        assert_eq!(workspaces.current(), "job");
+        assert_eq!(workspaces.list().unwrap(), vec!["default", "job"]);
+
+        workspaces.delete("job").unwrap();
+        assert_eq!(workspaces.current(), Workspaces::DEFAULT);
+        assert!(matches!(
+            workspaces.delete(Workspaces::DEFAULT),
+            Err(WorkspaceError::Default)
+        ));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
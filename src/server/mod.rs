@@ -0,0 +1,61 @@
+mod routes;
+
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::thread;
+
+use tiny_http::{Header, Response, Server};
+
+use crate::repository::Repository;
+
+use routes::Reply;
+
+const WORKERS: usize = 4;
+
+pub fn run(repo: Arc<Repository>, bind: SocketAddr) -> Result<(), Box<dyn Error>> {
+    let server = Arc::new(Server::http(bind).map_err(|err| err as Box<dyn Error>)?);
+    println!("Listening on http://{}", server.server_addr());
+
+    let workers = (0..WORKERS)
+        .map(|_| {
+            let server = server.clone();
+            let repo = repo.clone();
+            thread::spawn(move || serve(&server, &repo))
+        })
+        .collect::<Vec<_>>();
+
+    for worker in workers {
+        let _ = worker.join();
+    }
+
+    Ok(())
+}
+
+/// Changes are written to storage by the request which makes them, since the server is
+/// usually stopped by a signal and pools are otherwise only written when dropped. Pools
+/// are read again before every request, so that changes made meanwhile by the CLI or
+/// the TUI are seen and never written over.
+fn serve(server: &Server, repo: &Repository) {
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+
+        let reply = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => match repo.reload() {
+                Ok(()) => routes::handle(repo, request.method().as_str(), request.url(), &body),
+                Err(err) => Reply::error(500, err),
+            },
+            Err(err) => Reply::error(400, err),
+        };
+
+        let response = match reply.body {
+            Some(body) => Response::from_string(body)
+                .with_header(Header::from_bytes("Content-Type", "application/json").unwrap())
+                .with_status_code(reply.status)
+                .boxed(),
+            None => Response::empty(reply.status).boxed(),
+        };
+
+        let _ = request.respond(response);
+    }
+}
@@ -0,0 +1,524 @@
+use std::convert::Infallible;
+use std::fmt::Display;
+
+use chrono::{Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::domain::entity::{Group, TagSet};
+use crate::domain::usecase::add_tag::{self, AddTagError, Request as AddTagRequest};
+use crate::domain::usecase::clean;
+use crate::domain::usecase::plan::{self, PlanError, Request as PlanRequest};
+use crate::domain::usecase::remove_tag::{self, RemoveTagError, Request as RemoveTagRequest};
+use crate::domain::usecase::select::{self, Request as SelectRequest, Response, SelectItemError};
+use crate::domain::usecase::set_priority::{self, Request as SetPriorityRequest, SetPriorityError};
+use crate::domain::usecase::shift::{self, Request as ShiftRequest, ShiftError};
+use crate::domain::usecase::transition::TransitionError;
+use crate::repository::item::Pool;
+use crate::repository::{self, Repository};
+
+const DATETIME_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    pub status: u16,
+    pub body: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TagsBody {
+    #[serde(default)]
+    add: TagSet,
+    #[serde(default)]
+    remove: TagSet,
+}
+
+#[derive(Deserialize)]
+struct PriorityBody {
+    priority: i32,
+}
+
+impl Reply {
+    fn json<T: Serialize>(status: u16, value: &T) -> Self {
+        Self {
+            status,
+            body: serde_json::to_string(value).ok(),
+        }
+    }
+
+    fn empty() -> Self {
+        Self {
+            status: 204,
+            body: None,
+        }
+    }
+
+    pub fn error<E: Display>(status: u16, err: E) -> Self {
+        Self::json(status, &json!({ "error": err.to_string() }))
+    }
+}
+
+/// Routes a request to the use case behind it. Bodies and replies are JSON, and errors
+/// are replied as `{"error": "..."}` with a matching status code.
+pub fn handle(repo: &Repository, method: &str, url: &str, body: &str) -> Reply {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+    match (method, segments.as_slice()) {
+        ("GET", ["items"]) => list(repo, query),
+        ("POST", ["items"]) => create(repo, body),
+        ("DELETE", ["items"]) => remove(repo, query),
+        ("POST", ["items", id, "finish"]) => close(repo, id, Group::Finished),
+        ("POST", ["items", id, "cancel"]) => close(repo, id, Group::Canceled),
+        ("PATCH", ["items", id, "tags"]) => retag(repo, id, body),
+        ("PATCH", ["items", id, "priority"]) => prioritize(repo, id, body),
+        (_, ["items"] | ["items", _, "finish" | "cancel" | "tags" | "priority"]) => {
+            Reply::error(405, format!("Method {method} isn't allowed"))
+        }
+        _ => Reply::error(404, format!("No route for {path}")),
+    }
+}
+
+fn list(repo: &Repository, query: &str) -> Reply {
+    let mut state = Group::Planned.name().to_owned();
+    let mut request = SelectRequest {
+        tags: TagSet::new(),
+        before: None,
+        after: None,
+    };
+
+    for (name, value) in parse_query(query) {
+        match (name.as_str(), value) {
+            ("group", value) => state = value,
+            ("tag", value) => {
+                request.tags.insert(value);
+            }
+            ("before", value) => match parse_datetime(&value) {
+                Some(value) => request.before = Some(value),
+                None => return invalid(&name, &value),
+            },
+            ("after", value) => match parse_datetime(&value) {
+                Some(value) => request.after = Some(value),
+                None => return invalid(&name, &value),
+            },
+            (_, value) => return invalid(&name, &value),
+        }
+    }
+
+    let func = |pool: &dyn Pool| select::execute(pool, request);
+
+    let response = match repo.read([&state], |[pool], _| func(pool)) {
+        Ok(response) => response,
+        Err(err) => return Reply::error(400, err),
+    };
+
+    match response {
+        Ok(response) => Reply::json(200, &response),
+        Err(SelectItemError::NotFound) => Reply::json(200, &Response { items: Vec::new() }),
+        Err(err @ SelectItemError::Invalid) => Reply::error(400, err),
+    }
+}
+
+fn create(repo: &Repository, body: &str) -> Reply {
+    let request: PlanRequest = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(err) => return Reply::error(400, err),
+    };
+
+    let response = repo.transaction([Group::Planned.name()], |[planned], ids| {
+        plan::execute(planned, ids, request)
+    });
+
+    let response = match response {
+        Ok(response) => response,
+        Err(err) => return Reply::error(500, err),
+    };
+
+    match response {
+        Ok(response) => Reply::json(201, &response),
+        Err(err @ PlanError::Invalid) => Reply::error(422, err),
+        Err(err @ PlanError::Conflict) => Reply::error(409, err),
+    }
+}
+
+/// Only items in final states can be cleaned, those of every final state if no state is
+/// given.
+fn remove(repo: &Repository, query: &str) -> Reply {
+    let workflow = repo.workflow();
+    let mut states = Vec::new();
+
+    for (name, value) in parse_query(query) {
+        match name.as_str() {
+            "group" if !workflow.contains(&value) => return invalid(&name, &value),
+            "group" if !workflow.is_final(&value) => {
+                return Reply::error(
+                    400,
+                    format!("Items in {value} are open and can't be cleaned"),
+                )
+            }
+            "group" => states.push(value),
+            _ => return invalid(&name, &value),
+        }
+    }
+
+    if states.is_empty() {
+        states = workflow
+            .names()
+            .filter(|name| workflow.is_final(name))
+            .map(str::to_owned)
+            .collect();
+    }
+
+    let response = repo.transaction_all(|pools, _| {
+        for (_, pool) in pools
+            .into_iter()
+            .filter(|(name, _)| states.iter().any(|state| state == name))
+        {
+            clean::execute(pool);
+        }
+        Ok::<_, Infallible>(())
+    });
+
+    match response {
+        Ok(_) => Reply::empty(),
+        Err(err) => Reply::error(500, err),
+    }
+}
+
+/// Closes an item in any open state, as far as the workflow allows it.
+fn close(repo: &Repository, id: &str, group: Group) -> Reply {
+    let Some(id) = parse_id(id) else {
+        return Reply::error(400, format!("Invalid ID `{id}`"));
+    };
+
+    let workflow = repo.workflow();
+    let to = group.name();
+    let time = Local::now().naive_local();
+
+    // The item is located and moved at once, so that no other change gets in between.
+    let response = repo.transaction_all(|pools, ids| {
+        let (mut destination, sources): (Vec<_>, Vec<_>) =
+            pools.into_iter().partition(|(name, _)| *name == to);
+        let Some((_, destination)) = destination.pop() else {
+            unreachable!("built-in groups are states of every workflow");
+        };
+        if destination.get(id).is_ok() {
+            return Err(ShiftError::Already { state: to.into() });
+        }
+        let located = sources.into_iter().find(|(_, pool)| pool.get(id).is_ok());
+        let Some((from, source)) = located else {
+            return Err(ShiftError::NotFound);
+        };
+
+        let request = ShiftRequest { id, from, to, time };
+        shift::execute(&workflow, source, destination, ids, request)
+    });
+
+    let response = match response {
+        Ok(response) => response,
+        Err(err) => return Reply::error(500, err),
+    };
+
+    match response {
+        Ok(()) => Reply::empty(),
+        Err(
+            err @ (ShiftError::NotFound
+            | ShiftError::Transition {
+                source: TransitionError::NotFound,
+            }),
+        ) => Reply::error(404, err),
+        Err(err) => Reply::error(409, err),
+    }
+}
+
+fn retag(repo: &Repository, id: &str, body: &str) -> Reply {
+    let Some(id) = parse_id(id) else {
+        return Reply::error(400, format!("Invalid ID `{id}`"));
+    };
+
+    let TagsBody { add, remove } = match serde_json::from_str(body) {
+        Ok(body) => body,
+        Err(err) => return Reply::error(400, err),
+    };
+
+    let workflow = repo.workflow();
+
+    // Tags are added and removed together or not at all.
+    let response = repo.transaction_all(|pools, _| {
+        let Some((_, pool)) = repository::locate_open(&workflow, pools, id) else {
+            return Err(Reply::error(404, AddTagError::NotFound));
+        };
+
+        if !add.is_empty() {
+            let request = AddTagRequest { id, tags: add };
+
+            match add_tag::execute(pool, request) {
+                Ok(()) => {}
+                Err(err @ AddTagError::NotFound) => return Err(Reply::error(404, err)),
+                Err(err @ AddTagError::Conflict) => return Err(Reply::error(409, err)),
+            }
+        }
+
+        if !remove.is_empty() {
+            let request = RemoveTagRequest { id, tags: remove };
+
+            match remove_tag::execute(pool, request) {
+                Ok(()) => {}
+                Err(err @ RemoveTagError::ItemNotFound) => return Err(Reply::error(404, err)),
+                Err(err) => return Err(Reply::error(409, err)),
+            }
+        }
+
+        Ok(())
+    });
+
+    match response {
+        Ok(Ok(())) => Reply::empty(),
+        Ok(Err(reply)) => reply,
+        Err(err) => Reply::error(500, err),
+    }
+}
+
+fn prioritize(repo: &Repository, id: &str, body: &str) -> Reply {
+    let Some(id) = parse_id(id) else {
+        return Reply::error(400, format!("Invalid ID `{id}`"));
+    };
+
+    let PriorityBody { priority } = match serde_json::from_str(body) {
+        Ok(body) => body,
+        Err(err) => return Reply::error(400, err),
+    };
+
+    let workflow = repo.workflow();
+    let request = SetPriorityRequest { id, priority };
+
+    let response =
+        repo.transaction_all(
+            |pools, _| match repository::locate_open(&workflow, pools, id) {
+                Some((_, pool)) => set_priority::execute(pool, request),
+                None => Err(SetPriorityError::NotFound),
+            },
+        );
+
+    let response = match response {
+        Ok(response) => response,
+        Err(err) => return Reply::error(500, err),
+    };
+
+    match response {
+        Ok(()) => Reply::empty(),
+        Err(err @ SetPriorityError::Invalid) => Reply::error(422, err),
+        Err(err @ SetPriorityError::NotFound) => Reply::error(404, err),
+    }
+}
+
+fn invalid(name: &str, value: &str) -> Reply {
+    Reply::error(400, format!("Invalid query parameter `{name}={value}`"))
+}
+
+fn parse_id(value: &str) -> Option<u64> {
+    value.parse().ok()
+}
+
+fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+    DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+}
+
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(name), decode(value))
+        })
+        .collect()
+}
+
+/// Decodes `+` and percent-encoded bytes of a query component. Malformed escapes are
+/// kept as they are.
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut res = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => res.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        res.push(byte);
+                        i += 2;
+                    }
+                    None => res.push(b'%'),
+                }
+            }
+            byte => res.push(byte),
+        }
+
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&res).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use crate::domain::entity::{State, Workflow};
+    use crate::repository::id::TriePool;
+    use crate::repository::item::MemoryPool;
+    use crate::repository::Data;
+
+    use super::*;
+
+    #[test]
+    fn it_should_plan_list_and_finish_items() {
+        let repo = repository();
+
+        let body = r#"{"summary":"Test","deadline":"2023-06-17T23:20:00","tags":["a"]}"#;
+        let reply = handle(&repo, "POST", "/items", body);
+        assert_eq!(reply.status, 201);
+
+        let id = serde_json::from_str::<plan::Response>(&reply.body.unwrap())
+            .unwrap()
+            .id;
+
+        let reply = handle(
+            &repo,
+            "GET",
+            "/items?tag=a&before=2023-06-18+00%3A00%3A00",
+            "",
+        );
+        let Response { items } = serde_json::from_str(&reply.body.unwrap()).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id(), id);
+
+        let reply = handle(&repo, "POST", &format!("/items/{id}/finish"), "");
+        assert_eq!(reply.status, 204);
+
+        let reply = handle(&repo, "GET", "/items?group=finished", "");
+        let Response { items } = serde_json::from_str(&reply.body.unwrap()).unwrap();
+        assert_eq!(items.len(), 1);
+    }
+
+    #[test]
+    fn it_should_reply_errors_with_matching_status_codes() {
+        let repo = repository();
+
+        assert_eq!(handle(&repo, "POST", "/items", "{}").status, 400);
+        assert_eq!(handle(&repo, "GET", "/items?group=unknown", "").status, 400);
+        assert_eq!(handle(&repo, "POST", "/items/1/cancel", "").status, 404);
+        assert_eq!(handle(&repo, "PUT", "/items", "").status, 405);
+        assert_eq!(handle(&repo, "GET", "/unknown", "").status, 404);
+        assert_eq!(
+            handle(&repo, "DELETE", "/items?group=planned", "").status,
+            400
+        );
+    }
+
+    #[test]
+    fn it_should_close_items_as_far_as_the_workflow_allows_it() {
+        let state = |name: &str, to: &[&str]| State {
+            name: name.to_owned(),
+            to: to.iter().map(|&name| name.to_owned()).collect(),
+        };
+        let workflow = Workflow::new(vec![
+            state("planned", &["review", "canceled"]),
+            state("review", &["finished"]),
+            state("finished", &[]),
+            state("canceled", &[]),
+        ]);
+        let repo = with_workflow(workflow.unwrap());
+
+        let body = r#"{"summary":"Test","deadline":"2023-06-17T23:20:00"}"#;
+        let reply = handle(&repo, "POST", "/items", body);
+        let id = serde_json::from_str::<plan::Response>(&reply.body.unwrap())
+            .unwrap()
+            .id;
+
+        let finish = format!("/items/{id}/finish");
+        assert_eq!(handle(&repo, "POST", &finish, "").status, 409);
+
+        let moved = repo.transaction(["planned", "review"], |[planned, review], _| {
+            planned.remove(id).map(|item| review.add(item).is_ok())
+        });
+        assert!(matches!(moved, Ok(Ok(true))));
+
+        let tags = format!("/items/{id}/tags");
+        assert_eq!(
+            handle(&repo, "PATCH", &tags, r#"{"add":["a"]}"#).status,
+            204
+        );
+        let priority = format!("/items/{id}/priority");
+        assert_eq!(
+            handle(&repo, "PATCH", &priority, r#"{"priority":2}"#).status,
+            204
+        );
+        let edited = repo.read(["review"], |[review], _| {
+            review
+                .get(id)
+                .is_ok_and(|item| item.tags().contains("a") && item.priority().value() == 2)
+        });
+        assert!(matches!(edited, Ok(true)));
+
+        assert_eq!(handle(&repo, "POST", &finish, "").status, 204);
+        assert_eq!(repo.locate(id).as_deref(), Some("finished"));
+    }
+
+    #[test]
+    fn it_should_clean_items_of_every_final_state() {
+        let state = |name: &str, to: &[&str]| State {
+            name: name.to_owned(),
+            to: to.iter().map(|&name| name.to_owned()).collect(),
+        };
+        let workflow = Workflow::new(vec![
+            state("planned", &["finished", "canceled", "dropped"]),
+            state("finished", &[]),
+            state("canceled", &[]),
+            state("dropped", &[]),
+        ]);
+        let repo = with_workflow(workflow.unwrap());
+
+        let body = r#"{"summary":"Test","deadline":"2023-06-17T23:20:00"}"#;
+        let reply = handle(&repo, "POST", "/items", body);
+        let id = serde_json::from_str::<plan::Response>(&reply.body.unwrap())
+            .unwrap()
+            .id;
+        let moved = repo.transaction(["planned", "dropped"], |[planned, dropped], _| {
+            planned.remove(id).map(|item| dropped.add(item).is_ok())
+        });
+        assert!(matches!(moved, Ok(Ok(true))));
+
+        assert_eq!(
+            handle(&repo, "DELETE", "/items?group=unknown", "").status,
+            400
+        );
+        assert_eq!(handle(&repo, "DELETE", "/items", "").status, 204);
+        assert_eq!(repo.locate(id), None);
+    }
+
+    #[test]
+    fn it_should_decode_query_components() {
+        assert_eq!(decode("a+b%20c%2"), "a b c%2");
+        assert_eq!(decode("%E6%97%A5"), "日");
+    }
+
+    fn repository() -> Repository {
+        with_workflow(Workflow::default())
+    }
+
+    fn with_workflow(workflow: Workflow) -> Repository {
+        let data = Data::new(workflow, Box::new(TriePool::new()), |_| {
+            Ok::<_, Infallible>(Box::new(MemoryPool::new()))
+        });
+
+        Repository::new(data.unwrap())
+    }
+}
@@ -0,0 +1,923 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::{Error as IoError, ErrorKind};
+use std::mem;
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+
+use crate::domain::entity::{Group, Item, Workflow};
+use crate::domain::usecase::add_id::{self, Request as AddIdRequest};
+use crate::domain::usecase::import::Entry;
+use crate::domain::usecase::plan::{self, PlanError, Request};
+use crate::format::ics;
+use crate::repository::id::Pool as IdPool;
+use crate::repository::item::{AddError, Pool as ItemPool};
+
+const PROPFIND: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:getetag/></d:prop></d:propfind>"#;
+
+#[derive(Debug, Snafu)]
+pub enum CaldavError {
+    #[snafu(display("Failed to read {path}: {source}"))]
+    Load { path: String, source: IoError },
+    #[snafu(display("{path} is invalid: {source}"))]
+    Corrupt {
+        path: String,
+        source: serde_json::Error,
+    },
+    #[snafu(display("Failed to write {path}: {source}"))]
+    Save { path: String, source: IoError },
+    #[snafu(display("`{method} {url}` failed: {reason}"))]
+    Request {
+        method: String,
+        url: String,
+        reason: String,
+    },
+    #[snafu(display("{url} isn't a valid calendar: {reason}"))]
+    Invalid { url: String, reason: String },
+}
+
+/// Which side wins when an item was changed both locally and on the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Local,
+    Remote,
+}
+
+/// Where each synced item lives on the server, and what both sides looked like after
+/// the last sync.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Links {
+    pub url: String,
+    pub entries: Vec<Link>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Link {
+    pub id: u64,
+    pub href: String,
+    pub etag: String,
+    /// A hash of the item as rendered, which tells whether it changed locally.
+    pub fingerprint: u64,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub pushed: usize,
+    pub pulled: usize,
+    pub deleted: usize,
+    /// Items left alone because both sides changed or the server refused a write.
+    pub conflicts: Vec<String>,
+}
+
+impl Links {
+    /// Reads the mapping at `path`, where a missing file means nothing was synced yet.
+    pub fn load(path: &Path) -> Result<Self, CaldavError> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).context(CorruptSnafu {
+                path: path.display().to_string(),
+            }),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err).context(LoadSnafu {
+                path: path.display().to_string(),
+            }),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), CaldavError> {
+        let json = serde_json::to_string_pretty(self).context(CorruptSnafu {
+            path: path.display().to_string(),
+        })?;
+
+        fs::write(path, json + "\n").context(SaveSnafu {
+            path: path.display().to_string(),
+        })
+    }
+}
+
+/// Syncs the items of all states with the VTODO resources of the calendar collection
+/// at `links.url`, one resource per item. VTODOs only tell the built-in groups apart,
+/// so items are rendered as the group their state counts as.
+///
+/// Changes are detected against `links`, which is updated in place: items changed on
+/// one side only are copied to the other, and removals are mirrored. Writes are
+/// conditional on ETags, so an item changed on the server meanwhile is never
+/// overwritten. Items changed on both sides are reported as conflicts unless `prefer`
+/// picks a side. When a request fails, `links` still covers every item, whether it was
+/// synced before or not.
+pub fn sync(
+    workflow: &Workflow,
+    pools: Vec<(&str, &mut dyn ItemPool)>,
+    ids: &mut dyn IdPool,
+    links: &mut Links,
+    prefer: Option<Side>,
+) -> Result<Report, CaldavError> {
+    // Collections are addressed with a trailing slash, which resources are appended to.
+    let client = Client {
+        url: format!("{}/", links.url.trim_end_matches('/')),
+    };
+    let mut local = Local {
+        workflow,
+        pools: pools
+            .into_iter()
+            .map(|(state, pool)| (state, pool as &mut dyn ItemPool))
+            .collect(),
+        ids,
+    };
+    let mut report = Report::default();
+
+    let mut remote = client.list()?;
+    let mut items = local.items();
+
+    // Links are taken out while they're synced one by one. Ones which weren't synced
+    // yet are put back as they were on failure, so the next sync takes over from there.
+    let mut pending = mem::take(&mut links.entries).into_iter();
+
+    while let Some(link) = pending.next() {
+        let mut step = || -> Result<Option<Link>, CaldavError> {
+            let item = items.remove(&link.id);
+            let etag = remote.remove(&link.href);
+
+            let ours = Change::of(
+                item.as_ref()
+                    .map(|(state, item)| fingerprint(workflow.group(state), item)),
+                link.fingerprint,
+            );
+            let theirs = Change::of(etag.clone(), link.etag.clone());
+
+            let winner = match (ours, theirs) {
+                (Change::Same, Change::Same) => return Ok(Some(link.clone())),
+                (Change::Gone, Change::Gone) => return Ok(None),
+                (_, Change::Same) => Side::Local,
+                (Change::Same, _) => Side::Remote,
+                _ => match prefer {
+                    Some(side) => side,
+                    None => {
+                        report
+                            .conflicts
+                            .push(format!("{} changed on both sides", link.href));
+                        return Ok(Some(link.clone()));
+                    }
+                },
+            };
+
+            match (winner, item, etag) {
+                (Side::Local, Some((state, item)), etag) => {
+                    let group = workflow.group(state);
+                    client.push(&link.href, group, &item, etag.as_deref(), &mut report)
+                }
+                (Side::Local, None, Some(etag)) => {
+                    client.delete(&link.href, &etag, &mut report)?;
+                    Ok(None)
+                }
+                (Side::Remote, item, Some(_)) => {
+                    // The local item is only replaced once the remote one could be read.
+                    let (entry, etag) = client.fetch(&link.href)?;
+                    let replaced = item.map(|(state, item)| (state, item.id()));
+                    Ok(local.pull(&link.href, entry, etag, replaced, &mut report))
+                }
+                (Side::Remote, Some((state, item)), None) => {
+                    local.remove(state, item.id());
+                    report.deleted += 1;
+                    Ok(None)
+                }
+                (_, None, None) => Ok(None),
+            }
+        };
+
+        match step() {
+            Ok(kept) => links.entries.extend(kept),
+            Err(err) => {
+                links.entries.push(link);
+                links.entries.extend(pending);
+                return Err(err);
+            }
+        }
+    }
+
+    for (href, _) in remote {
+        let (entry, etag) = client.fetch(&href)?;
+        links
+            .entries
+            .extend(local.adopt(&href, entry, etag, &mut items, &mut report));
+    }
+
+    for (_, (state, item)) in items {
+        let href = format!("{}{}.ics", client.url, item.id());
+        let group = workflow.group(state);
+        links
+            .entries
+            .extend(client.push(&href, group, &item, None, &mut report)?);
+    }
+
+    links.entries.sort_unstable_by_key(|link| link.id);
+    Ok(report)
+}
+
+/// How one side of a linked item compares with the last sync.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Gone,
+    Same,
+    Changed,
+}
+
+impl Change {
+    fn of<T: PartialEq>(current: Option<T>, synced: T) -> Self {
+        match current {
+            None => Self::Gone,
+            Some(current) if current == synced => Self::Same,
+            Some(_) => Self::Changed,
+        }
+    }
+}
+
+fn fingerprint(group: Group, item: &Item) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    render(group, item).hash(&mut hasher);
+    hasher.finish()
+}
+
+fn render(group: Group, item: &Item) -> String {
+    // A fixed stamp keeps the rendering, and thus the fingerprint, stable.
+    ics::render(&[(group, item.clone())], NaiveDateTime::default())
+}
+
+struct Local<'a> {
+    workflow: &'a Workflow,
+    pools: Vec<(&'a str, &'a mut dyn ItemPool)>,
+    ids: &'a mut dyn IdPool,
+}
+
+impl<'a> Local<'a> {
+    /// Every state has a pool, the built-in groups included.
+    fn pool(&mut self, state: &str) -> &mut dyn ItemPool {
+        match self.pools.iter_mut().find(|(name, _)| *name == state) {
+            Some((_, pool)) => &mut **pool,
+            None => unreachable!("State `{state}` has no pool"),
+        }
+    }
+
+    fn items(&self) -> HashMap<u64, (&'a str, Item)> {
+        self.pools
+            .iter()
+            .flat_map(|&(state, ref pool)| {
+                let items = pool
+                    .select(Default::default(), None, None)
+                    .unwrap_or_default();
+                items
+                    .into_iter()
+                    .map(move |item| (item.id(), (state, item)))
+            })
+            .collect()
+    }
+
+    fn remove(&mut self, state: &str, id: u64) -> Option<Item> {
+        let item = self.pool(state).remove(id).ok();
+        self.ids.remove(id);
+        item
+    }
+
+    /// Adds an item to the pool of `state`, the way it's planned, except that only open
+    /// items get their IDs registered.
+    fn add(&mut self, state: &str, request: Request) -> Result<u64, PlanError> {
+        let item = plan::preview(request)?;
+        let id = item.id();
+
+        if let Err(AddError::Conflict) = self.pool(state).add(item) {
+            return Err(PlanError::Conflict);
+        }
+
+        if !self.workflow.is_final(state) {
+            let _ = add_id::execute(&mut *self.ids, AddIdRequest { id });
+        }
+
+        Ok(id)
+    }
+
+    /// Replaces the item `replaced`, if any, with `entry` read from `href`. The estimate
+    /// isn't part of VTODOs, so the one of the replaced item is kept, and so is its state
+    /// as long as it counts as the group of the VTODO.
+    fn pull(
+        &mut self,
+        href: &str,
+        entry: Entry,
+        etag: String,
+        replaced: Option<(&str, u64)>,
+        report: &mut Report,
+    ) -> Option<Link> {
+        let Entry {
+            group, mut request, ..
+        } = entry;
+        let mut state = group.name();
+
+        let replaced = replaced.and_then(|(name, id)| Some((name, self.remove(name, id)?)));
+
+        if let Some((name, item)) = replaced {
+            request.estimate = item.estimate().map(|estimate| estimate.num_seconds());
+
+            if self.workflow.group(name) == group {
+                state = name;
+            }
+        }
+
+        match self.add(state, request) {
+            Ok(id) => {
+                report.pulled += 1;
+                let item = self.pool(state).get(id).ok()?;
+
+                Some(Link {
+                    id,
+                    href: href.to_owned(),
+                    etag,
+                    fingerprint: fingerprint(group, &item),
+                })
+            }
+            Err(err) => {
+                report
+                    .conflicts
+                    .push(format!("{href} can't be added: {err}"));
+                None
+            }
+        }
+    }
+
+    /// Links an unknown resource to the same local item, or adds it if there's none.
+    fn adopt(
+        &mut self,
+        href: &str,
+        entry: Entry,
+        etag: String,
+        items: &mut HashMap<u64, (&str, Item)>,
+        report: &mut Report,
+    ) -> Option<Link> {
+        let Entry { request, .. } = &entry;
+        let id = items
+            .iter()
+            .find(|(_, (_, item))| {
+                item.summary() == request.summary
+                    && item.content() == request.content
+                    && *item.deadline() == request.deadline
+            })
+            .map(|(&id, _)| id);
+
+        match id.and_then(|id| items.remove(&id)) {
+            Some((state, item)) => Some(Link {
+                id: item.id(),
+                href: href.to_owned(),
+                etag,
+                fingerprint: fingerprint(self.workflow.group(state), &item),
+            }),
+            None => self.pull(href, entry, etag, None, report),
+        }
+    }
+}
+
+struct Client {
+    url: String,
+}
+
+impl Client {
+    /// Lists the ETags of the `.ics` resources in the collection by their URLs.
+    fn list(&self) -> Result<HashMap<String, String>, CaldavError> {
+        let response = ureq::request("PROPFIND", &self.url)
+            .set("Depth", "1")
+            .set("Content-Type", "application/xml; charset=utf-8")
+            .send_string(PROPFIND)
+            .map_err(|err| self.error("PROPFIND", &self.url, err))?;
+
+        let xml = response.into_string().map_err(|err| CaldavError::Invalid {
+            url: self.url.clone(),
+            reason: err.to_string(),
+        })?;
+
+        Ok(elements(&xml, "response")
+            .into_iter()
+            .filter_map(|response| {
+                let href = elements(response, "href").into_iter().next()?;
+                let etag = elements(response, "getetag").into_iter().next()?;
+                let href = self.resolve(&unescape(href.trim()));
+
+                href.ends_with(".ics")
+                    .then(|| (href, unescape(etag.trim())))
+            })
+            .collect())
+    }
+
+    fn get(&self, href: &str) -> Result<(String, String), CaldavError> {
+        let response = ureq::get(href)
+            .call()
+            .map_err(|err| self.error("GET", href, err))?;
+
+        let etag = response.header("ETag").unwrap_or_default().to_owned();
+        let body = response.into_string().map_err(|err| CaldavError::Invalid {
+            url: href.to_owned(),
+            reason: err.to_string(),
+        })?;
+
+        Ok((body, etag))
+    }
+
+    /// Reads the item at `href` along with its ETag.
+    fn fetch(&self, href: &str) -> Result<(Entry, String), CaldavError> {
+        let (body, etag) = self.get(href)?;
+        Ok((self.parse(href, &body)?, etag))
+    }
+
+    fn parse(&self, href: &str, body: &str) -> Result<Entry, CaldavError> {
+        let reason = match ics::parse(body, None) {
+            Ok(mut entries) if entries.len() == 1 => return Ok(entries.remove(0)),
+            Ok(entries) => format!("{} VTODOs instead of one", entries.len()),
+            Err(err) => err.to_string(),
+        };
+
+        Err(CaldavError::Invalid {
+            url: href.to_owned(),
+            reason,
+        })
+    }
+
+    /// Writes `item` to `href`, either over the version tagged `etag` or as a new
+    /// resource. A refused precondition means the server side changed meanwhile.
+    fn push(
+        &self,
+        href: &str,
+        group: Group,
+        item: &Item,
+        etag: Option<&str>,
+        report: &mut Report,
+    ) -> Result<Option<Link>, CaldavError> {
+        let body = render(group, item);
+        let request = ureq::put(href).set("Content-Type", "text/calendar; charset=utf-8");
+        let request = match etag {
+            Some(etag) => request.set("If-Match", etag),
+            None => request.set("If-None-Match", "*"),
+        };
+
+        let etag = match request.send_string(&body) {
+            Ok(response) => response.header("ETag").map(str::to_owned),
+            Err(ureq::Error::Status(412, _)) => {
+                report
+                    .conflicts
+                    .push(format!("{href} changed on the server meanwhile"));
+                return Ok(None);
+            }
+            Err(err) => return Err(self.error("PUT", href, err)),
+        };
+
+        let etag = match etag {
+            Some(etag) => etag,
+            None => self.get(href)?.1,
+        };
+
+        report.pushed += 1;
+        Ok(Some(Link {
+            id: item.id(),
+            href: href.to_owned(),
+            etag,
+            fingerprint: fingerprint(group, item),
+        }))
+    }
+
+    fn delete(&self, href: &str, etag: &str, report: &mut Report) -> Result<(), CaldavError> {
+        match ureq::delete(href).set("If-Match", etag).call() {
+            Ok(_) | Err(ureq::Error::Status(404, _)) => report.deleted += 1,
+            Err(ureq::Error::Status(412, _)) => report
+                .conflicts
+                .push(format!("{href} changed on the server meanwhile")),
+            Err(err) => return Err(self.error("DELETE", href, err)),
+        }
+
+        Ok(())
+    }
+
+    /// Turns an href into a URL, as servers usually answer with absolute paths.
+    fn resolve(&self, href: &str) -> String {
+        if href.contains("://") {
+            return href.to_owned();
+        }
+
+        let (scheme, rest) = self.url.split_once("://").unwrap_or(("", &self.url));
+        match href.strip_prefix('/') {
+            Some(path) => {
+                let host = rest.split('/').next().unwrap_or(rest);
+                format!("{scheme}://{host}/{path}")
+            }
+            None => format!("{}{href}", self.url),
+        }
+    }
+
+    fn error(&self, method: &str, url: &str, err: ureq::Error) -> CaldavError {
+        let reason = match err {
+            ureq::Error::Status(code, response) => {
+                format!("{code} {}", response.status_text())
+            }
+            ureq::Error::Transport(transport) => transport.to_string(),
+        };
+
+        CaldavError::Request {
+            method: method.to_owned(),
+            url: url.to_owned(),
+            reason,
+        }
+    }
+}
+
+/// Finds the contents of the elements named `name` whatever their namespace prefix,
+/// which is all that's needed from a multistatus answer.
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut res = Vec::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let end = rest.find('>').unwrap_or(rest.len());
+        let tag = &rest[..end];
+        let tag_name = tag.split([' ', '\t', '\r', '\n', '/']).next().unwrap_or("");
+        let local = tag_name.rsplit(':').next().unwrap_or(tag_name);
+
+        if local != name || tag.starts_with('/') || tag.ends_with('/') {
+            continue;
+        }
+
+        rest = &rest[(end + 1).min(rest.len())..];
+        let close = format!("</{tag_name}>");
+        match rest.find(&close) {
+            Some(close_at) => {
+                res.push(&rest[..close_at]);
+                rest = &rest[close_at + close.len()..];
+            }
+            None => break,
+        }
+    }
+
+    res
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    use tiny_http::{Header, Response, Server};
+
+    use crate::domain::entity::State;
+    use crate::repository::id::TriePool;
+    use crate::repository::item::MemoryPool;
+
+    use super::*;
+
+    /// Resources by path, with a version used as the ETag.
+    type Store = Arc<Mutex<HashMap<String, (String, u32)>>>;
+
+    /// A tiny stand-in for a CalDAV server, serving a single collection at `/cal/`.
+    struct Stub {
+        server: Arc<Server>,
+        store: Store,
+        url: String,
+    }
+
+    impl Stub {
+        fn start() -> Self {
+            let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+            let url = format!("http://{}/cal/", server.server_addr());
+            let store = Store::default();
+
+            let (inner, resources) = (server.clone(), store.clone());
+            thread::spawn(move || {
+                while let Ok(mut request) = inner.recv() {
+                    let mut body = String::new();
+                    let _ = request.as_reader().read_to_string(&mut body);
+
+                    let header = |name: &'static str| {
+                        request
+                            .headers()
+                            .iter()
+                            .find(|header| header.field.equiv(name))
+                            .map(|header| header.value.to_string())
+                    };
+                    let (if_match, if_none_match) = (header("If-Match"), header("If-None-Match"));
+                    let path = request.url().to_owned();
+                    let method = request.method().to_string();
+
+                    let mut store = resources.lock().unwrap();
+                    let current = store
+                        .get(&path)
+                        .map(|(_, version)| format!("\"{version}\""));
+                    let refused = if_match.is_some_and(|etag| current.as_ref() != Some(&etag))
+                        || (if_none_match.is_some() && current.is_some());
+
+                    let response = match method.as_str() {
+                        _ if refused => Response::from_string("").with_status_code(412),
+                        "PROPFIND" => {
+                            let responses = store
+                                .iter()
+                                .map(|(path, (_, version))| {
+                                    format!(
+                                        "<D:response><D:href>{path}</D:href><D:propstat><D:prop>\
+                                         <D:getetag>&quot;{version}&quot;</D:getetag>\
+                                         </D:prop></D:propstat></D:response>"
+                                    )
+                                })
+                                .collect::<String>();
+                            Response::from_string(format!(
+                                "<?xml version=\"1.0\"?><D:multistatus xmlns:D=\"DAV:\">\
+                                 <D:response><D:href>/cal/</D:href></D:response>\
+                                 {responses}</D:multistatus>"
+                            ))
+                            .with_status_code(207)
+                        }
+                        "GET" => match store.get(&path) {
+                            Some((body, _)) => Response::from_string(body.clone())
+                                .with_header(etag(current.as_deref().unwrap())),
+                            None => Response::from_string("").with_status_code(404),
+                        },
+                        "PUT" => {
+                            let version = store.values().map(|(_, v)| *v).max().unwrap_or(0) + 1;
+                            store.insert(path, (body, version));
+                            Response::from_string("")
+                                .with_status_code(201)
+                                .with_header(etag(&format!("\"{version}\"")))
+                        }
+                        "DELETE" => match store.remove(&path) {
+                            Some(_) => Response::from_string("").with_status_code(204),
+                            None => Response::from_string("").with_status_code(404),
+                        },
+                        _ => Response::from_string("").with_status_code(405),
+                    };
+
+                    drop(store);
+                    let _ = request.respond(response);
+                }
+            });
+
+            Self { server, store, url }
+        }
+
+        fn put(&self, path: &str, body: String) {
+            let mut store = self.store.lock().unwrap();
+            let version = store.values().map(|(_, v)| *v).max().unwrap_or(0) + 1;
+            store.insert(path.to_owned(), (body, version));
+        }
+    }
+
+    impl Drop for Stub {
+        fn drop(&mut self) {
+            self.server.unblock();
+        }
+    }
+
+    fn etag(value: &str) -> Header {
+        Header::from_bytes("ETag", value).unwrap()
+    }
+
+    /// The pools of a workflow where items are worked on in between.
+    struct Pools {
+        workflow: Workflow,
+        planned: MemoryPool,
+        doing: MemoryPool,
+        finished: MemoryPool,
+        canceled: MemoryPool,
+        ids: TriePool,
+    }
+
+    impl Pools {
+        fn new() -> Self {
+            let state = |name: &str, to: &[&str]| State {
+                name: name.to_owned(),
+                to: to.iter().map(|&name| name.to_owned()).collect(),
+            };
+            let workflow = Workflow::new(vec![
+                state("planned", &["doing", "canceled"]),
+                state("doing", &["finished"]),
+                state("finished", &[]),
+                state("canceled", &[]),
+            ]);
+
+            Self {
+                workflow: workflow.unwrap(),
+                planned: MemoryPool::new(),
+                doing: MemoryPool::new(),
+                finished: MemoryPool::new(),
+                canceled: MemoryPool::new(),
+                ids: TriePool::new(),
+            }
+        }
+
+        fn sync(&mut self, links: &mut Links, prefer: Option<Side>) -> Report {
+            self.try_sync(links, prefer).unwrap()
+        }
+
+        fn try_sync(
+            &mut self,
+            links: &mut Links,
+            prefer: Option<Side>,
+        ) -> Result<Report, CaldavError> {
+            let pools = vec![
+                ("planned", &mut self.planned as &mut dyn ItemPool),
+                ("doing", &mut self.doing),
+                ("finished", &mut self.finished),
+                ("canceled", &mut self.canceled),
+            ];
+
+            sync(&self.workflow, pools, &mut self.ids, links, prefer)
+        }
+
+        fn summaries(&self) -> Vec<String> {
+            let mut summaries = self
+                .planned
+                .select(Default::default(), None, None)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|item| item.summary().to_owned())
+                .collect::<Vec<_>>();
+            summaries.sort();
+            summaries
+        }
+    }
+
+    fn item(summary: &str) -> Item {
+        let deadline =
+            NaiveDateTime::parse_from_str("2023-06-18 12:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        Item::new(
+            summary,
+            "",
+            deadline,
+            Default::default(),
+            0.try_into().unwrap(),
+        )
+    }
+
+    #[test]
+    fn it_should_push_pull_and_mirror_removals() {
+        let stub = Stub::start();
+        let mut pools = Pools::new();
+        let mut links = Links {
+            url: stub.url.clone(),
+            entries: Vec::new(),
+        };
+
+        let local = item("Local");
+        let _ = pools.planned.add(local.clone());
+        stub.put(
+            "/cal/remote.ics",
+            ics::render(
+                &[(Group::Planned, item("Remote"))],
+                NaiveDateTime::default(),
+            ),
+        );
+
+        let report = pools.sync(&mut links, None);
+        assert_eq!((report.pushed, report.pulled), (1, 1));
+        assert_eq!(pools.summaries(), ["Local", "Remote"]);
+        assert_eq!(links.entries.len(), 2);
+        assert!(stub
+            .store
+            .lock()
+            .unwrap()
+            .contains_key(&format!("/cal/{}.ics", local.id())));
+
+        assert_eq!(pools.sync(&mut links, None), Report::default());
+
+        let _ = pools.planned.remove(local.id());
+        let _ = pools.finished.add(local.clone());
+        stub.store.lock().unwrap().remove("/cal/remote.ics");
+
+        let report = pools.sync(&mut links, None);
+        assert_eq!((report.pushed, report.deleted), (1, 1));
+        assert!(pools.summaries().is_empty());
+        let store = stub.store.lock().unwrap();
+        let (body, _) = &store[&format!("/cal/{}.ics", local.id())];
+        assert!(body.contains("STATUS:COMPLETED"));
+    }
+
+    #[test]
+    fn it_should_report_conflicts_unless_a_side_is_preferred() {
+        let stub = Stub::start();
+        let mut pools = Pools::new();
+        let mut links = Links {
+            url: stub.url.clone(),
+            entries: Vec::new(),
+        };
+
+        let local = item("Shared");
+        let _ = pools.planned.add(local.clone());
+        pools.sync(&mut links, None);
+
+        let path = format!("/cal/{}.ics", local.id());
+        let mut tags = crate::domain::entity::TagSet::new();
+        tags.insert("local".to_owned());
+        let _ = pools.planned.add_tag(local.id(), tags);
+        stub.put(
+            &path,
+            ics::render(
+                &[(Group::Planned, item("Renamed"))],
+                NaiveDateTime::default(),
+            ),
+        );
+
+        let report = pools.sync(&mut links, None);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(pools.summaries(), ["Shared"]);
+
+        let report = pools.sync(&mut links, Some(Side::Remote));
+        assert_eq!((report.pulled, report.conflicts.len()), (1, 0));
+        assert_eq!(pools.summaries(), ["Renamed"]);
+        assert_eq!(
+            links.entries[0].href,
+            format!("{}{}.ics", stub.url, local.id())
+        );
+    }
+
+    #[test]
+    fn it_should_keep_items_in_other_open_states() {
+        let stub = Stub::start();
+        let mut pools = Pools::new();
+        let mut links = Links {
+            url: stub.url.clone(),
+            entries: Vec::new(),
+        };
+
+        let local = item("Shared");
+        let _ = pools.planned.add(local.clone());
+        pools.sync(&mut links, None);
+
+        let _ = pools.planned.remove(local.id());
+        let _ = pools.doing.add(local.clone());
+        assert_eq!(pools.sync(&mut links, None), Report::default());
+        assert_eq!(stub.store.lock().unwrap().len(), 1);
+
+        let path = format!("/cal/{}.ics", local.id());
+        stub.put(
+            &path,
+            ics::render(
+                &[(Group::Planned, item("Renamed"))],
+                NaiveDateTime::default(),
+            ),
+        );
+
+        let report = pools.sync(&mut links, Some(Side::Remote));
+        assert_eq!(report.pulled, 1);
+        assert!(pools.summaries().is_empty());
+        let doing = pools.doing.select(Default::default(), None, None);
+        let doing = doing.unwrap_or_default();
+        assert_eq!(doing.len(), 1);
+        assert_eq!(doing[0].summary(), "Renamed");
+    }
+
+    #[test]
+    fn it_should_keep_links_and_items_when_the_server_fails_partway() {
+        let stub = Stub::start();
+        let mut pools = Pools::new();
+        let mut links = Links {
+            url: stub.url.clone(),
+            entries: Vec::new(),
+        };
+
+        let (first, second) = (item("First"), item("Second"));
+        let _ = pools.planned.add(first.clone());
+        let _ = pools.planned.add(second.clone());
+        pools.sync(&mut links, None);
+
+        // Both changed on the server, but one of them can no longer be read.
+        stub.put(
+            &format!("/cal/{}.ics", first.id()),
+            ics::render(
+                &[(Group::Planned, item("Renamed"))],
+                NaiveDateTime::default(),
+            ),
+        );
+        stub.put(&format!("/cal/{}.ics", second.id()), "broken".to_owned());
+
+        let res = pools.try_sync(&mut links, None);
+        assert!(matches!(res, Err(CaldavError::Invalid { .. })));
+        assert_eq!(links.entries.len(), 2);
+        assert!(pools.summaries().contains(&"Second".to_owned()));
+        assert_eq!(pools.summaries().len(), 2);
+    }
+
+    #[test]
+    fn it_should_find_elements_whatever_their_prefix() {
+        let xml = "<d:multistatus xmlns:d=\"DAV:\"><d:response><d:href>/a.ics</d:href>\
+                   </d:response><response><href>/b.ics</href><getetag/></response>\
+                   </d:multistatus>";
+
+        let responses = elements(xml, "response");
+        assert_eq!(responses.len(), 2);
+        assert_eq!(elements(responses[1], "href"), ["/b.ics"]);
+        assert!(elements(responses[1], "getetag").is_empty());
+    }
+}
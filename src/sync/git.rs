@@ -0,0 +1,134 @@
+use std::fs;
+use std::io::Error as IoError;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use snafu::prelude::*;
+
+use crate::repository::cipher::Cipher;
+use crate::repository::item::merge::{self, MergeError};
+
+/// Files which only make sense on the machine they're on.
+const IGNORED: &str = "history\nworkspace\n";
+
+#[derive(Debug, Snafu)]
+pub enum GitError {
+    #[snafu(display("Failed to run git: {source}"))]
+    Spawn { source: IoError },
+    #[snafu(display("`git {args}` failed: {stderr}"))]
+    Status { args: String, stderr: String },
+    #[snafu(display("{} isn't in a git repository, run `git init` there first", dir.display()))]
+    NotRepository { dir: PathBuf },
+    #[snafu(display("Can't merge {path} automatically, the merge is aborted: {source}"))]
+    Unmergeable { path: String, source: MergeError },
+    #[snafu(display("Failed to write {path}: {source}"))]
+    Write { path: String, source: IoError },
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub committed: bool,
+    /// Whether there's an upstream branch to pull from and push to.
+    pub tracked: bool,
+    /// Pool files whose conflicts were merged item by item.
+    pub merged: Vec<String>,
+}
+
+/// Commits all changes in `dir` with the local `git` binary, then pulls and pushes if
+/// the branch has an upstream. Conflicting pool files are merged item by item, see
+/// [`merge::merge`], while any other conflict aborts the merge. Encrypted pool files
+/// are merged with `cipher`, so only ones sharing the passphrase of the workspace in use
+/// can be. A `.gitignore` for machine-specific files is written if there's none.
+pub fn sync(dir: &Path, cipher: Option<&Cipher>, message: &str) -> Result<Report, GitError> {
+    let git = Git { dir };
+    let mut report = Report::default();
+
+    ensure!(
+        git.run(&["rev-parse", "--is-inside-work-tree"]).is_ok(),
+        NotRepositorySnafu { dir }
+    );
+
+    let ignore = dir.join(".gitignore");
+    if !ignore.exists() {
+        fs::write(&ignore, IGNORED).context(WriteSnafu { path: ".gitignore" })?;
+    }
+
+    git.run(&["add", "--all", "."])?;
+    if git.run(&["diff", "--cached", "--quiet"]).is_err() {
+        git.run(&["commit", "--quiet", "--message", message])?;
+        report.committed = true;
+    }
+
+    let upstream = ["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"];
+    if git.run(&upstream).is_err() {
+        return Ok(report);
+    }
+    report.tracked = true;
+
+    if let Err(err) = git.run(&["pull", "--quiet", "--no-rebase", "--no-edit"]) {
+        let conflicts = git.run(&["diff", "--name-only", "--diff-filter=U", "--relative"])?;
+        let conflicts = String::from_utf8_lossy(&conflicts.stdout)
+            .lines()
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+
+        if conflicts.is_empty() {
+            return Err(err);
+        }
+
+        for path in conflicts {
+            if let Err(err) = resolve(&git, &path, cipher) {
+                let _ = git.run(&["merge", "--abort"]);
+                return Err(err);
+            }
+
+            report.merged.push(path);
+        }
+
+        git.run(&["commit", "--quiet", "--no-edit"])?;
+    }
+
+    git.run(&["push", "--quiet"])?;
+    Ok(report)
+}
+
+fn resolve(git: &Git, path: &str, cipher: Option<&Cipher>) -> Result<(), GitError> {
+    // A missing base means the file was added on both sides.
+    let stage = |n| {
+        git.run(&["show", &format!(":{n}:./{path}")])
+            .map(|output| output.stdout)
+    };
+
+    let base = stage(1).unwrap_or_default();
+    let merged = merge::merge_files(&base, &stage(2)?, &stage(3)?, cipher)
+        .context(UnmergeableSnafu { path })?;
+
+    fs::write(git.dir.join(path), merged).context(WriteSnafu { path })?;
+    git.run(&["add", "--", path])?;
+    Ok(())
+}
+
+struct Git<'a> {
+    dir: &'a Path,
+}
+
+impl Git<'_> {
+    fn run(&self, args: &[&str]) -> Result<Output, GitError> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(self.dir)
+            .args(args)
+            .output()
+            .context(SpawnSnafu)?;
+
+        ensure!(
+            output.status.success(),
+            StatusSnafu {
+                args: args.join(" "),
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            }
+        );
+
+        Ok(output)
+    }
+}
@@ -0,0 +1,5 @@
+mod caldav;
+mod git;
+
+pub use caldav::{sync as caldav, CaldavError, Link, Links, Report as CaldavReport, Side};
+pub use git::{sync as git, GitError, Report};
@@ -0,0 +1,454 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::Local;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::widgets::TableState;
+
+use crate::domain::entity::{Group, Item, TagSet};
+use crate::domain::usecase::add_tag::{self, Request as AddTagRequest};
+use crate::domain::usecase::edit::{self, Request as EditRequest};
+use crate::domain::usecase::plan::{self, Request as PlanRequest};
+use crate::domain::usecase::remove_tag::{self, Request as RemoveTagRequest};
+use crate::domain::usecase::select::{self, Request as SelectRequest, Response, SelectItemError};
+use crate::domain::usecase::set_priority::{self, Request as SetPriorityRequest};
+use crate::domain::usecase::shift::{self, Request as ShiftRequest, ShiftError};
+use crate::format::inline::{self, Inline};
+use crate::format::sorted_tags;
+use crate::repository::id::Pool as IdPool;
+use crate::repository::item::Pool;
+use crate::repository::timer::{LocalPool as TimerPool, Pool as _};
+use crate::repository::{self, Repository, TransactionError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prompt {
+    Add,
+    Edit(u64),
+    Tag(u64),
+    Filter,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    Info(String),
+    Error(String),
+}
+
+pub struct App {
+    repo: Arc<Repository>,
+    storage: PathBuf,
+    pub group: Group,
+    pub items: Vec<Item>,
+    pub state: TableState,
+    pub filter: String,
+    pub prompt: Option<Prompt>,
+    pub input: String,
+    pub message: Option<Message>,
+    pub quit: bool,
+}
+
+impl App {
+    pub fn new(repo: Arc<Repository>, storage: PathBuf) -> Self {
+        let mut app = Self {
+            repo,
+            storage,
+            group: Group::Planned,
+            items: Vec::new(),
+            state: TableState::default(),
+            filter: String::new(),
+            prompt: None,
+            input: String::new(),
+            message: None,
+            quit: false,
+        };
+
+        app.reload();
+        app
+    }
+
+    pub fn selected(&self) -> Option<&Item> {
+        self.state
+            .selected()
+            .and_then(|index| self.items.get(index))
+    }
+
+    pub fn handle(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+        } else if let Some(prompt) = self.prompt {
+            self.handle_input(prompt, key.code);
+        } else {
+            self.message = None;
+            self.handle_normal(key.code);
+        }
+    }
+
+    fn handle_normal(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => self.switch(1),
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => {
+                self.switch(Group::ALL.len() - 1)
+            }
+            KeyCode::Char('1') => self.switch_to(Group::Planned),
+            KeyCode::Char('2') => self.switch_to(Group::Finished),
+            KeyCode::Char('3') => self.switch_to(Group::Canceled),
+            KeyCode::Down | KeyCode::Char('j') => self.state.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.state.select_previous(),
+            KeyCode::Home | KeyCode::Char('g') => self.state.select_first(),
+            KeyCode::End | KeyCode::Char('G') => self.state.select_last(),
+            KeyCode::Char('/') => self.open(Prompt::Filter, self.filter.clone()),
+            KeyCode::Char('a') => self.open(Prompt::Add, String::new()),
+            KeyCode::Char(key) if self.group == Group::Planned => self.handle_planned(key),
+            _ => {}
+        }
+    }
+
+    fn handle_planned(&mut self, key: char) {
+        let Some(item) = self.selected().cloned() else {
+            return;
+        };
+
+        match key {
+            'f' => self.close(item.id(), Group::Finished),
+            'c' => self.close(item.id(), Group::Canceled),
+            '+' | '=' => self.bump(&item, true),
+            '-' => self.bump(&item, false),
+            't' => self.open(Prompt::Tag(item.id()), String::new()),
+            'e' => {
+                let text = inline::render(
+                    item.summary(),
+                    &sorted_tags(&item),
+                    item.priority().value(),
+                    item.deadline(),
+                );
+                self.open(Prompt::Edit(item.id()), text);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_input(&mut self, prompt: Prompt, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                self.prompt = None;
+
+                if prompt == Prompt::Filter {
+                    self.filter.clear();
+                    self.reload();
+                }
+            }
+            KeyCode::Enter => {
+                self.prompt = None;
+                let input = std::mem::take(&mut self.input);
+                self.submit(prompt, input);
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.update(prompt);
+            }
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                self.update(prompt);
+            }
+            _ => {}
+        }
+    }
+
+    fn open(&mut self, prompt: Prompt, input: String) {
+        self.prompt = Some(prompt);
+        self.input = input;
+    }
+
+    /// Filtering is applied while typing.
+    fn update(&mut self, prompt: Prompt) {
+        if prompt == Prompt::Filter {
+            self.filter = self.input.clone();
+            self.reload();
+        }
+    }
+
+    fn submit(&mut self, prompt: Prompt, input: String) {
+        match prompt {
+            Prompt::Add => self.add(&input),
+            Prompt::Edit(id) => self.edit(id, &input),
+            Prompt::Tag(id) => self.retag(id, &input),
+            Prompt::Filter => {}
+        }
+
+        self.reload();
+    }
+
+    fn switch(&mut self, step: usize) {
+        let index = Group::ALL.iter().position(|&g| g == self.group).unwrap();
+        self.switch_to(Group::ALL[(index + step) % Group::ALL.len()]);
+    }
+
+    fn switch_to(&mut self, group: Group) {
+        self.group = group;
+        self.state = TableState::default();
+        self.reload();
+    }
+
+    pub fn reload(&mut self) {
+        let tags = self
+            .filter
+            .split_whitespace()
+            .map(|tag| tag.trim_start_matches('#').to_owned())
+            .filter(|tag| !tag.is_empty())
+            .collect::<TagSet>();
+
+        let workflow = self.repo.workflow();
+        let group = self.group;
+
+        // The tab of a built-in group shows the items of every state which counts towards
+        // it.
+        let response = self.repo.read_all(|pools, _| {
+            let mut items = Vec::new();
+
+            for (_, pool) in pools
+                .into_iter()
+                .filter(|(name, _)| workflow.group(name) == group)
+            {
+                let request = SelectRequest {
+                    tags: tags.clone(),
+                    before: None,
+                    after: None,
+                };
+
+                match select::execute(pool, request) {
+                    Ok(Response { items: found }) => items.extend(found),
+                    Err(SelectItemError::NotFound) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+
+            items.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            Ok(items)
+        });
+
+        self.items = response.unwrap_or_else(|err| {
+            self.message = Some(Message::Error(err.to_string()));
+            Vec::new()
+        });
+
+        match self.state.selected() {
+            _ if self.items.is_empty() => self.state.select(None),
+            Some(index) if index >= self.items.len() => {
+                self.state.select(Some(self.items.len() - 1))
+            }
+            None => self.state.select(Some(0)),
+            Some(_) => {}
+        }
+    }
+
+    fn close(&mut self, id: u64, group: Group) {
+        self.message = Some(match self.shift(id, group.name()) {
+            Ok(()) => Message::Info(format!("Mark {id} as {group}")),
+            Err(err) => Message::Error(err),
+        });
+
+        self.reload();
+    }
+
+    /// Moves an item from whichever state it's in to `to`, as far as the workflow allows
+    /// it. The item is located in the same transaction.
+    fn shift(&self, id: u64, to: &str) -> Result<(), String> {
+        let workflow = self.repo.workflow();
+        let time = Local::now().naive_local();
+
+        let response = self.repo.transaction_all(|pools, ids| {
+            let (mut destination, sources): (Vec<_>, Vec<_>) =
+                pools.into_iter().partition(|(name, _)| *name == to);
+            let Some((_, destination)) = destination.pop() else {
+                unreachable!("built-in groups are states of every workflow");
+            };
+            if destination.get(id).is_ok() {
+                return Err(ShiftError::Already { state: to.into() });
+            }
+            let located = sources.into_iter().find(|(_, pool)| pool.get(id).is_ok());
+            let Some((from, source)) = located else {
+                return Err(ShiftError::NotFound);
+            };
+
+            let request = ShiftRequest { id, from, to, time };
+            shift::execute(&workflow, source, destination, ids, request)
+        });
+        settle(response)
+    }
+
+    fn bump(&mut self, item: &Item, up: bool) {
+        let mut priority = item.priority().clone();
+
+        if up {
+            priority.upgrade();
+        } else {
+            priority.downgrade();
+        }
+
+        let request = SetPriorityRequest {
+            id: item.id(),
+            priority: priority.value(),
+        };
+
+        let response = self.change(item.id(), |pool, _| set_priority::execute(pool, request));
+
+        if let Err(err) = response {
+            self.message = Some(Message::Error(err));
+        }
+
+        self.reload();
+    }
+
+    /// Words prefixed with `-` are removed and any others are added, with an optional
+    /// leading `+` or `#`.
+    fn retag(&mut self, id: u64, input: &str) {
+        let mut added = TagSet::new();
+        let mut removed = TagSet::new();
+
+        for word in input.split_whitespace() {
+            if let Some(tag) = word.strip_prefix('-') {
+                removed.insert(tag.trim_start_matches('#').to_owned());
+            } else {
+                added.insert(word.trim_start_matches(['+', '#']).to_owned());
+            }
+        }
+
+        added.remove("");
+        removed.remove("");
+
+        let response = self.change(id, |pool, _| {
+            let added = if added.is_empty() {
+                Ok(())
+            } else {
+                let request = AddTagRequest { id, tags: added };
+                add_tag::execute(pool, request).map_err(|err| err.to_string())
+            };
+
+            let removed = if removed.is_empty() {
+                Ok(())
+            } else {
+                let request = RemoveTagRequest { id, tags: removed };
+                remove_tag::execute(pool, request).map_err(|err| err.to_string())
+            };
+
+            added.and(removed)
+        });
+
+        if let Err(err) = response {
+            self.message = Some(Message::Error(err));
+        }
+    }
+
+    fn add(&mut self, input: &str) {
+        let request = match self.parse(input, String::new()) {
+            Ok(request) => request,
+            Err(err) => {
+                self.message = Some(Message::Error(err));
+                return;
+            }
+        };
+
+        let response = self
+            .repo
+            .transaction([Group::Planned.name()], |[planned], ids| {
+                plan::execute(planned, ids, request)
+            });
+
+        self.message = Some(match settle(response) {
+            Ok(response) => Message::Info(format!("New item: {}", response.id)),
+            Err(err) => Message::Error(err.to_string()),
+        });
+    }
+
+    fn edit(&mut self, id: u64, input: &str) {
+        let (content, estimate) = self
+            .items
+            .iter()
+            .find(|item| item.id() == id)
+            .map(|item| (item.content().to_owned(), item.estimate()))
+            .unwrap_or_default();
+
+        let item = match self.parse(input, content) {
+            Ok(request) => PlanRequest {
+                estimate: estimate.map(|estimate| estimate.num_seconds()),
+                ..request
+            },
+            Err(err) => {
+                self.message = Some(Message::Error(err));
+                return;
+            }
+        };
+
+        let request = EditRequest { id, item };
+        let response = self
+            .change(id, |pool, ids| edit::execute(pool, ids, request))
+            .and_then(|response| {
+                self.rekey(id, response.id)?;
+                Ok(response)
+            });
+
+        self.message = Some(match response {
+            Ok(response) => Message::Info(format!("Edit {id}, now {}", response.id)),
+            Err(err) => Message::Error(err),
+        });
+    }
+
+    /// Applies `f` to the pool of the open state the item with `id` is in, which is
+    /// located in the same transaction.
+    fn change<F, T, E>(&self, id: u64, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&mut dyn Pool, &mut dyn IdPool) -> Result<T, E>,
+        E: Display,
+    {
+        let workflow = self.repo.workflow();
+        let response = self.repo.transaction_all(|pools, ids| {
+            match repository::locate_open(&workflow, pools, id) {
+                Some((_, pool)) => f(pool, ids).map_err(|err| err.to_string()),
+                None => Err(SelectItemError::NotFound.to_string()),
+            }
+        });
+        settle(response)
+    }
+
+    /// Tracked time follows an item whose ID changed, once the change is saved.
+    fn rekey(&self, from: u64, to: u64) -> Result<(), String> {
+        if from == to {
+            return Ok(());
+        }
+
+        let mut timers =
+            TimerPool::open(self.storage.join("timers.json")).map_err(|err| err.to_string())?;
+        timers.rekey(from, to);
+        timers.sync().map_err(|err| err.to_string())
+    }
+
+    fn parse(&self, input: &str, content: String) -> Result<PlanRequest, String> {
+        let Inline {
+            summary,
+            tags,
+            priority,
+            deadline,
+        } = inline::parse(input, Local::now().naive_local()).map_err(|err| err.to_string())?;
+
+        Ok(PlanRequest {
+            summary,
+            content,
+            deadline: deadline.ok_or("A deadline is required, use `due:` or `@`")?,
+            tags,
+            priority: priority.unwrap_or_default(),
+            estimate: None,
+        })
+    }
+}
+
+/// Both why a transaction failed and why the change inside it did are shown the same.
+fn settle<T, E: Display>(response: Result<Result<T, E>, TransactionError>) -> Result<T, String> {
+    match response {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(err)) => Err(err.to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
@@ -0,0 +1,34 @@
+mod app;
+mod ui;
+
+use std::error::Error;
+use std::path::Path;
+use std::sync::Arc;
+
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+
+use crate::repository::Repository;
+
+use app::App;
+
+pub fn run(repo: Arc<Repository>, storage: &Path) -> Result<(), Box<dyn Error>> {
+    let mut terminal = ratatui::try_init()?;
+    let mut app = App::new(repo, storage.to_owned());
+
+    let res = (|| -> Result<(), Box<dyn Error>> {
+        while !app.quit {
+            terminal.draw(|frame| ui::draw(frame, &mut app))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    app.handle(key);
+                }
+            }
+        }
+
+        Ok(())
+    })();
+
+    ratatui::try_restore()?;
+    res
+}
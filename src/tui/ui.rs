@@ -0,0 +1,156 @@
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, Tabs, Wrap};
+use ratatui::Frame;
+
+use crate::domain::entity::Group;
+use crate::format::sorted_tags;
+
+use super::app::{App, Message, Prompt};
+
+const HELP: &str =
+    "a add  e edit  f finish  c cancel  t tags  +/- priority  / filter  tab switch  q quit";
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [tabs, body, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(0),
+        Constraint::Length(if app.prompt.is_some() { 3 } else { 1 }),
+    ])
+    .areas(frame.area());
+
+    let [list, details] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(body);
+
+    draw_tabs(frame, app, tabs);
+    draw_list(frame, app, list);
+    draw_details(frame, app, details);
+
+    match app.prompt {
+        Some(prompt) => draw_input(frame, app, prompt, footer),
+        None => draw_status(frame, app, footer),
+    }
+}
+
+fn draw_tabs(frame: &mut Frame, app: &App, area: Rect) {
+    let titles = Group::ALL.iter().map(|group| group.to_string());
+    let selected = Group::ALL.iter().position(|&g| g == app.group);
+
+    let title = if app.filter.is_empty() {
+        " todo ".to_owned()
+    } else {
+        format!(" todo [{}] ", app.filter)
+    };
+
+    let tabs = Tabs::new(titles)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .select(selected)
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED));
+
+    frame.render_widget(tabs, area);
+}
+
+fn draw_list(frame: &mut Frame, app: &mut App, area: Rect) {
+    let rows = app.items.iter().map(|item| {
+        let tags = sorted_tags(item)
+            .into_iter()
+            .map(|tag| format!("#{tag}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Row::new(vec![
+            Cell::from(item.deadline().format("%Y-%m-%d %H:%M").to_string()),
+            Cell::from(item.priority().to_string()),
+            Cell::from(item.summary().to_owned()),
+            Cell::from(tags),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(16),
+            Constraint::Length(3),
+            Constraint::Fill(2),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(
+        Row::new(vec!["Deadline", "Pri", "Summary", "Tags"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL))
+    .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    frame.render_stateful_widget(table, area, &mut app.state);
+}
+
+fn draw_details(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).title(" Details ");
+
+    let Some(item) = app.selected() else {
+        frame.render_widget(block, area);
+        return;
+    };
+
+    let field = |name: &'static str, value: String| {
+        Line::from(vec![
+            Span::styled(name, Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(value),
+        ])
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            item.summary().to_owned(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::default(),
+        field("ID:       ", item.id().to_string()),
+        field("Deadline: ", item.deadline().to_string()),
+        field("Priority: ", item.priority().to_string()),
+        field("Tags:     ", sorted_tags(item).join(", ")),
+        Line::default(),
+    ];
+
+    lines.extend(
+        item.content()
+            .lines()
+            .map(|line| Line::from(line.to_owned())),
+    );
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_input(frame: &mut Frame, app: &App, prompt: Prompt, area: Rect) {
+    let title = match prompt {
+        Prompt::Add => " Add: summary #tag !priority due:date ",
+        Prompt::Edit(_) => " Edit: summary #tag !priority due:date ",
+        Prompt::Tag(_) => " Tags: +add -remove ",
+        Prompt::Filter => " Filter by tags ",
+    };
+
+    let input = Paragraph::new(app.input.as_str())
+        .block(Block::default().borders(Borders::ALL).title(title));
+
+    frame.render_widget(input, area);
+    frame.set_cursor_position(Position::new(
+        area.x + 1 + app.input.chars().count() as u16,
+        area.y + 1,
+    ));
+}
+
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let status = match &app.message {
+        Some(Message::Info(text)) => Span::styled(text.as_str(), Style::default().fg(Color::Green)),
+        Some(Message::Error(text)) => Span::styled(text.as_str(), Style::default().fg(Color::Red)),
+        None => Span::styled(HELP, Style::default().fg(Color::DarkGray)),
+    };
+
+    frame.render_widget(Paragraph::new(Line::from(status)), area);
+}